@@ -0,0 +1,107 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use just_a_simple_wayland_screenshot_tool::{
+    CaptureMode, CaptureOptions, OutputFormat, Screenshot, ScreenshotBackend,
+    capture::SyntheticBackend,
+    export::{ExportOptions, Exporter},
+    processing::ImageProcessor,
+};
+use std::hint::black_box;
+
+const SIZES: [(&str, u32, u32); 2] = [("1080p", 1920, 1080), ("4k", 3840, 2160)];
+
+fn frame(width: u32, height: u32) -> Screenshot {
+    Screenshot::new(
+        SyntheticBackend::new(width, height).frame(),
+        CaptureMode::Screen,
+        OutputFormat::Png,
+    )
+}
+
+fn capture(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("capture");
+    group.sample_size(20);
+
+    for (name, width, height) in SIZES {
+        let backend = SyntheticBackend::new(width, height);
+        let options = CaptureOptions::default();
+        group.bench_function(BenchmarkId::new("synthetic", name), |b| {
+            b.iter(|| {
+                runtime
+                    .block_on(backend.capture(CaptureMode::Screen, &options))
+                    .unwrap()
+            })
+        });
+
+        // The portal hands frames over as PNG files.
+        let encoded = Exporter::encode(
+            &frame(width, height),
+            OutputFormat::Png,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+        group.bench_function(BenchmarkId::new("decode_png", name), |b| {
+            b.iter(|| image::load_from_memory(black_box(&encoded)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn processing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("processing");
+    group.sample_size(20);
+
+    for (name, width, height) in SIZES {
+        let screenshot = frame(width, height);
+
+        group.bench_function(BenchmarkId::new("crop", name), |b| {
+            b.iter(|| {
+                ImageProcessor::crop(
+                    black_box(&screenshot),
+                    width / 4,
+                    height / 4,
+                    width / 2,
+                    height / 2,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("blur", name), |b| {
+            b.iter(|| ImageProcessor::blur(black_box(&screenshot), 4.0).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("resize", name), |b| {
+            b.iter(|| {
+                ImageProcessor::resize(black_box(&screenshot), width / 2, height / 2).unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    group.sample_size(10);
+
+    for (name, width, height) in SIZES {
+        let screenshot = frame(width, height);
+        let options = ExportOptions {
+            quality: Some(90),
+            ..Default::default()
+        };
+
+        // Every raster format OutputFormat has; AVIF is not among them yet.
+        for format in [OutputFormat::Png, OutputFormat::Jpeg, OutputFormat::Webp] {
+            let id = BenchmarkId::new(format.extension(), name);
+            group.bench_function(id, |b| {
+                b.iter(|| Exporter::encode(black_box(&screenshot), format, &options).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, capture, processing, encode);
+criterion_main!(benches);
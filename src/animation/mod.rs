@@ -1 +1,110 @@
+use std::f64::consts::PI;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Spring,
+    Bounce,
+}
+
+impl std::str::FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "ease-in" | "cubic-in" => Ok(Self::EaseIn),
+            "ease-out" | "cubic-out" => Ok(Self::EaseOut),
+            "ease-in-out" | "cubic" => Ok(Self::EaseInOut),
+            "spring" => Ok(Self::Spring),
+            "bounce" => Ok(Self::Bounce),
+            _ => Err(format!("Invalid easing: {}", s)),
+        }
+    }
+}
+
+impl Easing {
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            // Damped oscillation that overshoots once and settles by t = 1.
+            Self::Spring => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - (-6.0 * t).exp() * (2.5 * PI * t).cos()
+                }
+            }
+            Self::Bounce => {
+                let (n, d) = (7.5625, 2.75);
+                if t < 1.0 / d {
+                    n * t * t
+                } else if t < 2.0 / d {
+                    let t = t - 1.5 / d;
+                    n * t * t + 0.75
+                } else if t < 2.5 / d {
+                    let t = t - 2.25 / d;
+                    n * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d;
+                    n * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Circle,
+    Pill,
+    Square,
+    RoundedRect,
+}
+
+impl std::str::FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "circle" => Ok(Self::Circle),
+            "pill" => Ok(Self::Pill),
+            "square" => Ok(Self::Square),
+            "rounded-rect" | "rounded" => Ok(Self::RoundedRect),
+            _ => Err(format!("Invalid shape: {}", s)),
+        }
+    }
+}
+
+impl Shape {
+    pub fn corner_radius(self, width: f64, height: f64, rounded: f64) -> f64 {
+        let half = width.min(height) / 2.0;
+
+        match self {
+            Self::Circle | Self::Pill => half,
+            Self::Square => 0.0,
+            Self::RoundedRect => rounded.min(half),
+        }
+    }
+}
+
+pub fn parse_or<T: std::str::FromStr<Err = String>>(value: &str, fallback: T) -> T {
+    value.parse().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        fallback
+    })
+}
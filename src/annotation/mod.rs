@@ -0,0 +1,478 @@
+use crate::{
+    Region, Result, Screenshot, ScreenshotError,
+    processing::{
+        ImageProcessor, TextStyle,
+        beautify::round_corners,
+        color::parse_color,
+        text::{blend_pixel, text_size},
+    },
+};
+use image::{Rgba, RgbaImage, imageops};
+use imageproc::point::Point;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SIDECAR_EXTENSION: &str = "wst.json";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Annotation {
+    Rectangle {
+        region: Region,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_thickness")]
+        thickness: u32,
+    },
+    Arrow {
+        from: (i32, i32),
+        to: (i32, i32),
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_thickness")]
+        thickness: u32,
+    },
+    Text {
+        x: i32,
+        y: i32,
+        text: String,
+        #[serde(default)]
+        style: TextStyle,
+    },
+    Highlight {
+        region: Region,
+        #[serde(default = "default_highlight")]
+        color: String,
+    },
+    Blur {
+        region: Region,
+        #[serde(default = "default_sigma")]
+        sigma: f32,
+    },
+    Pixelate {
+        region: Region,
+        #[serde(default = "default_block_size")]
+        block_size: u32,
+    },
+    Freehand {
+        points: Vec<(i32, i32, f32)>,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_thickness")]
+        thickness: u32,
+    },
+    StepMarker {
+        x: i32,
+        y: i32,
+        number: u32,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_marker_radius")]
+        radius: u32,
+    },
+    Callout {
+        x: i32,
+        y: i32,
+        anchor: (i32, i32),
+        text: String,
+        #[serde(default)]
+        shape: CalloutShape,
+        #[serde(default = "default_callout_fill")]
+        fill: String,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_thickness")]
+        thickness: u32,
+        #[serde(default = "default_callout_style")]
+        style: TextStyle,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalloutShape {
+    #[default]
+    Bubble,
+    Label,
+}
+
+impl std::str::FromStr for CalloutShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bubble" | "speech" => Ok(Self::Bubble),
+            "label" => Ok(Self::Label),
+            _ => Err(format!(
+                "Invalid callout shape: {} (use bubble or label)",
+                s
+            )),
+        }
+    }
+}
+
+fn default_color() -> String {
+    "#ff3b30".to_string()
+}
+
+fn default_highlight() -> String {
+    "#ffeb3b66".to_string()
+}
+
+fn default_thickness() -> u32 {
+    3
+}
+
+fn default_sigma() -> f32 {
+    8.0
+}
+
+fn default_block_size() -> u32 {
+    12
+}
+
+fn default_marker_radius() -> u32 {
+    16
+}
+
+fn default_callout_fill() -> String {
+    "#ffffff".to_string()
+}
+
+pub fn default_callout_style() -> TextStyle {
+    TextStyle {
+        size: 20.0,
+        color: "#1c1c1e".to_string(),
+        ..TextStyle::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationLayer {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<PathBuf>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+impl Default for AnnotationLayer {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            image: None,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+impl AnnotationLayer {
+    pub fn sidecar_path<P: AsRef<Path>>(image: P) -> PathBuf {
+        image.as_ref().with_extension(SIDECAR_EXTENSION)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let layer: Self = serde_json::from_str(&contents)?;
+
+        if layer.version > FORMAT_VERSION {
+            return Err(ScreenshotError::Config(format!(
+                "Annotation file version {} is newer than supported version {}",
+                layer.version, FORMAT_VERSION
+            )));
+        }
+
+        Ok(layer)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    pub fn next_step_number(&self) -> u32 {
+        self.annotations
+            .iter()
+            .filter_map(|annotation| match annotation {
+                Annotation::StepMarker { number, .. } => Some(*number),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    pub fn push(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    pub fn render(&self, screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut result = screenshot.clone();
+        for annotation in &self.annotations {
+            result = annotation.render(&result)?;
+        }
+        Ok(result)
+    }
+}
+
+impl Annotation {
+    pub fn render(&self, screenshot: &Screenshot) -> Result<Screenshot> {
+        match self {
+            Self::Rectangle {
+                region,
+                color,
+                thickness,
+            } => ImageProcessor::outline(screenshot, *region, annotation_color(color)?, *thickness),
+            Self::Arrow {
+                from,
+                to,
+                color,
+                thickness,
+            } => {
+                let mut result = screenshot.clone();
+                draw_arrow(
+                    &mut result.data,
+                    *from,
+                    *to,
+                    annotation_color(color)?,
+                    *thickness,
+                );
+                Ok(result)
+            }
+            Self::Text { x, y, text, style } => {
+                let mut result = screenshot.clone();
+                style.draw(&mut result.data, text, *x, *y)?;
+                Ok(result)
+            }
+            Self::Highlight { region, color } => {
+                let color = annotation_color(color)?;
+                let mut result = screenshot.clone();
+                for (x, y, pixel) in result.data.enumerate_pixels_mut() {
+                    if region.contains(x as i32, y as i32) {
+                        blend_pixel(pixel, color, color[3]);
+                    }
+                }
+                Ok(result)
+            }
+            Self::Blur { region, sigma } => {
+                ImageProcessor::blur_region(screenshot, *region, *sigma)
+            }
+            Self::Pixelate { region, block_size } => {
+                ImageProcessor::pixelate(screenshot, Some(*region), *block_size)
+            }
+            Self::Freehand {
+                points,
+                color,
+                thickness,
+            } => {
+                let color = annotation_color(color)?;
+                let mut result = screenshot.clone();
+                // The third component is stylus pressure, where 0.5 draws at
+                // the nominal thickness.
+                for pair in points.windows(2) {
+                    let ((x0, y0, p0), (x1, y1, p1)) = (pair[0], pair[1]);
+                    let width = (*thickness as f32 * (p0 + p1)).round().max(1.0) as u32;
+                    draw_thick_line(&mut result.data, (x0, y0), (x1, y1), color, width);
+                }
+                if let [(x, y, pressure)] = points[..] {
+                    let width = (*thickness as f32 * pressure * 2.0).round().max(1.0) as u32;
+                    draw_thick_line(&mut result.data, (x, y), (x, y), color, width);
+                }
+                Ok(result)
+            }
+            Self::StepMarker {
+                x,
+                y,
+                number,
+                color,
+                radius,
+            } => ImageProcessor::add_step_marker(
+                screenshot,
+                (*x, *y),
+                *number,
+                *radius,
+                annotation_color(color)?,
+            ),
+            Self::Callout {
+                x,
+                y,
+                anchor,
+                text,
+                shape,
+                fill,
+                color,
+                thickness,
+                style,
+            } => {
+                let mut result = screenshot.clone();
+                draw_callout(
+                    &mut result.data,
+                    (*x, *y),
+                    *anchor,
+                    text,
+                    *shape,
+                    annotation_color(fill)?,
+                    annotation_color(color)?,
+                    *thickness,
+                    style,
+                )?;
+                Ok(result)
+            }
+        }
+    }
+}
+
+fn annotation_color(color: &str) -> Result<Rgba<u8>> {
+    parse_color(color).ok_or_else(|| ScreenshotError::Config(format!("Invalid color: {}", color)))
+}
+
+fn draw_arrow(
+    image: &mut RgbaImage,
+    from: (i32, i32),
+    to: (i32, i32),
+    color: Rgba<u8>,
+    thickness: u32,
+) {
+    let (dx, dy) = ((to.0 - from.0) as f64, (to.1 - from.1) as f64);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < 1.0 {
+        return;
+    }
+
+    draw_thick_line(image, from, to, color, thickness);
+
+    let head = (thickness as f64 * 4.0).max(12.0).min(length / 2.0);
+    let angle = dy.atan2(dx);
+    for side in [-1.0, 1.0] {
+        let wing = angle + std::f64::consts::PI + side * std::f64::consts::FRAC_PI_6;
+        let end = (
+            to.0 + (wing.cos() * head).round() as i32,
+            to.1 + (wing.sin() * head).round() as i32,
+        );
+        draw_thick_line(image, to, end, color, thickness);
+    }
+}
+
+// The box grows to fit the text; the tail or leader runs from the box to
+// the anchor, which is the point being called out.
+#[allow(clippy::too_many_arguments)]
+fn draw_callout(
+    image: &mut RgbaImage,
+    (x, y): (i32, i32),
+    anchor: (i32, i32),
+    text: &str,
+    shape: CalloutShape,
+    fill: Rgba<u8>,
+    border: Rgba<u8>,
+    thickness: u32,
+    style: &TextStyle,
+) -> Result<()> {
+    let font = style.load_font()?;
+    let (text_width, text_height) = text_size(text, &font, style.size);
+    let padding = (style.size * 0.5).round().max(4.0) as u32;
+    let width = text_width + padding * 2;
+    let height = text_height + padding * 2;
+    let radius = match shape {
+        CalloutShape::Bubble => width.min(height) / 3,
+        CalloutShape::Label => height / 2,
+    }
+    .min(width / 2);
+
+    let inside = |(px, py): (i32, i32)| {
+        px >= x && py >= y && px < x + width as i32 && py < y + height as i32
+    };
+    // Where the tail or leader leaves the box, kept clear of the corners.
+    let root = (
+        anchor
+            .0
+            .clamp(x + radius as i32, x + (width - radius) as i32),
+        anchor
+            .1
+            .clamp(y + radius as i32, y + (height - radius) as i32),
+    );
+
+    let tail = (shape == CalloutShape::Bubble && !inside(anchor))
+        .then(|| tail_base(root, anchor, (radius / 2).max(thickness + 2) as f64));
+
+    if let Some(base) = tail {
+        for corner in base {
+            draw_thick_line(image, corner, anchor, border, thickness);
+        }
+    }
+    if shape == CalloutShape::Label && !inside(anchor) {
+        draw_thick_line(image, root, anchor, border, thickness);
+        imageproc::drawing::draw_filled_circle_mut(image, anchor, (thickness + 2) as i32, border);
+    }
+
+    let outline = RgbaImage::from_pixel(width + thickness * 2, height + thickness * 2, border);
+    imageops::overlay(
+        image,
+        &round_corners(&outline, radius + thickness),
+        (x - thickness as i32) as i64,
+        (y - thickness as i32) as i64,
+    );
+    let body = RgbaImage::from_pixel(width, height, fill);
+    imageops::overlay(image, &round_corners(&body, radius), x as i64, y as i64);
+
+    // Filling the tail after the box hides the border where the two meet.
+    if let Some([a, b]) = tail {
+        let tip = (anchor.0 - root.0, anchor.1 - root.1);
+        let length = ((tip.0 * tip.0 + tip.1 * tip.1) as f64).sqrt().max(1.0);
+        let inset = thickness as f64 / length;
+        let tip = (
+            anchor.0 - (tip.0 as f64 * inset).round() as i32,
+            anchor.1 - (tip.1 as f64 * inset).round() as i32,
+        );
+        if tip != a && tip != b && a != b {
+            imageproc::drawing::draw_polygon_mut(
+                image,
+                &[
+                    Point::new(a.0, a.1),
+                    Point::new(tip.0, tip.1),
+                    Point::new(b.0, b.1),
+                ],
+                fill,
+            );
+        }
+    }
+
+    style.draw(image, text, x + padding as i32, y + padding as i32)
+}
+
+fn tail_base(root: (i32, i32), anchor: (i32, i32), half_width: f64) -> [(i32, i32); 2] {
+    let (dx, dy) = ((anchor.0 - root.0) as f64, (anchor.1 - root.1) as f64);
+    let length = (dx * dx + dy * dy).sqrt().max(1.0);
+    let (nx, ny) = (-dy / length * half_width, dx / length * half_width);
+    [
+        (root.0 + nx.round() as i32, root.1 + ny.round() as i32),
+        (root.0 - nx.round() as i32, root.1 - ny.round() as i32),
+    ]
+}
+
+fn draw_thick_line(
+    image: &mut RgbaImage,
+    from: (i32, i32),
+    to: (i32, i32),
+    color: Rgba<u8>,
+    thickness: u32,
+) {
+    let radius = (thickness / 2) as i32;
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+
+    for step in 0..=steps {
+        let t = step as f64 / steps as f64;
+        let x = from.0 as f64 + (to.0 - from.0) as f64 * t;
+        let y = from.1 as f64 + (to.1 - from.1) as f64 * t;
+        imageproc::drawing::draw_filled_circle_mut(
+            image,
+            (x.round() as i32, y.round() as i32),
+            radius,
+            color,
+        );
+    }
+}
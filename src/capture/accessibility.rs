@@ -0,0 +1,257 @@
+use crate::{Region, Result, ScreenshotError, capture::Compositor};
+use std::collections::VecDeque;
+use zbus::Connection;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+const STATE_ACTIVE: u32 = 1;
+const STATE_FOCUSED: u32 = 12;
+const STATE_SHOWING: u32 = 25;
+
+const COORD_TYPE_SCREEN: u32 = 0;
+const COORD_TYPE_WINDOW: u32 = 1;
+
+const MAX_VISITED: usize = 20_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WidgetQuery {
+    Focused,
+    Matching {
+        role: Option<String>,
+        name: Option<String>,
+    },
+}
+
+impl std::str::FromStr for WidgetQuery {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("focused") {
+            return Ok(Self::Focused);
+        }
+
+        let mut role = None;
+        let mut name = None;
+
+        for part in s.split(',') {
+            match part.split_once(['=', ':']) {
+                Some(("role", value)) => role = Some(value.trim().to_lowercase()),
+                Some(("name", value)) => name = Some(value.trim().to_string()),
+                _ => return Err(format!("Invalid widget query: {}", s)),
+            }
+        }
+
+        if role.is_none() && name.is_none() {
+            return Err(format!("Invalid widget query: {}", s));
+        }
+
+        Ok(Self::Matching { role, name })
+    }
+}
+
+struct Node {
+    destination: String,
+    path: OwnedObjectPath,
+}
+
+pub struct AccessibilityBus {
+    connection: Connection,
+}
+
+impl AccessibilityBus {
+    pub async fn connect() -> Result<Self> {
+        let session = Connection::session().await.map_err(bus_error)?;
+        let reply = session
+            .call_method(
+                Some("org.a11y.Bus"),
+                "/org/a11y/bus",
+                Some("org.a11y.Bus"),
+                "GetAddress",
+                &(),
+            )
+            .await
+            .map_err(bus_error)?;
+        let address: String = reply.body().deserialize().map_err(bus_error)?;
+
+        let connection = zbus::connection::Builder::address(address.as_str())
+            .map_err(bus_error)?
+            .build()
+            .await
+            .map_err(bus_error)?;
+
+        Ok(Self { connection })
+    }
+
+    pub async fn find_widget(&self, query: &WidgetQuery) -> Result<Region> {
+        let root = Node {
+            destination: "org.a11y.atspi.Registry".to_string(),
+            path: OwnedObjectPath::try_from("/org/a11y/atspi/accessible/root")
+                .map_err(bus_error)?,
+        };
+
+        let mut queue = VecDeque::from([root]);
+        let mut visited = 0;
+
+        while let Some(node) = queue.pop_front() {
+            visited += 1;
+            if visited > MAX_VISITED {
+                break;
+            }
+
+            let states = self.states(&node).await.unwrap_or_default();
+            let is_root = visited == 1;
+
+            if !is_root && !has_state(&states, STATE_SHOWING) && !has_state(&states, STATE_ACTIVE) {
+                continue;
+            }
+
+            if !is_root && self.matches(&node, &states, query).await {
+                return self.extents(&node).await;
+            }
+
+            let mut children = self.children(&node).await.unwrap_or_default();
+            if query == &WidgetQuery::Focused {
+                let mut prioritized = Vec::with_capacity(children.len());
+                for child in children.drain(..) {
+                    let active = self
+                        .states(&child)
+                        .await
+                        .map(|s| has_state(&s, STATE_ACTIVE))
+                        .unwrap_or(false);
+                    if active {
+                        prioritized.insert(0, child);
+                    } else {
+                        prioritized.push(child);
+                    }
+                }
+                children = prioritized;
+            }
+
+            queue.extend(children);
+        }
+
+        Err(ScreenshotError::CaptureFailed(
+            "No accessible widget matches the query".to_string(),
+        ))
+    }
+
+    async fn matches(&self, node: &Node, states: &[u32], query: &WidgetQuery) -> bool {
+        match query {
+            WidgetQuery::Focused => has_state(states, STATE_FOCUSED),
+            WidgetQuery::Matching { role, name } => {
+                if let Some(role) = role {
+                    match self.role_name(node).await {
+                        Ok(node_role) if node_role.to_lowercase() == *role => {}
+                        _ => return false,
+                    }
+                }
+
+                if let Some(name) = name {
+                    match self.name(node).await {
+                        Ok(node_name) if node_name.contains(name.as_str()) => {}
+                        _ => return false,
+                    }
+                }
+
+                true
+            }
+        }
+    }
+
+    async fn call<B, R>(&self, node: &Node, interface: &str, method: &str, body: &B) -> Result<R>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+        R: for<'d> zbus::zvariant::DynamicDeserialize<'d>,
+    {
+        let reply = self
+            .connection
+            .call_method(
+                Some(node.destination.as_str()),
+                node.path.as_str(),
+                Some(interface),
+                method,
+                body,
+            )
+            .await
+            .map_err(bus_error)?;
+
+        reply.body().deserialize().map_err(bus_error)
+    }
+
+    async fn children(&self, node: &Node) -> Result<Vec<Node>> {
+        let children: Vec<(String, OwnedObjectPath)> = self
+            .call(node, "org.a11y.atspi.Accessible", "GetChildren", &())
+            .await?;
+
+        Ok(children
+            .into_iter()
+            .map(|(destination, path)| Node { destination, path })
+            .collect())
+    }
+
+    async fn states(&self, node: &Node) -> Result<Vec<u32>> {
+        self.call(node, "org.a11y.atspi.Accessible", "GetState", &())
+            .await
+    }
+
+    async fn role_name(&self, node: &Node) -> Result<String> {
+        self.call(node, "org.a11y.atspi.Accessible", "GetRoleName", &())
+            .await
+    }
+
+    async fn name(&self, node: &Node) -> Result<String> {
+        let value: OwnedValue = self
+            .call(
+                node,
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                &("org.a11y.atspi.Accessible", "Name"),
+            )
+            .await?;
+
+        String::try_from(value).map_err(bus_error)
+    }
+
+    async fn extents(&self, node: &Node) -> Result<Region> {
+        // Wayland toolkits usually report window-relative coordinates only, so
+        // anchor them to the compositor's focused window when one is known.
+        if let Ok(Some(window)) = Compositor::detect().active_window() {
+            let (x, y, width, height): (i32, i32, i32, i32) = self
+                .call(
+                    node,
+                    "org.a11y.atspi.Component",
+                    "GetExtents",
+                    &(COORD_TYPE_WINDOW,),
+                )
+                .await?;
+
+            return Ok(Region::new(
+                window.x + x,
+                window.y + y,
+                width.max(0) as u32,
+                height.max(0) as u32,
+            ));
+        }
+
+        let (x, y, width, height): (i32, i32, i32, i32) = self
+            .call(
+                node,
+                "org.a11y.atspi.Component",
+                "GetExtents",
+                &(COORD_TYPE_SCREEN,),
+            )
+            .await?;
+
+        Ok(Region::new(x, y, width.max(0) as u32, height.max(0) as u32))
+    }
+}
+
+fn has_state(states: &[u32], state: u32) -> bool {
+    let word = (state / 32) as usize;
+    states
+        .get(word)
+        .is_some_and(|bits| bits & (1 << (state % 32)) != 0)
+}
+
+fn bus_error<E: std::fmt::Display>(e: E) -> ScreenshotError {
+    ScreenshotError::CaptureFailed(format!("Accessibility bus error: {}", e))
+}
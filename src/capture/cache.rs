@@ -0,0 +1,52 @@
+use crate::{
+    CaptureMode, CaptureOptions, Display, LogicalRegion, Result, Screenshot, ScreenshotBackend,
+    ScreenshotError, capture::Layout,
+};
+use tokio::sync::OnceCell;
+
+// Captures the full screen at most once and crops every later request from
+// that frame, so several regions in one invocation cost a single portal call.
+pub struct FrameCache<'a> {
+    backend: &'a dyn ScreenshotBackend,
+    options: &'a CaptureOptions,
+    displays: OnceCell<Vec<Display>>,
+    frame: OnceCell<Screenshot>,
+}
+
+impl<'a> FrameCache<'a> {
+    pub fn new(backend: &'a dyn ScreenshotBackend, options: &'a CaptureOptions) -> Self {
+        Self {
+            backend,
+            options,
+            displays: OnceCell::new(),
+            frame: OnceCell::new(),
+        }
+    }
+
+    pub async fn displays(&self) -> Result<&[Display]> {
+        self.displays
+            .get_or_try_init(|| self.backend.get_displays())
+            .await
+            .map(Vec::as_slice)
+    }
+
+    pub async fn frame(&self) -> Result<&Screenshot> {
+        self.frame
+            .get_or_try_init(|| self.backend.capture(CaptureMode::Screen, self.options))
+            .await
+    }
+
+    pub async fn region(&self, region: LogicalRegion) -> Result<Screenshot> {
+        let layout = Layout::new(self.displays().await?);
+        let frame = self.frame().await?;
+        let region = layout.frame_region(region, frame.width(), frame.height()).0;
+
+        if region.width == 0 || region.height == 0 {
+            return Err(ScreenshotError::InvalidRegion(
+                "Target is outside the captured area".to_string(),
+            ));
+        }
+
+        frame.crop(region)
+    }
+}
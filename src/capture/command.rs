@@ -0,0 +1,121 @@
+use crate::{
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, Result, Screenshot,
+    ScreenshotBackend, ScreenshotError, WindowInfo,
+    capture::{Compositor, layout::crop_frame},
+    config::FallbackCommandConfig,
+};
+use async_trait::async_trait;
+use image::RgbaImage;
+
+// Runs an external tool such as grim, gnome-screenshot or spectacle. The
+// image is read from stdout unless an argument contains `{file}`, in which
+// case the tool writes to that temporary path instead.
+pub struct CommandBackend {
+    command: String,
+    args: Vec<String>,
+}
+
+impl CommandBackend {
+    pub fn new(config: &FallbackCommandConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            args: config.args.clone(),
+        }
+    }
+
+    pub(crate) async fn grab(&self) -> Result<RgbaImage> {
+        let path = std::env::temp_dir().join(format!(
+            "wst-capture-{}-{}.png",
+            std::process::id(),
+            chrono::Local::now().timestamp_millis()
+        ));
+        let uses_file = self.args.iter().any(|arg| arg.contains("{file}"));
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.replace("{file}", &path.to_string_lossy()))
+            .collect();
+
+        let output = tokio::process::Command::new(&self.command)
+            .args(&args)
+            .output()
+            .await
+            .map_err(|e| {
+                ScreenshotError::CaptureFailed(format!("Failed to run {}: {}", self.command, e))
+            })?;
+
+        let image = if uses_file {
+            let image = image::open(&path);
+            let _ = std::fs::remove_file(&path);
+            image
+        } else {
+            image::load_from_memory(&output.stdout)
+        };
+
+        if !output.status.success() {
+            return Err(ScreenshotError::CaptureFailed(format!(
+                "{} exited with {}: {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(image.map_err(ScreenshotError::Image)?.to_rgba8())
+    }
+}
+
+#[async_trait]
+impl ScreenshotBackend for CommandBackend {
+    async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
+        if matches!(
+            mode,
+            CaptureMode::Window | CaptureMode::Region | CaptureMode::Freeform
+        ) && options.region.is_none()
+        {
+            return Err(ScreenshotError::CaptureFailed(format!(
+                "{} cannot select interactively; pass a region",
+                self.command
+            )));
+        }
+
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        let frame = self.grab().await?;
+        let mut screenshot = crop_frame(self, frame, mode, options).await?;
+        screenshot.metadata.compositor = Some(self.command.clone());
+        screenshot.metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
+
+        Ok(screenshot)
+    }
+
+    async fn get_displays(&self) -> Result<Vec<Display>> {
+        Compositor::detect().displays()
+    }
+
+    async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
+        Compositor::detect().active_window()
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        Compositor::detect().windows()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        let knows_layout = Compositor::detect() != Compositor::Unknown;
+
+        BackendCapabilities {
+            cursor: false,
+            per_output: knows_layout,
+            window: knows_layout,
+            non_interactive: true,
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{Display, Region, Result, ScreenshotError, Transform, WindowInfo};
+use serde_json::Value;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compositor {
+    Hyprland,
+    Sway,
+    Unknown,
+}
+
+impl Compositor {
+    pub fn detect() -> Self {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Self::Hyprland
+        } else if std::env::var_os("SWAYSOCK").is_some() {
+            Self::Sway
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Self::Hyprland => Some("Hyprland".to_string()),
+            Self::Sway => Some("sway".to_string()),
+            Self::Unknown => std::env::var("XDG_CURRENT_DESKTOP")
+                .ok()
+                .filter(|desktop| !desktop.is_empty()),
+        }
+    }
+
+    pub fn windows(&self) -> Result<Vec<WindowInfo>> {
+        match self {
+            Self::Hyprland => {
+                let border = self.hyprland_option_int("general:border_size");
+                let clients = query_json("hyprctl", &["clients", "-j"])?;
+                let monitors = query_json("hyprctl", &["monitors", "-j"])?;
+
+                let visible_workspaces: Vec<i64> = monitors
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|m| {
+                        [
+                            m["activeWorkspace"]["id"].as_i64(),
+                            m["specialWorkspace"]["id"].as_i64().filter(|id| *id != 0),
+                        ]
+                    })
+                    .flatten()
+                    .collect();
+
+                Ok(clients
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|c| c["mapped"].as_bool().unwrap_or(true))
+                    .filter(|c| !c["hidden"].as_bool().unwrap_or(false))
+                    .filter(|c| {
+                        c["workspace"]["id"]
+                            .as_i64()
+                            .is_none_or(|id| visible_workspaces.contains(&id))
+                    })
+                    .filter_map(|c| hyprland_window(c, border))
+                    .collect())
+            }
+            Self::Sway => {
+                let tree = query_json("swaymsg", &["-t", "get_tree", "-r"])?;
+                let mut windows = Vec::new();
+                collect_sway_windows(&tree, &mut windows);
+                Ok(windows.into_iter().map(|(window, _)| window).collect())
+            }
+            Self::Unknown => Ok(foreign_toplevels()
+                .into_iter()
+                .map(|(window, _)| window)
+                .collect()),
+        }
+    }
+
+    pub fn active_window(&self) -> Result<Option<WindowInfo>> {
+        match self {
+            Self::Hyprland => {
+                let border = self.hyprland_option_int("general:border_size");
+                let active = query_json("hyprctl", &["activewindow", "-j"])?;
+                Ok(hyprland_window(&active, border))
+            }
+            Self::Sway => {
+                let tree = query_json("swaymsg", &["-t", "get_tree", "-r"])?;
+                let mut windows = Vec::new();
+                collect_sway_windows(&tree, &mut windows);
+                Ok(windows
+                    .into_iter()
+                    .find(|(_, focused)| *focused)
+                    .map(|(window, _)| window))
+            }
+            Self::Unknown => Ok(foreign_toplevels()
+                .into_iter()
+                .find(|(_, activated)| *activated)
+                .map(|(window, _)| window)),
+        }
+    }
+
+    pub fn displays(&self) -> Result<Vec<Display>> {
+        match self {
+            Self::Hyprland => {
+                let monitors = query_json("hyprctl", &["monitors", "-j"])?;
+                Ok(monitors
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|m| {
+                        Some(Display {
+                            name: m["name"].as_str()?.to_string(),
+                            width: m["width"].as_u64()? as u32,
+                            height: m["height"].as_u64()? as u32,
+                            x: m["x"].as_i64()? as i32,
+                            y: m["y"].as_i64()? as i32,
+                            scale: m["scale"].as_f64().unwrap_or(1.0),
+                            is_primary: m["focused"].as_bool().unwrap_or(false),
+                            transform: Transform::from_wl(
+                                m["transform"].as_u64().unwrap_or(0) as u32
+                            ),
+                        })
+                    })
+                    .collect())
+            }
+            Self::Sway => {
+                let outputs = query_json("swaymsg", &["-t", "get_outputs", "-r"])?;
+                Ok(outputs
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|o| o["active"].as_bool().unwrap_or(false))
+                    .filter_map(|o| {
+                        let rect = sway_rect(&o["rect"])?;
+                        let scale = o["scale"].as_f64().unwrap_or(1.0);
+                        let mode = &o["current_mode"];
+                        let transform: Transform = o["transform"]
+                            .as_str()
+                            .and_then(|t| t.parse().ok())
+                            .unwrap_or_default();
+                        let (rect_width, rect_height) = transform.apply(rect.width, rect.height);
+
+                        Some(Display {
+                            name: o["name"].as_str()?.to_string(),
+                            width: mode["width"]
+                                .as_u64()
+                                .map(|w| w as u32)
+                                .unwrap_or((rect_width as f64 * scale).round() as u32),
+                            height: mode["height"]
+                                .as_u64()
+                                .map(|h| h as u32)
+                                .unwrap_or((rect_height as f64 * scale).round() as u32),
+                            x: rect.x,
+                            y: rect.y,
+                            scale,
+                            is_primary: o["focused"].as_bool().unwrap_or(false),
+                            transform,
+                        })
+                    })
+                    .collect())
+            }
+            Self::Unknown => Ok(wlr_outputs()),
+        }
+    }
+
+    pub fn shadow_extent(&self) -> u32 {
+        match self {
+            Self::Hyprland => {
+                let enabled = self
+                    .hyprland_option_int("decoration:shadow:enabled")
+                    .or_else(|| self.hyprland_option_int("decoration:drop_shadow"))
+                    .unwrap_or(0);
+
+                if enabled == 0 {
+                    0
+                } else {
+                    self.hyprland_option_int("decoration:shadow:range")
+                        .or_else(|| self.hyprland_option_int("decoration:shadow_range"))
+                        .unwrap_or(0)
+                }
+            }
+            Self::Sway | Self::Unknown => 0,
+        }
+    }
+
+    fn hyprland_option_int(&self, option: &str) -> Option<u32> {
+        query_json("hyprctl", &["getoption", option, "-j"])
+            .ok()
+            .and_then(|v| v["int"].as_i64())
+            .map(|v| v.max(0) as u32)
+    }
+}
+
+fn query_json(program: &str, args: &[&str]) -> Result<Value> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("{} not available: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(ScreenshotError::CaptureFailed(format!(
+            "{} exited with {}",
+            program, output.status
+        )));
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+// wlr-foreign-toplevel-management does not expose geometry, so toplevels
+// listed through lswt only carry their title, app_id and activation state.
+fn foreign_toplevels() -> Vec<(WindowInfo, bool)> {
+    let Ok(listing) = query_json("lswt", &["--json"]) else {
+        return Vec::new();
+    };
+
+    listing["toplevels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|t| {
+            let window = WindowInfo {
+                title: t["title"].as_str().unwrap_or_default().to_string(),
+                app_id: t["app-id"].as_str().unwrap_or_default().to_string(),
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 0,
+                frame: None,
+            };
+            (window, t["activated"].as_bool().unwrap_or(false))
+        })
+        .collect()
+}
+
+fn wlr_outputs() -> Vec<Display> {
+    let Ok(outputs) = query_json("wlr-randr", &["--json"]) else {
+        return Vec::new();
+    };
+
+    outputs
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|o| o["enabled"].as_bool().unwrap_or(false))
+        .filter_map(|o| {
+            let mode = o["modes"]
+                .as_array()?
+                .iter()
+                .find(|m| m["current"].as_bool().unwrap_or(false))?;
+
+            Some(Display {
+                name: o["name"].as_str()?.to_string(),
+                width: mode["width"].as_u64()? as u32,
+                height: mode["height"].as_u64()? as u32,
+                x: o["position"]["x"].as_i64().unwrap_or(0) as i32,
+                y: o["position"]["y"].as_i64().unwrap_or(0) as i32,
+                scale: o["scale"].as_f64().unwrap_or(1.0),
+                is_primary: false,
+                transform: o["transform"]
+                    .as_str()
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn hyprland_window(client: &Value, border: Option<u32>) -> Option<WindowInfo> {
+    let at = client["at"].as_array()?;
+    let size = client["size"].as_array()?;
+
+    let x = at.first()?.as_i64()? as i32;
+    let y = at.get(1)?.as_i64()? as i32;
+    let width = size.first()?.as_u64()? as u32;
+    let height = size.get(1)?.as_u64()? as u32;
+    let border = border.unwrap_or(0);
+
+    Some(WindowInfo {
+        title: client["title"].as_str().unwrap_or_default().to_string(),
+        app_id: client["class"].as_str().unwrap_or_default().to_string(),
+        x,
+        y,
+        width,
+        height,
+        frame: Some(Region::new(
+            x - border as i32,
+            y - border as i32,
+            width + border * 2,
+            height + border * 2,
+        )),
+    })
+}
+
+fn collect_sway_windows(node: &Value, windows: &mut Vec<(WindowInfo, bool)>) {
+    let children = node["nodes"]
+        .as_array()
+        .into_iter()
+        .chain(node["floating_nodes"].as_array())
+        .flatten();
+
+    let is_window = node["pid"].is_number()
+        && matches!(node["type"].as_str(), Some("con") | Some("floating_con"));
+
+    if is_window
+        && node["visible"].as_bool().unwrap_or(true)
+        && let Some(window) = sway_window(node)
+    {
+        windows.push((window, node["focused"].as_bool().unwrap_or(false)));
+    }
+
+    for child in children {
+        collect_sway_windows(child, windows);
+    }
+}
+
+fn sway_window(node: &Value) -> Option<WindowInfo> {
+    let rect = sway_rect(&node["rect"])?;
+    let content =
+        sway_rect(&node["window_rect"]).unwrap_or(Region::new(0, 0, rect.width, rect.height));
+
+    let app_id = node["app_id"]
+        .as_str()
+        .or_else(|| node["window_properties"]["class"].as_str())
+        .unwrap_or_default();
+
+    Some(WindowInfo {
+        title: node["name"].as_str().unwrap_or_default().to_string(),
+        app_id: app_id.to_string(),
+        x: rect.x + content.x,
+        y: rect.y + content.y,
+        width: content.width,
+        height: content.height,
+        frame: Some(rect),
+    })
+}
+
+fn sway_rect(rect: &Value) -> Option<Region> {
+    Some(Region::new(
+        rect["x"].as_i64()? as i32,
+        rect["y"].as_i64()? as i32,
+        rect["width"].as_u64()? as u32,
+        rect["height"].as_u64()? as u32,
+    ))
+}
@@ -1,9 +1,32 @@
-use crate::{Result, ScreenshotBackend};
+use crate::{Result, ScreenshotBackend, config::CaptureConfig};
+
+pub fn create_backend(config: &CaptureConfig) -> Result<Box<dyn ScreenshotBackend>> {
+    #[cfg(feature = "testing")]
+    if std::env::var("JSWST_BACKEND").is_ok_and(|backend| backend == "mock") {
+        return Ok(Box::new(crate::capture::MockBackend::from_env()?));
+    }
 
-pub fn create_backend() -> Result<Box<dyn ScreenshotBackend>> {
     if std::env::var("WAYLAND_DISPLAY").is_ok() {
-        Ok(Box::new(crate::capture::WaylandBackend::new()?))
-    } else {
-        Err(crate::error::ScreenshotError::BackendUnavailable)
+        if config.fallback_command.prefer {
+            return Ok(Box::new(crate::capture::CommandBackend::new(
+                &config.fallback_command,
+            )));
+        }
+
+        #[cfg(feature = "kwin")]
+        if crate::capture::KWinBackend::is_available() {
+            return Ok(Box::new(crate::capture::KWinBackend::new(config)?));
+        }
+
+        return Ok(Box::new(crate::capture::WaylandBackend::with_config(
+            config,
+        )?));
     }
+
+    #[cfg(feature = "x11")]
+    if std::env::var("DISPLAY").is_ok() {
+        return Ok(Box::new(crate::capture::X11Backend::new()?));
+    }
+
+    Err(crate::error::ScreenshotError::BackendUnavailable)
 }
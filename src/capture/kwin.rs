@@ -0,0 +1,254 @@
+use crate::{
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, OutputFormat, Result,
+    Screenshot, ScreenshotBackend, ScreenshotError, WindowInfo,
+    capture::{Compositor, WaylandBackend},
+    config::CaptureConfig,
+};
+use async_trait::async_trait;
+use image::RgbaImage;
+use std::collections::HashMap;
+use std::os::fd::AsFd;
+use tokio::io::AsyncReadExt;
+use zbus::Connection;
+use zbus::zvariant::{Fd, OwnedValue, Value};
+
+// QImage::Format values KWin hands back.
+const FORMAT_RGB32: u32 = 4;
+const FORMAT_ARGB32: u32 = 5;
+const FORMAT_ARGB32_PREMULTIPLIED: u32 = 6;
+const FORMAT_RGBX8888: u32 = 16;
+const FORMAT_RGBA8888: u32 = 17;
+const FORMAT_RGBA8888_PREMULTIPLIED: u32 = 18;
+
+const INTERACTIVE_WINDOW: u32 = 0;
+
+enum Target {
+    Workspace,
+    ActiveScreen,
+    Screen(String),
+    ActiveWindow,
+    PickWindow,
+    Area(crate::Region),
+}
+
+pub struct KWinBackend {
+    window_decorations: bool,
+    fallback: WaylandBackend,
+}
+
+impl KWinBackend {
+    pub fn new(config: &CaptureConfig) -> Result<Self> {
+        Ok(Self {
+            window_decorations: config.window_decorations,
+            fallback: WaylandBackend::with_config(config)?,
+        })
+    }
+
+    // KWin only serves ScreenShot2 to executables whose desktop entry lists
+    // it in X-KDE-DBUS-Restricted-Interfaces (data/wst.desktop); without
+    // one installed the call is refused and the portal is used instead.
+    pub fn is_available() -> bool {
+        std::env::var("XDG_CURRENT_DESKTOP")
+            .map(|desktop| desktop.split(':').any(|name| name == "KDE"))
+            .unwrap_or(false)
+    }
+
+    async fn capture_target(&self, target: Target, cursor: bool) -> Result<RgbaImage> {
+        let connection = Connection::session().await.map_err(kwin_error)?;
+        let (sender, mut receiver) = tokio::net::unix::pipe::pipe()?;
+        let pipe = sender.into_blocking_fd()?;
+
+        let mut options: HashMap<&str, Value> = HashMap::new();
+        options.insert("include-cursor", cursor.into());
+        options.insert("include-decoration", self.window_decorations.into());
+        options.insert("native-resolution", true.into());
+
+        let fd = Fd::from(pipe.as_fd());
+        let reply = match &target {
+            Target::Workspace => call(&connection, "CaptureWorkspace", &(options, fd)).await,
+            Target::ActiveScreen => call(&connection, "CaptureActiveScreen", &(options, fd)).await,
+            Target::Screen(name) => {
+                call(&connection, "CaptureScreen", &(name.as_str(), options, fd)).await
+            }
+            Target::ActiveWindow => call(&connection, "CaptureActiveWindow", &(options, fd)).await,
+            Target::PickWindow => {
+                call(
+                    &connection,
+                    "CaptureInteractive",
+                    &(INTERACTIVE_WINDOW, options, fd),
+                )
+                .await
+            }
+            Target::Area(region) => {
+                call(
+                    &connection,
+                    "CaptureArea",
+                    &(region.x, region.y, region.width, region.height, options, fd),
+                )
+                .await
+            }
+        }?;
+        // KWin writes the pixels after replying; our copy of the write end
+        // must be closed for the read to see EOF.
+        drop(pipe);
+
+        let mut data = Vec::new();
+        receiver.read_to_end(&mut data).await?;
+
+        decode(&reply, data)
+    }
+}
+
+async fn call<B>(
+    connection: &Connection,
+    method: &str,
+    body: &B,
+) -> Result<HashMap<String, OwnedValue>>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+{
+    let reply = connection
+        .call_method(
+            Some("org.kde.KWin"),
+            "/org/kde/KWin/ScreenShot2",
+            Some("org.kde.KWin.ScreenShot2"),
+            method,
+            body,
+        )
+        .await
+        .map_err(|e| match &e {
+            zbus::Error::MethodError(name, _, _)
+                if name.as_str().ends_with(".NoAuthorized")
+                    || name.as_str() == "org.freedesktop.DBus.Error.AccessDenied" =>
+            {
+                ScreenshotError::BackendUnavailable
+            }
+            _ => kwin_error(e),
+        })?;
+
+    reply.body().deserialize().map_err(kwin_error)
+}
+
+fn decode(results: &HashMap<String, OwnedValue>, data: Vec<u8>) -> Result<RgbaImage> {
+    let field = |name: &str| {
+        results
+            .get(name)
+            .and_then(|value| value.downcast_ref::<u32>().ok())
+            .ok_or_else(|| ScreenshotError::CaptureFailed(format!("KWin reply lacks {}", name)))
+    };
+    let (width, height, stride, format) = (
+        field("width")?,
+        field("height")?,
+        field("stride")?,
+        field("format")?,
+    );
+
+    let bgra = match format {
+        FORMAT_RGB32 | FORMAT_ARGB32 | FORMAT_ARGB32_PREMULTIPLIED => true,
+        FORMAT_RGBX8888 | FORMAT_RGBA8888 | FORMAT_RGBA8888_PREMULTIPLIED => false,
+        other => {
+            return Err(ScreenshotError::CaptureFailed(format!(
+                "Unsupported KWin image format {}",
+                other
+            )));
+        }
+    };
+    let opaque = matches!(format, FORMAT_RGB32 | FORMAT_RGBX8888);
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in data.chunks(stride as usize).take(height as usize) {
+        for px in row[..(width as usize * 4).min(row.len())].chunks_exact(4) {
+            let alpha = if opaque { 255 } else { px[3] };
+            if bgra {
+                pixels.extend_from_slice(&[px[2], px[1], px[0], alpha]);
+            } else {
+                pixels.extend_from_slice(&[px[0], px[1], px[2], alpha]);
+            }
+        }
+    }
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| ScreenshotError::CaptureFailed("KWin sent a short image".to_string()))
+}
+
+#[async_trait]
+impl ScreenshotBackend for KWinBackend {
+    async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
+        let target = match (mode, options.region) {
+            // KWin takes logical coordinates and scales with native-resolution.
+            (_, Some(region)) => Target::Area(region.0),
+            (CaptureMode::Screen, None) => Target::Workspace,
+            (CaptureMode::Monitor, None) => match options.monitor_index {
+                Some(index) => {
+                    let displays = self.get_displays().await?;
+                    let display = displays.get(index).ok_or(ScreenshotError::NoDisplay)?;
+                    Target::Screen(display.name.clone())
+                }
+                None => Target::ActiveScreen,
+            },
+            (CaptureMode::ActiveWindow, None) => Target::ActiveWindow,
+            (CaptureMode::Window, None) => Target::PickWindow,
+            (CaptureMode::Region | CaptureMode::Freeform, None) => {
+                return Err(ScreenshotError::CaptureFailed(
+                    "KWin cannot select a region interactively; pass a region".to_string(),
+                ));
+            }
+        };
+
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        let monitor = match &target {
+            Target::Screen(name) => Some(name.clone()),
+            _ => None,
+        };
+        let data = match self.capture_target(target, options.include_cursor).await {
+            Err(ScreenshotError::BackendUnavailable) => {
+                tracing::warn!("KWin refused the screenshot; using the portal instead");
+                let mut options = options.clone();
+                options.delay = None;
+                return self.fallback.capture(mode, &options).await;
+            }
+            result => result?,
+        };
+
+        let mut screenshot = Screenshot::new(data, mode, OutputFormat::Png);
+        let metadata = &mut screenshot.metadata;
+        metadata.monitor = monitor;
+        metadata.compositor = Some("KWin".to_string());
+        metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
+
+        Ok(screenshot)
+    }
+
+    async fn get_displays(&self) -> Result<Vec<Display>> {
+        Compositor::detect().displays()
+    }
+
+    async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
+        Compositor::detect().active_window()
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        Compositor::detect().windows()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            cursor: true,
+            per_output: true,
+            window: true,
+            non_interactive: true,
+        }
+    }
+}
+
+fn kwin_error(e: impl std::fmt::Display) -> ScreenshotError {
+    ScreenshotError::CaptureFailed(format!("KWin: {}", e))
+}
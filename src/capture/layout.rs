@@ -0,0 +1,358 @@
+use crate::{
+    CaptureMode, CaptureOptions, Display, LogicalRegion, OutputFormat, PhysicalRegion, Region,
+    Result, Screenshot, ScreenshotBackend, ScreenshotError,
+};
+use image::RgbaImage;
+
+pub struct Layout {
+    bounds: Region,
+    outputs: Vec<(Region, f64)>,
+}
+
+impl Layout {
+    pub fn new(displays: &[Display]) -> Self {
+        let bounds = displays
+            .iter()
+            .map(|d| d.logical_region().0)
+            .fold(Region::new(0, 0, 0, 0), |bounds, region| {
+                bounds.union(&region)
+            });
+
+        let outputs = displays
+            .iter()
+            .map(|d| (d.logical_region().0, d.scale))
+            .collect();
+
+        Self { bounds, outputs }
+    }
+
+    pub fn bounds(&self) -> LogicalRegion {
+        LogicalRegion(self.bounds)
+    }
+
+    pub fn frame_scale(&self, frame_width: u32) -> f64 {
+        if self.bounds.width == 0 {
+            1.0
+        } else {
+            frame_width as f64 / self.bounds.width as f64
+        }
+    }
+
+    // Outputs are placed in the frame at its overall density, but a region is
+    // measured at the scale of the output holding its centre.
+    pub fn to_physical(&self, region: LogicalRegion, frame_width: u32) -> PhysicalRegion {
+        let frame_scale = self.frame_scale(frame_width);
+        let region = region.0;
+        let centre = (
+            region.x + region.width as i32 / 2,
+            region.y + region.height as i32 / 2,
+        );
+        let (origin, scale) = self
+            .outputs
+            .iter()
+            .find(|(output, scale)| *scale > 0.0 && output.contains(centre.0, centre.1))
+            .map_or(
+                ((self.bounds.x, self.bounds.y), frame_scale),
+                |(output, scale)| ((output.x, output.y), *scale),
+            );
+
+        let relative = LogicalRegion(Region::new(
+            region.x - origin.0,
+            region.y - origin.1,
+            region.width,
+            region.height,
+        ))
+        .to_physical(scale)
+        .0;
+
+        PhysicalRegion(Region::new(
+            relative.x + ((origin.0 - self.bounds.x) as f64 * frame_scale).round() as i32,
+            relative.y + ((origin.1 - self.bounds.y) as f64 * frame_scale).round() as i32,
+            relative.width,
+            relative.height,
+        ))
+    }
+
+    pub fn frame_region(
+        &self,
+        region: LogicalRegion,
+        frame_width: u32,
+        frame_height: u32,
+    ) -> PhysicalRegion {
+        let region = self.to_physical(region, frame_width).0;
+
+        let left = (region.x as i64).clamp(0, frame_width as i64);
+        let top = (region.y as i64).clamp(0, frame_height as i64);
+        let right = (region.x as i64 + region.width as i64).clamp(left, frame_width as i64);
+        let bottom = (region.y as i64 + region.height as i64).clamp(top, frame_height as i64);
+
+        PhysicalRegion(Region::new(
+            left as i32,
+            top as i32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+}
+
+// Narrows a full-desktop frame down to what the mode and options ask for,
+// recording which output and window it came from.
+pub async fn crop_frame(
+    backend: &dyn ScreenshotBackend,
+    frame: RgbaImage,
+    mode: CaptureMode,
+    options: &CaptureOptions,
+) -> Result<Screenshot> {
+    let displays = backend.get_displays().await?;
+    let layout = Layout::new(&displays);
+    let mut window = None;
+
+    let target = match (mode, options.monitor_index) {
+        (CaptureMode::ActiveWindow, _) => {
+            let active = backend.get_activate_window().await?.ok_or_else(|| {
+                ScreenshotError::CaptureFailed(
+                    "The compositor reported no focused window".to_string(),
+                )
+            })?;
+            let region = active.region();
+            window = Some(active);
+            Some((region, "The focused window lies outside the screen"))
+        }
+        (CaptureMode::Monitor, Some(index)) => {
+            let display = displays.get(index).ok_or(ScreenshotError::NoDisplay)?;
+            Some((
+                display.logical_region().0,
+                "The monitor lies outside the screen",
+            ))
+        }
+        _ => None,
+    };
+
+    let scale = layout.frame_scale(frame.width());
+    let (data, visible) = match target {
+        Some((target, message)) => {
+            let region = layout
+                .frame_region(LogicalRegion(target), frame.width(), frame.height())
+                .0;
+            let visible = target.intersect(&layout.bounds().0);
+
+            match visible {
+                Some(visible) if !region.is_empty() => (crop(&frame, region), Some(visible)),
+                _ => return Err(ScreenshotError::InvalidRegion(message.to_string())),
+            }
+        }
+        None => (frame, None),
+    };
+
+    let data = match options.region {
+        Some(region) => {
+            let region = match visible {
+                // The frame is already cut down to the target, so the region is
+                // measured from the target's corner at the target's own density.
+                Some(visible) => {
+                    let scale = data.width() as f64 / visible.width.max(1) as f64;
+                    LogicalRegion(Region::new(
+                        region.0.x - visible.x,
+                        region.0.y - visible.y,
+                        region.0.width,
+                        region.0.height,
+                    ))
+                    .to_physical(scale)
+                    .0
+                }
+                None => layout.to_physical(region, data.width()).0,
+            };
+            let bounds = Region::new(0, 0, data.width(), data.height());
+
+            if region.intersect(&bounds) != Some(region) {
+                return Err(ScreenshotError::InvalidRegion(
+                    "Region out of bounds".to_string(),
+                ));
+            }
+
+            crop(&data, region)
+        }
+        None => data,
+    };
+
+    // The output holding the centre of what was captured, if any.
+    let captured = options
+        .region
+        .map(|region| region.0)
+        .or(target.map(|t| t.0));
+    let display = match captured {
+        Some(region) => {
+            let (x, y) = (
+                region.x + region.width as i32 / 2,
+                region.y + region.height as i32 / 2,
+            );
+            displays
+                .iter()
+                .find(|display| display.logical_region().0.contains(x, y))
+        }
+        None if displays.len() == 1 => displays.first(),
+        None => None,
+    };
+
+    let mut screenshot = Screenshot::new(data, mode, OutputFormat::Png);
+    let metadata = &mut screenshot.metadata;
+    metadata.monitor = display.map(|display| display.name.clone());
+    metadata.scale = Some(display.map_or(scale, |display| display.scale));
+    metadata.window_title = window.as_ref().map(|window| window.title.clone());
+    metadata.app_id = window.map(|window| window.app_id);
+
+    Ok(screenshot)
+}
+
+fn crop(frame: &RgbaImage, region: Region) -> RgbaImage {
+    image::imageops::crop_imm(
+        frame,
+        region.x as u32,
+        region.y as u32,
+        region.width,
+        region.height,
+    )
+    .to_image()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionAnchor {
+    ActiveWindow,
+    Monitor(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct RelativeRegion {
+    pub anchor: RegionAnchor,
+    pub region: Region,
+}
+
+impl std::str::FromStr for RelativeRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid relative region: {} (expected window:x,y,w,h or monitor:NAME:x,y,w,h)",
+                s
+            )
+        };
+
+        let (target, rest) = s.split_once(':').ok_or_else(invalid)?;
+
+        let (anchor, region) = match target {
+            "window" | "win" | "active" => (RegionAnchor::ActiveWindow, rest),
+            "monitor" | "display" | "output" => {
+                let (name, region) = rest.split_once(':').ok_or_else(invalid)?;
+                (RegionAnchor::Monitor(name.to_string()), region)
+            }
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            anchor,
+            region: region.parse()?,
+        })
+    }
+}
+
+impl RelativeRegion {
+    pub async fn resolve(&self, backend: &dyn ScreenshotBackend) -> Result<LogicalRegion> {
+        let (origin_x, origin_y) = match &self.anchor {
+            RegionAnchor::ActiveWindow => {
+                let window = backend.get_activate_window().await?.ok_or_else(|| {
+                    ScreenshotError::InvalidRegion("No active window found".to_string())
+                })?;
+                (window.x, window.y)
+            }
+            RegionAnchor::Monitor(name) => {
+                let displays = backend.get_displays().await?;
+                let display = displays
+                    .iter()
+                    .find(|d| d.name == *name)
+                    .or_else(|| name.parse::<usize>().ok().and_then(|i| displays.get(i)))
+                    .ok_or(ScreenshotError::NoDisplay)?;
+                (display.x, display.y)
+            }
+        };
+
+        Ok(LogicalRegion(Region::new(
+            origin_x + self.region.x,
+            origin_y + self.region.y,
+            self.region.width,
+            self.region.height,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Transform;
+
+    fn display(name: &str, x: i32, width: u32, height: u32, scale: f64) -> Display {
+        Display {
+            name: name.to_string(),
+            width,
+            height,
+            x,
+            y: 0,
+            scale,
+            is_primary: x == 0,
+            transform: Transform::Normal,
+        }
+    }
+
+    // A 2x laptop panel with a 1x monitor to its right, captured as one frame
+    // at the highest density.
+    fn mixed_layout() -> Layout {
+        Layout::new(&[
+            display("eDP-1", 0, 2560, 1440, 2.0),
+            display("HDMI-A-1", 1280, 1920, 1080, 1.0),
+        ])
+    }
+
+    #[test]
+    fn regions_use_the_scale_of_their_output() {
+        let layout = mixed_layout();
+        assert_eq!(layout.bounds().0, Region::new(0, 0, 3200, 1080));
+        assert_eq!(layout.frame_scale(6400), 2.0);
+
+        let primary = LogicalRegion(Region::new(100, 100, 200, 100));
+        assert_eq!(
+            layout.to_physical(primary, 6400).0,
+            Region::new(200, 200, 400, 200)
+        );
+
+        let secondary = LogicalRegion(Region::new(1380, 100, 200, 100));
+        assert_eq!(
+            layout.to_physical(secondary, 6400).0,
+            Region::new(2660, 100, 200, 100)
+        );
+    }
+
+    #[test]
+    fn frame_regions_are_clipped_to_the_frame() {
+        let layout = mixed_layout();
+        let overhanging = LogicalRegion(Region::new(3100, 1000, 200, 200));
+
+        assert_eq!(
+            layout.frame_region(overhanging, 6400, 2160).0,
+            Region::new(6200, 2000, 200, 160)
+        );
+    }
+
+    #[test]
+    fn an_unknown_layout_takes_regions_as_frame_pixels() {
+        let layout = Layout::new(&[]);
+        let region = LogicalRegion(Region::new(10, 20, 30, 40));
+
+        assert_eq!(layout.to_physical(region, 640).0, region.0);
+        assert_eq!(
+            layout
+                .frame_region(LogicalRegion(Region::new(600, 0, 100, 50)), 640, 480)
+                .0,
+            Region::new(600, 0, 40, 50)
+        );
+    }
+}
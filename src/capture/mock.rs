@@ -0,0 +1,180 @@
+use crate::{
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, Result, Screenshot,
+    ScreenshotBackend, ScreenshotError, Transform, WindowInfo, capture::layout::crop_frame,
+    processing::color::parse_color,
+};
+use async_trait::async_trait;
+use image::{Rgba, RgbaImage};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MockContent {
+    Solid(Rgba<u8>),
+    Gradient,
+    Checkerboard(u32),
+}
+
+impl std::str::FromStr for MockContent {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, value) = s.split_once(':').unwrap_or((s, ""));
+
+        match (kind.to_lowercase().as_str(), value) {
+            ("gradient", "") => Ok(Self::Gradient),
+            ("checkerboard", "") => Ok(Self::Checkerboard(32)),
+            ("checkerboard", size) => size
+                .parse()
+                .ok()
+                .filter(|size| *size > 0)
+                .map(Self::Checkerboard)
+                .ok_or_else(|| format!("Invalid checkerboard size: {}", size)),
+            ("solid", color) => parse_color(color)
+                .map(Self::Solid)
+                .ok_or_else(|| format!("Invalid color: {}", color)),
+            _ => Err(format!("Invalid mock content: {}", s)),
+        }
+    }
+}
+
+pub struct MockBackend {
+    width: u32,
+    height: u32,
+    content: MockContent,
+    windows: Vec<WindowInfo>,
+    active: Option<usize>,
+}
+
+impl MockBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            content: MockContent::Gradient,
+            windows: Vec::new(),
+            active: None,
+        }
+    }
+
+    pub fn content(mut self, content: MockContent) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn windows(mut self, windows: Vec<WindowInfo>) -> Self {
+        self.active = (!windows.is_empty()).then_some(0);
+        self.windows = windows;
+        self
+    }
+
+    pub fn active_window(mut self, index: impl Into<Option<usize>>) -> Self {
+        self.active = index.into();
+        self
+    }
+
+    // JSWST_MOCK_SIZE=WxH, JSWST_MOCK_CONTENT=solid:#rrggbb|gradient|checkerboard[:size],
+    // JSWST_MOCK_WINDOWS=a JSON array of windows, inline or as a file path.
+    pub fn from_env() -> Result<Self> {
+        let (width, height) = match std::env::var("JSWST_MOCK_SIZE") {
+            Ok(size) => size
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .ok_or_else(|| {
+                    ScreenshotError::Config(format!("Invalid JSWST_MOCK_SIZE: {}", size))
+                })?,
+            Err(_) => (1920, 1080),
+        };
+        let mut backend = Self::new(width, height);
+
+        if let Ok(content) = std::env::var("JSWST_MOCK_CONTENT") {
+            backend = backend.content(content.parse().map_err(ScreenshotError::Config)?);
+        }
+
+        if let Ok(windows) = std::env::var("JSWST_MOCK_WINDOWS") {
+            let json = if windows.trim_start().starts_with('[') {
+                windows
+            } else {
+                std::fs::read_to_string(&windows)?
+            };
+            backend = backend.windows(serde_json::from_str(&json)?);
+        }
+
+        Ok(backend)
+    }
+
+    pub fn frame(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| match self.content {
+            MockContent::Solid(color) => color,
+            MockContent::Gradient => Rgba([
+                (x * 255 / self.width.max(1)) as u8,
+                (y * 255 / self.height.max(1)) as u8,
+                128,
+                255,
+            ]),
+            MockContent::Checkerboard(size) => {
+                if (x / size + y / size) % 2 == 0 {
+                    Rgba([255, 255, 255, 255])
+                } else {
+                    Rgba([0, 0, 0, 255])
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl ScreenshotBackend for MockBackend {
+    async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        // There is nobody to pick a window, so the active one stands in.
+        let target = match mode {
+            CaptureMode::Window if options.region.is_none() => CaptureMode::ActiveWindow,
+            mode => mode,
+        };
+        let mut screenshot = crop_frame(self, self.frame(), target, options).await?;
+        screenshot.metadata.mode = mode;
+        screenshot.metadata.compositor = Some("mock".to_string());
+        screenshot.metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
+
+        Ok(screenshot)
+    }
+
+    async fn get_displays(&self) -> Result<Vec<Display>> {
+        Ok(vec![Display {
+            name: "MOCK-1".to_string(),
+            width: self.width,
+            height: self.height,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            is_primary: true,
+            transform: Transform::Normal,
+        }])
+    }
+
+    async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
+        Ok(self
+            .active
+            .and_then(|index| self.windows.get(index).cloned()))
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(self.windows.clone())
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            cursor: false,
+            per_output: true,
+            window: true,
+            non_interactive: true,
+        }
+    }
+}
@@ -1,5 +1,37 @@
+#[cfg(feature = "accessibility")]
+pub mod accessibility;
+pub mod cache;
+pub mod command;
+pub mod compositor;
 pub mod factory;
+#[cfg(feature = "kwin")]
+pub mod kwin;
+pub mod layout;
+#[cfg(feature = "testing")]
+pub mod mock;
+pub mod privacy;
+pub mod request;
+pub mod synthetic;
 pub mod wayland;
+pub mod window;
+#[cfg(feature = "x11")]
+pub mod x11;
 
+#[cfg(feature = "accessibility")]
+pub use accessibility::{AccessibilityBus, WidgetQuery};
+pub use cache::FrameCache;
+pub use command::CommandBackend;
+pub use compositor::Compositor;
 pub use factory::create_backend;
+#[cfg(feature = "kwin")]
+pub use kwin::KWinBackend;
+pub use layout::{Layout, RegionAnchor, RelativeRegion};
+#[cfg(feature = "testing")]
+pub use mock::{MockBackend, MockContent};
+pub use privacy::{mask_private_area, mask_private_windows};
+pub use request::CaptureRequest;
+pub use synthetic::SyntheticBackend;
 pub use wayland::WaylandBackend;
+pub use window::WindowMatcher;
+#[cfg(feature = "x11")]
+pub use x11::X11Backend;
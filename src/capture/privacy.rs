@@ -0,0 +1,91 @@
+use crate::{
+    CaptureMode, LogicalRegion, Region, Result, Screenshot, ScreenshotBackend, WindowInfo,
+    capture::Layout,
+    config::{PrivacyConfig, PrivacyStyle},
+    processing::ImageProcessor,
+};
+use image::Rgba;
+
+const PIXELATE_BLOCK: u32 = 16;
+
+// Only screen and monitor captures are masked here; a single region or window
+// capture already shows exactly what the user picked.
+pub async fn mask_private_windows(
+    backend: &dyn ScreenshotBackend,
+    screenshot: Screenshot,
+    config: &PrivacyConfig,
+) -> Result<Screenshot> {
+    if config.mask_apps.is_empty()
+        || !matches!(
+            screenshot.metadata.mode,
+            CaptureMode::Screen | CaptureMode::Monitor
+        )
+    {
+        return Ok(screenshot);
+    }
+
+    let windows = match backend.get_windows().await {
+        Ok(windows) => windows,
+        Err(e) => {
+            tracing::warn!("Cannot mask private windows: {}", e);
+            return Ok(screenshot);
+        }
+    };
+    if !windows.iter().any(|window| config.hides(window)) {
+        return Ok(screenshot);
+    }
+
+    let displays = backend.get_displays().await?;
+    let origin = match screenshot.metadata.mode {
+        CaptureMode::Monitor => screenshot
+            .metadata
+            .monitor
+            .as_deref()
+            .and_then(|name| displays.iter().find(|display| display.name == name))
+            .map(|display| display.logical_region().0),
+        _ => None,
+    }
+    .unwrap_or_else(|| Layout::new(&displays).bounds().0);
+
+    mask_private_area(screenshot, &windows, config, LogicalRegion(origin))
+}
+
+// Masks the private windows that overlap a capture known to show `area` of
+// the desktop, as batch region and per-window captures are.
+pub fn mask_private_area(
+    screenshot: Screenshot,
+    windows: &[WindowInfo],
+    config: &PrivacyConfig,
+    area: LogicalRegion,
+) -> Result<Screenshot> {
+    let origin = area.0;
+    let hidden: Vec<Region> = windows
+        .iter()
+        .filter(|window| config.hides(window))
+        .filter_map(|window| window.region().intersect(&origin))
+        .collect();
+    if hidden.is_empty() {
+        return Ok(screenshot);
+    }
+
+    let scale = screenshot.width() as f64 / origin.width.max(1) as f64;
+    let mut masked = screenshot;
+    for region in hidden {
+        let region = Region::new(
+            ((region.x - origin.x) as f64 * scale).floor() as i32,
+            ((region.y - origin.y) as f64 * scale).floor() as i32,
+            (region.width as f64 * scale).ceil() as u32,
+            (region.height as f64 * scale).ceil() as u32,
+        );
+
+        masked = match config.style {
+            PrivacyStyle::Black => ImageProcessor::mask(&masked, &[region], Rgba([0, 0, 0, 255]))?,
+            // Windows on another monitor fall outside the frame.
+            PrivacyStyle::Pixelate => {
+                ImageProcessor::pixelate(&masked, Some(region), PIXELATE_BLOCK).unwrap_or(masked)
+            }
+        };
+    }
+
+    Ok(masked)
+}
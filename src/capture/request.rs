@@ -0,0 +1,34 @@
+use crate::{
+    CaptureMode, CaptureOptions, LogicalRegion, Result, Screenshot, ScreenshotBackend,
+    capture::create_backend, config::CaptureConfig,
+};
+
+// One capture as the CLI and the overlay both describe it, so neither has to
+// rebuild the other's arguments.
+#[derive(Debug, Clone)]
+pub struct CaptureRequest {
+    pub mode: CaptureMode,
+    pub options: CaptureOptions,
+}
+
+impl CaptureRequest {
+    pub fn new(mode: CaptureMode, options: CaptureOptions) -> Self {
+        Self { mode, options }
+    }
+
+    pub fn region(region: LogicalRegion) -> Self {
+        Self::new(
+            CaptureMode::Region,
+            CaptureOptions::builder().region(region).build(),
+        )
+    }
+
+    pub async fn run(&self, config: &CaptureConfig) -> Result<Screenshot> {
+        let backend = create_backend(config)?;
+        self.run_with(backend.as_ref()).await
+    }
+
+    pub async fn run_with(&self, backend: &dyn ScreenshotBackend) -> Result<Screenshot> {
+        backend.capture(self.mode, &self.options).await
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, Result, Screenshot,
+    ScreenshotBackend, ScreenshotError, Transform, WindowInfo, capture::layout::crop_frame,
+};
+use async_trait::async_trait;
+use image::{Rgba, RgbaImage};
+
+// Produces the same frame for the same size and seed, so benchmarks and
+// tests can run without a compositor.
+pub struct SyntheticBackend {
+    width: u32,
+    height: u32,
+    seed: u64,
+}
+
+impl SyntheticBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::with_seed(width, height, 0)
+    }
+
+    pub fn with_seed(width: u32, height: u32, seed: u64) -> Self {
+        Self {
+            width,
+            height,
+            seed,
+        }
+    }
+
+    // A gradient with a little noise on top, which compresses roughly like
+    // a real desktop rather than like a flat fill.
+    pub fn frame(&self) -> RgbaImage {
+        let mut state = self.seed ^ 0x9e37_79b9_7f4a_7c15;
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let noise = (state & 0x0f) as u8;
+
+            let r = (x * 255 / self.width.max(1)) as u8;
+            let g = (y * 255 / self.height.max(1)) as u8;
+            let b = ((x / 64 + y / 64) % 2 * 96) as u8;
+            Rgba([
+                r.saturating_add(noise),
+                g.saturating_add(noise),
+                b.saturating_add(noise),
+                255,
+            ])
+        })
+    }
+}
+
+#[async_trait]
+impl ScreenshotBackend for SyntheticBackend {
+    async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
+        if matches!(mode, CaptureMode::Window) && options.region.is_none() {
+            return Err(ScreenshotError::CaptureFailed(
+                "The synthetic backend has no windows".to_string(),
+            ));
+        }
+
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        let mut screenshot = crop_frame(self, self.frame(), mode, options).await?;
+        screenshot.metadata.compositor = Some("synthetic".to_string());
+        screenshot.metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
+
+        Ok(screenshot)
+    }
+
+    async fn get_displays(&self) -> Result<Vec<Display>> {
+        Ok(vec![Display {
+            name: "SYNTHETIC-1".to_string(),
+            width: self.width,
+            height: self.height,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            is_primary: true,
+            transform: Transform::Normal,
+        }])
+    }
+
+    async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
+        Ok(None)
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            cursor: false,
+            per_output: true,
+            window: false,
+            non_interactive: true,
+        }
+    }
+}
@@ -1,99 +1,172 @@
 use crate::{
-    CaptureMode, CaptureOptions, Display, OutputFormat, Result, Screenshot, ScreenshotBackend,
-    ScreenshotError, WindowInfo,
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, Result, Screenshot,
+    ScreenshotBackend, ScreenshotError, WindowInfo,
+    capture::{CommandBackend, Compositor, layout::crop_frame},
+    config::CaptureConfig,
 };
 use async_trait::async_trait;
 use image::RgbaImage;
-
-pub struct WaylandBackend;
+use std::path::Path;
+use std::time::Duration;
+
+pub struct WaylandBackend {
+    portal_timeout: Option<Duration>,
+    portal_retries: u32,
+    interactive_timeout: Option<Duration>,
+    keep_portal_files: bool,
+    fallback: Option<CommandBackend>,
+}
 
 impl WaylandBackend {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Self::with_config(&CaptureConfig::default())
+    }
+
+    pub fn with_config(config: &CaptureConfig) -> Result<Self> {
+        Ok(Self {
+            portal_timeout: (config.portal_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.portal_timeout_secs)),
+            portal_retries: config.portal_retries,
+            interactive_timeout: (config.interactive_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.interactive_timeout_secs)),
+            keep_portal_files: config.keep_portal_files,
+            fallback: config
+                .fallback_command
+                .enabled
+                .then(|| CommandBackend::new(&config.fallback_command)),
+        })
     }
 
     async fn capture_via_portal(&self, interactive: bool) -> Result<RgbaImage> {
+        let result = self.request_with_timeout(interactive).await;
+
+        match (&self.fallback, result) {
+            (Some(fallback), Err(e)) if !interactive => {
+                tracing::warn!(
+                    "Screenshot portal failed ({}); using the capture command",
+                    e
+                );
+                fallback.grab().await.map_err(|fallback_error| {
+                    tracing::warn!("{}", fallback_error);
+                    e
+                })
+            }
+            (_, result) => result,
+        }
+    }
+
+    // An interactive request waits on the user, so it gets its own, longer
+    // timeout and is never retried.
+    async fn request_with_timeout(&self, interactive: bool) -> Result<RgbaImage> {
+        let (timeout, retries) = if interactive {
+            (self.interactive_timeout, 0)
+        } else {
+            (self.portal_timeout, self.portal_retries)
+        };
+        let Some(timeout) = timeout else {
+            return self.request_portal(interactive).await;
+        };
+
+        let mut attempt = 0;
+        loop {
+            match tokio::time::timeout(timeout, self.request_portal(interactive)).await {
+                Ok(result) => return result,
+                Err(_) if attempt < retries => {
+                    attempt += 1;
+                    tracing::warn!("Screenshot portal timed out; retrying");
+                }
+                Err(_) => {
+                    return Err(ScreenshotError::PortalTimeout(timeout.as_secs()));
+                }
+            }
+        }
+    }
+
+    async fn request_portal(&self, interactive: bool) -> Result<RgbaImage> {
         use ashpd::desktop::screenshot::ScreenshotRequest;
 
         let response = ScreenshotRequest::default()
             .interactive(interactive)
             .send()
-            .await
-            .map_err(|e| ScreenshotError::Portal(e.to_string()))?
-            .response()
-            .map_err(|e| ScreenshotError::Portal(e.to_string()))?;
+            .await?
+            .response()?;
 
         let uri = response.uri();
         let path = uri
             .to_file_path()
             .map_err(|_| ScreenshotError::Portal("Invalid file path".to_string()))?;
 
-        let bytes = tokio::fs::read(&path)
-            .await
-            .map_err(|e| ScreenshotError::Io(e))?;
-
-        let img = image::load_from_memory(&bytes).map_err(ScreenshotError::Image)?;
+        load_portal_file(&path, self.keep_portal_files)
+    }
+}
 
-        let _ = tokio::fs::remove_file(path).await;
+// The portal leaves its file behind in ~/Pictures or /run, so it is removed
+// whether or not it decodes.
+fn load_portal_file(path: &Path, keep: bool) -> Result<RgbaImage> {
+    let img = image::open(path).map_err(ScreenshotError::Image);
 
-        Ok(img.to_rgba8())
+    if keep {
+        tracing::debug!("Keeping portal file {}", path.display());
+    } else if let Err(e) = std::fs::remove_file(path) {
+        tracing::warn!("Failed to remove portal file {}: {}", path.display(), e);
     }
+
+    Ok(img?.to_rgba8())
 }
 
 #[async_trait]
 impl ScreenshotBackend for WaylandBackend {
     async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
-        if let Some(delay) = options.delay {
-            tokio::time::sleep(delay).await;
-        }
-
-        let data = match mode {
-            CaptureMode::Screen => self.capture_via_portal(false).await?,
-            CaptureMode::Window | CaptureMode::Region => self.capture_via_portal(true).await?,
-            CaptureMode::Monitor => self.capture_via_portal(false).await?,
-        };
-
-        let data = if let Some(region) = options.region {
-            let region = region.normalize();
-
-            if region.x < 0
-                || region.y < 0
-                || region.x as u32 + region.width > data.width()
-                || region.y as u32 + region.height > data.height()
-            {
-                return Err(ScreenshotError::InvalidRegion(
-                    "Region out of bounds".to_string(),
-                ));
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        let frame = match mode {
+            // The geometry is already known; grab the screen and crop below
+            // rather than asking the user to select it again.
+            CaptureMode::Region | CaptureMode::Freeform if options.region.is_some() => {
+                self.capture_via_portal(false).await?
+            }
+            CaptureMode::Window | CaptureMode::Region | CaptureMode::Freeform => {
+                self.capture_via_portal(true).await?
+            }
+            CaptureMode::Screen | CaptureMode::Monitor | CaptureMode::ActiveWindow => {
+                self.capture_via_portal(false).await?
             }
-
-            image::imageops::crop_imm(
-                &data,
-                region.x as u32,
-                region.y as u32,
-                region.width,
-                region.height,
-            )
-            .to_image()
-        } else {
-            data
         };
+        let mut screenshot = crop_frame(self, frame, mode, options).await?;
+        screenshot.metadata.compositor = Compositor::detect().name();
+        screenshot.metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
 
-        Ok(Screenshot::new(data, mode, OutputFormat::Png))
+        Ok(screenshot)
     }
 
     async fn get_displays(&self) -> Result<Vec<Display>> {
-        Ok(vec![Display {
-            name: "Primary Display".to_string(),
-            width: 1920,
-            height: 1080,
-            x: 0,
-            y: 0,
-            scale: 1.0,
-            is_primary: true,
-        }])
+        // An unknown layout stays empty, so regions are taken as frame pixels.
+        Ok(Compositor::detect().displays().unwrap_or_default())
     }
 
     async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
-        Ok(None)
+        Compositor::detect().active_window()
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        Compositor::detect().windows()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        let knows_layout = Compositor::detect() != Compositor::Unknown;
+
+        BackendCapabilities {
+            cursor: false,
+            per_output: knows_layout,
+            window: knows_layout,
+            non_interactive: true,
+        }
     }
 }
@@ -0,0 +1,82 @@
+use crate::WindowInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchField {
+    Title,
+    AppId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    Contains,
+    Exact,
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowMatcher {
+    pub field: MatchField,
+    pub op: MatchOp,
+    pub value: String,
+}
+
+impl WindowMatcher {
+    pub fn app_id(app_id: &str) -> Self {
+        Self {
+            field: MatchField::AppId,
+            op: MatchOp::Exact,
+            value: app_id.to_string(),
+        }
+    }
+
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        let subject = match self.field {
+            MatchField::Title => &window.title,
+            MatchField::AppId => &window.app_id,
+        };
+
+        match self.op {
+            MatchOp::Contains => subject.to_lowercase().contains(&self.value.to_lowercase()),
+            MatchOp::Exact => *subject == self.value,
+        }
+    }
+}
+
+impl std::str::FromStr for WindowMatcher {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let split = s
+            .find(['~', '='])
+            .map(|i| (&s[..i], &s[i..i + 1], &s[i + 1..]));
+
+        let Some((field, op, value)) = split else {
+            return Ok(Self {
+                field: MatchField::Title,
+                op: MatchOp::Contains,
+                value: s.to_string(),
+            });
+        };
+
+        let field = match field.trim().to_lowercase().as_str() {
+            "title" | "name" => MatchField::Title,
+            "app_id" | "app-id" | "appid" | "class" => MatchField::AppId,
+            other => return Err(format!("Invalid match field: {}", other)),
+        };
+
+        let op = if op == "~" {
+            MatchOp::Contains
+        } else {
+            MatchOp::Exact
+        };
+
+        if value.is_empty() {
+            return Err(format!("Empty match pattern: {}", s));
+        }
+
+        Ok(Self {
+            field,
+            op,
+            value: value.to_string(),
+        })
+    }
+}
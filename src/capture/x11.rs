@@ -0,0 +1,378 @@
+use crate::{
+    BackendCapabilities, CaptureEvent, CaptureMode, CaptureOptions, Display, OutputFormat, Region,
+    Result, Screenshot, ScreenshotBackend, ScreenshotError, Transform, WindowInfo,
+};
+use async_trait::async_trait;
+use image::RgbaImage;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::shm::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ConnectionExt as _, ImageFormat, ImageOrder, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+pub struct X11Backend {
+    conn: RustConnection,
+    root: Window,
+    width: u32,
+    height: u32,
+    shm: bool,
+}
+
+impl X11Backend {
+    pub fn new() -> Result<Self> {
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let (root, width, height) = {
+            let screen = &conn.setup().roots[screen];
+            (
+                screen.root,
+                screen.width_in_pixels as u32,
+                screen.height_in_pixels as u32,
+            )
+        };
+        let shm = conn
+            .extension_information(shm::X11_EXTENSION_NAME)
+            .map_err(x11_error)?
+            .is_some();
+
+        Ok(Self {
+            conn,
+            root,
+            width,
+            height,
+            shm,
+        })
+    }
+
+    fn grab(&self, region: Region) -> Result<RgbaImage> {
+        let region = region
+            .intersect(&Region::new(0, 0, self.width, self.height))
+            .ok_or_else(|| ScreenshotError::InvalidRegion("Region out of bounds".to_string()))?;
+
+        // A remote server can have MIT-SHM without sharing our memory.
+        let (depth, data) = if self.shm {
+            self.get_image_shm(region).or_else(|e| {
+                tracing::debug!("MIT-SHM capture failed ({}); using GetImage", e);
+                self.get_image(region)
+            })?
+        } else {
+            self.get_image(region)?
+        };
+
+        // 24 and 32 bit visuals are both padded to four bytes per pixel with
+        // eight bits a channel; 30-bit ones pack ten and are not handled.
+        if !matches!(depth, 24 | 32) {
+            return Err(ScreenshotError::CaptureFailed(format!(
+                "Unsupported X11 visual depth {}",
+                depth
+            )));
+        }
+
+        let lsb_first = self.conn.setup().image_byte_order == ImageOrder::LSB_FIRST;
+        let pixels = data
+            .chunks_exact(4)
+            .flat_map(|px| {
+                if lsb_first {
+                    [px[2], px[1], px[0], 255]
+                } else {
+                    [px[1], px[2], px[3], 255]
+                }
+            })
+            .collect();
+
+        RgbaImage::from_raw(region.width, region.height, pixels).ok_or_else(|| {
+            ScreenshotError::CaptureFailed("X server returned a short image".to_string())
+        })
+    }
+
+    fn get_image(&self, region: Region) -> Result<(u8, Vec<u8>)> {
+        let reply = self
+            .conn
+            .get_image(
+                ImageFormat::Z_PIXMAP,
+                self.root,
+                region.x as i16,
+                region.y as i16,
+                region.width as u16,
+                region.height as u16,
+                !0,
+            )
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        Ok((reply.depth, reply.data))
+    }
+
+    // The server writes the pixels into a shared segment instead of sending
+    // them over the socket, which is much faster for full-screen grabs.
+    fn get_image_shm(&self, region: Region) -> Result<(u8, Vec<u8>)> {
+        let size = region.width as usize * region.height as usize * 4;
+        let id = unsafe { libc::shmget(libc::IPC_PRIVATE, size, libc::IPC_CREAT | 0o600) };
+        if id < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let addr = unsafe { libc::shmat(id, std::ptr::null(), libc::SHM_RDONLY) };
+        if addr as isize == -1 {
+            let error = std::io::Error::last_os_error();
+            unsafe { libc::shmctl(id, libc::IPC_RMID, std::ptr::null_mut()) };
+            return Err(error.into());
+        }
+
+        let result = (|| {
+            let segment = self.conn.generate_id().map_err(x11_error)?;
+            let attached = self
+                .conn
+                .shm_attach(segment, id as u32, false)
+                .map_err(x11_error)?
+                .check()
+                .map_err(x11_error);
+            // Once both sides are attached the segment can be marked for
+            // removal; it goes away when the last one detaches.
+            unsafe { libc::shmctl(id, libc::IPC_RMID, std::ptr::null_mut()) };
+            attached?;
+
+            let reply = self
+                .conn
+                .shm_get_image(
+                    self.root,
+                    region.x as i16,
+                    region.y as i16,
+                    region.width as u16,
+                    region.height as u16,
+                    !0,
+                    ImageFormat::Z_PIXMAP.into(),
+                    segment,
+                    0,
+                )
+                .map_err(x11_error)?
+                .reply()
+                .map_err(x11_error);
+            let _ = self.conn.shm_detach(segment);
+            let reply = reply?;
+
+            let len = (reply.size as usize).min(size);
+            let data = unsafe { std::slice::from_raw_parts(addr as *const u8, len) }.to_vec();
+            Ok((reply.depth, data))
+        })();
+        unsafe { libc::shmdt(addr) };
+
+        result
+    }
+
+    fn atom(&self, name: &str) -> Result<Atom> {
+        Ok(self
+            .conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .atom)
+    }
+
+    fn property(&self, window: Window, property: Atom, kind: Atom) -> Result<Vec<u8>> {
+        Ok(self
+            .conn
+            .get_property(false, window, property, kind, 0, u32::MAX / 4)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .value)
+    }
+
+    fn root_windows(&self, name: &str) -> Result<Vec<Window>> {
+        let reply = self
+            .conn
+            .get_property(
+                false,
+                self.root,
+                self.atom(name)?,
+                AtomEnum::WINDOW,
+                0,
+                4096,
+            )
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        Ok(reply
+            .value32()
+            .map(|values| values.filter(|window| *window != 0).collect())
+            .unwrap_or_default())
+    }
+
+    fn window_info(&self, window: Window) -> Result<WindowInfo> {
+        let geometry = self
+            .conn
+            .get_geometry(window)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        let origin = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+
+        let title = self.property(
+            window,
+            self.atom("_NET_WM_NAME")?,
+            self.atom("UTF8_STRING")?,
+        )?;
+        let title = if title.is_empty() {
+            self.property(window, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())?
+        } else {
+            title
+        };
+        // WM_CLASS holds the instance and class names, NUL separated.
+        let class = self.property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())?;
+        let app_id = class
+            .split(|byte| *byte == 0)
+            .rfind(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).to_string())
+            .unwrap_or_default();
+
+        Ok(WindowInfo {
+            title: String::from_utf8_lossy(&title).to_string(),
+            app_id,
+            x: origin.dst_x as i32,
+            y: origin.dst_y as i32,
+            width: geometry.width as u32,
+            height: geometry.height as u32,
+            frame: None,
+        })
+    }
+}
+
+#[async_trait]
+impl ScreenshotBackend for X11Backend {
+    async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot> {
+        options.wait_delay().await;
+        options.emit(CaptureEvent::CaptureStarted { mode });
+        let started = std::time::Instant::now();
+
+        let mut monitor = None;
+        let mut window = None;
+        let screen = Region::new(0, 0, self.width, self.height);
+        // X11 has one root window in a single coordinate space, so logical
+        // and physical coordinates coincide.
+        let region = match (mode, options.region) {
+            (_, Some(region)) => region.0,
+            (CaptureMode::Screen, None) => screen,
+            (CaptureMode::Monitor, None) => match options.monitor_index {
+                Some(index) => {
+                    let displays = self.get_displays().await?;
+                    let display = displays.get(index).ok_or(ScreenshotError::NoDisplay)?;
+                    monitor = Some(display.name.clone());
+                    display.logical_region().0
+                }
+                None => screen,
+            },
+            (CaptureMode::ActiveWindow, None) => {
+                let active = self.get_activate_window().await?.ok_or_else(|| {
+                    ScreenshotError::CaptureFailed("No window has focus".to_string())
+                })?;
+                let region = active.region();
+                window = Some(active);
+                region
+            }
+            (CaptureMode::Window | CaptureMode::Region | CaptureMode::Freeform, None) => {
+                return Err(ScreenshotError::CaptureFailed(
+                    "Interactive selection is not supported on X11; pass a region".to_string(),
+                ));
+            }
+        };
+
+        let mut screenshot = Screenshot::new(self.grab(region)?, mode, OutputFormat::Png);
+        let metadata = &mut screenshot.metadata;
+        metadata.monitor = monitor;
+        metadata.window_title = window.as_ref().map(|window| window.title.clone());
+        metadata.app_id = window.map(|window| window.app_id);
+        metadata.compositor = Some("X11".to_string());
+        metadata.scale = Some(1.0);
+        metadata.capture_duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        options.emit(CaptureEvent::CaptureFinished {
+            width: screenshot.width(),
+            height: screenshot.height(),
+        });
+
+        Ok(screenshot)
+    }
+
+    async fn get_displays(&self) -> Result<Vec<Display>> {
+        let monitors = self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .monitors;
+
+        let mut displays = Vec::new();
+        for monitor in monitors {
+            let name = self
+                .conn
+                .get_atom_name(monitor.name)
+                .map_err(x11_error)?
+                .reply()
+                .map_err(x11_error)?
+                .name;
+
+            displays.push(Display {
+                name: String::from_utf8_lossy(&name).to_string(),
+                width: monitor.width as u32,
+                height: monitor.height as u32,
+                x: monitor.x as i32,
+                y: monitor.y as i32,
+                scale: 1.0,
+                is_primary: monitor.primary,
+                transform: Transform::Normal,
+            });
+        }
+
+        if displays.is_empty() {
+            displays.push(Display {
+                name: "Screen".to_string(),
+                width: self.width,
+                height: self.height,
+                x: 0,
+                y: 0,
+                scale: 1.0,
+                is_primary: true,
+                transform: Transform::Normal,
+            });
+        }
+
+        Ok(displays)
+    }
+
+    async fn get_activate_window(&self) -> Result<Option<WindowInfo>> {
+        match self.root_windows("_NET_ACTIVE_WINDOW")?.first() {
+            Some(window) => Ok(Some(self.window_info(*window)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>> {
+        self.root_windows("_NET_CLIENT_LIST")?
+            .into_iter()
+            .map(|window| self.window_info(window))
+            .collect()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            cursor: false,
+            per_output: true,
+            window: true,
+            non_interactive: true,
+        }
+    }
+}
+
+fn x11_error(e: impl std::fmt::Display) -> ScreenshotError {
+    ScreenshotError::CaptureFailed(format!("X11: {}", e))
+}
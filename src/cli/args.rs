@@ -1,25 +1,45 @@
-use crate::{CaptureMode, OutputFormat};
-use clap::{Parser, Subcommand};
+use crate::{
+    CaptureMode, OutputFormat,
+    capture::{RelativeRegion, WindowMatcher},
+    export::LinkFormat,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
 use std::path::PathBuf;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "wst")]
 #[command(author = "Wayland Screenshot Tool")]
 #[command(version = "1.0")]
 #[command(about = "Modern screenshot tool for Wayland", long_about = None)]
+#[command(after_help = "Exit status:
+  0  success
+  1  general failure, or an upload or remote copy failed
+  2  invalid command-line usage
+  3  the portal denied permission
+  4  the portal timed out
+  5  no capture backend, clipboard or helper tool available
+  6  invalid region, format or configuration
+  7  cancelled by the user")]
 pub struct Args {
-    #[arg(value_name = "MODE")]
-    pub mode: Option<String>,
+    #[arg(value_name = "MODE", value_enum, ignore_case = true)]
+    pub mode: Option<CaptureMode>,
 
     #[arg(value_name = "OUTPUT")]
     pub output: Option<PathBuf>,
 
+    #[arg(short = 'o', long = "output", id = "outputs", value_name = "PATH|-")]
+    pub outputs: Vec<PathBuf>,
+
     #[arg(short, long, value_name = "FORMAT")]
     pub format: Option<String>,
 
     #[arg(short, long, value_name = "SECONDS")]
     pub delay: Option<u64>,
 
+    #[arg(long)]
+    pub silent_delay: bool,
+
     #[arg(short = 'c', long)]
     pub clipboard: bool,
 
@@ -32,15 +52,96 @@ pub struct Args {
     #[arg(short, long)]
     pub json: bool,
 
+    #[arg(long, value_name = "FORMAT")]
+    pub format_link: Option<String>,
+
+    #[arg(long, value_name = "PRESET")]
+    pub beautify: Option<String>,
+
+    #[arg(long, value_name = "NAME")]
+    pub pipeline: Option<String>,
+
+    #[arg(long)]
+    pub srgb_convert: bool,
+
+    #[arg(long, value_name = "LEVELS")]
+    pub palette: Option<u32>,
+
+    #[arg(long, value_name = "satty|swappy|auto|PROGRAM")]
+    pub edit_with: Option<String>,
+
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
+
+    #[arg(long)]
+    pub no_metadata: bool,
+
+    #[arg(long)]
+    pub open: bool,
+
+    #[arg(long)]
+    pub reveal: bool,
+
+    #[arg(long, value_name = "TARGET", num_args = 0..=1, default_missing_value = "")]
+    pub upload: Option<String>,
+
+    #[arg(long, value_name = "TEXT", conflicts_with = "no_metadata")]
+    pub comment: Option<String>,
+
     #[arg(short = 'x', long)]
     pub headless: bool,
 
     #[arg(short, long, value_name = "REGION")]
-    pub region: Option<String>,
+    pub region: Vec<String>,
+
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["geometry", "slurp", "region_of"])]
+    pub regions: Option<PathBuf>,
+
+    #[arg(short = 'g', long, value_name = "X,Y WxH", conflicts_with_all = ["region", "slurp"])]
+    pub geometry: Option<String>,
+
+    #[arg(long, conflicts_with = "region")]
+    pub slurp: bool,
+
+    #[arg(long, value_name = "TARGET:REGION", conflicts_with = "region")]
+    pub region_of: Option<String>,
 
     #[arg(short, long, value_name = "INDEX")]
     pub monitor: Option<usize>,
 
+    #[arg(long)]
+    pub all: bool,
+
+    #[arg(long, requires = "all")]
+    pub combined: bool,
+
+    #[arg(long, conflicts_with_all = ["window_match", "app_id"])]
+    pub all_windows: bool,
+
+    #[arg(long, value_name = "DIR", requires = "all_windows")]
+    pub out_dir: Option<PathBuf>,
+
+    #[arg(long = "match", value_name = "PATTERN")]
+    pub window_match: Option<String>,
+
+    #[arg(long, value_name = "APP_ID", conflicts_with = "window_match")]
+    pub app_id: Option<String>,
+
+    #[arg(long, value_name = "QUERY", conflicts_with_all = ["window_match", "app_id"])]
+    pub widget: Option<String>,
+
+    #[arg(long, value_name = "X,Y,W,H|FILE")]
+    pub mask: Vec<String>,
+
+    #[arg(long)]
+    pub auto_redact: bool,
+
+    #[arg(long)]
+    pub include_decorations: bool,
+
+    #[arg(long)]
+    pub exclude_shadow: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 
@@ -48,25 +149,60 @@ pub struct Args {
     pub verbose: bool,
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(Subcommand, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     #[cfg(feature = "gui")]
     Gui,
 
+    #[cfg(feature = "gui")]
+    Preferences,
+
     #[cfg(feature = "gui")]
     Edit {
-        file: PathBuf,
+        #[arg(conflicts_with = "from_clipboard")]
+        file: Option<PathBuf>,
+
+        #[arg(long)]
+        from_clipboard: bool,
+
+        #[arg(long, value_name = "FILE")]
+        annotations: Option<PathBuf>,
     },
 
     List {
-        #[arg(default_value = "displays")]
-        what: String,
+        #[arg(value_enum, default_value_t = ListTarget::Displays, ignore_case = true)]
+        what: ListTarget,
     },
 
     Completions {
         shell: String,
     },
 
+    Watch {
+        #[arg(long, value_name = "SECONDS")]
+        interval: Option<u64>,
+
+        #[arg(long)]
+        count: Option<u32>,
+
+        #[arg(long, value_name = "MINUTES")]
+        skip_idle: Option<u64>,
+    },
+
+    Seq {
+        #[command(subcommand)]
+        action: SeqAction,
+    },
+
+    Path {
+        #[arg(long, conflicts_with = "dir")]
+        last: bool,
+
+        #[arg(long)]
+        dir: bool,
+    },
+
     Config {
         #[arg(long)]
         show: bool,
@@ -78,6 +214,94 @@ pub enum Commands {
         edit: bool,
     },
 
+    Diff {
+        expected: PathBuf,
+
+        actual: PathBuf,
+
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        #[arg(long, value_name = "PIXELS")]
+        aa_tolerance: Option<u32>,
+
+        #[arg(long, value_name = "PIXELS")]
+        max_diff_pixels: Option<u64>,
+
+        #[arg(long, value_name = "RATIO")]
+        max_diff_ratio: Option<f64>,
+
+        #[arg(long, value_name = "X,Y,W,H")]
+        ignore: Vec<String>,
+
+        #[arg(long, value_name = "X,Y,W,H|FILE")]
+        mask: Vec<String>,
+    },
+
+    Montage {
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+
+        #[arg(long)]
+        columns: Option<u32>,
+
+        #[arg(long, default_value_t = 16)]
+        spacing: u32,
+
+        #[arg(long)]
+        no_labels: bool,
+    },
+
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    Upload {
+        file: PathBuf,
+
+        #[arg(long, value_name = "TARGET")]
+        to: Option<String>,
+    },
+
+    Daemon,
+
+    Serve {
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+
+        #[arg(long, value_name = "TOKEN")]
+        token: Option<String>,
+    },
+
+    Flameshot {
+        #[command(subcommand)]
+        action: FlameshotAction,
+    },
+
+    Print {
+        file: Option<PathBuf>,
+    },
+
+    Scan {
+        file: Option<PathBuf>,
+
+        #[arg(short, long, value_name = "X,Y,W,H", conflicts_with = "file")]
+        region: Option<String>,
+
+        #[arg(long)]
+        open: bool,
+
+        #[arg(long)]
+        copy: bool,
+    },
+
     Process {
         input: PathBuf,
 
@@ -94,30 +318,272 @@ pub enum Commands {
 
         #[arg(long)]
         blur: Option<f32>,
+
+        #[arg(long, value_name = "DEGREES")]
+        rotate: Option<u32>,
+
+        #[arg(long, value_name = "h|v")]
+        flip: Option<String>,
+
+        #[arg(long, value_name = "TOLERANCE", num_args = 0..=1, default_missing_value = "0")]
+        auto_trim: Option<u8>,
+
+        #[arg(long, allow_hyphen_values = true)]
+        brightness: Option<f32>,
+
+        #[arg(long)]
+        contrast: Option<f32>,
+
+        #[arg(long)]
+        saturation: Option<f32>,
+
+        #[arg(long)]
+        gamma: Option<f32>,
+
+        #[arg(long)]
+        grayscale: bool,
+
+        #[arg(long)]
+        invert: bool,
+
+        #[arg(long, value_name = "RADIUS")]
+        round_corners: Option<u32>,
+
+        #[arg(long, value_name = "PIXELS")]
+        padding: Option<u32>,
+
+        #[arg(long, value_name = "BACKGROUND", requires = "padding")]
+        background: Option<String>,
+
+        #[arg(long, value_name = "PRESET")]
+        beautify: Option<String>,
+
+        #[arg(long, value_name = "NAME")]
+        pipeline: Option<String>,
+
+        #[arg(long = "processor", value_name = "NAME[:PARAMS]")]
+        processors: Vec<String>,
+
+        #[arg(long, value_name = "browser|phone|laptop|NAME")]
+        frame: Option<String>,
+
+        #[arg(long, value_name = "URL", requires = "frame")]
+        frame_url: Option<String>,
+
+        #[arg(long, value_name = "macos|gnome", num_args = 0..=1, default_missing_value = "")]
+        chrome: Option<String>,
+
+        #[arg(long, value_name = "TEXT", requires = "chrome")]
+        chrome_title: Option<String>,
+
+        #[arg(long, value_name = "FILE", num_args = 1..)]
+        merge_pdf: Vec<PathBuf>,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListTarget {
+    #[value(alias = "monitors")]
+    Displays,
+    Windows,
+    Capabilities,
+    Presets,
+    Processors,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AuthAction {
+    Login { target: String },
+
+    Logout { target: String },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum FlameshotAction {
+    Gui(FlameshotArgs),
+
+    Full(FlameshotArgs),
+
+    Screen {
+        #[arg(short, long)]
+        number: Option<usize>,
+
+        #[command(flatten)]
+        args: FlameshotArgs,
     },
 }
 
+#[derive(clap::Args, Debug, Clone)]
+pub struct FlameshotArgs {
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    #[arg(short, long)]
+    pub clipboard: bool,
+
+    #[arg(short, long, value_name = "MILLISECONDS")]
+    pub delay: Option<u64>,
+
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SeqAction {
+    Start {
+        name: String,
+
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    Next,
+
+    Stop,
+
+    Status,
+}
+
 impl Args {
+    // `grim -g GEOMETRY FILE` puts the file where wst expects the mode, which
+    // clap rejects; retry with an explicit region mode before giving up.
+    pub fn parse_with_grim_compat() -> Self {
+        let argv: Vec<OsString> = std::env::args_os().collect();
+        let error = match Self::try_parse_from(&argv) {
+            Ok(args) => return args,
+            Err(error) => error,
+        };
+
+        let has_geometry = argv.iter().skip(1).any(|arg| {
+            let arg = arg.to_string_lossy();
+            arg == "-g" || arg == "--geometry" || arg == "--slurp" || arg.starts_with("--geometry=")
+        });
+        let invalid = match error.get(clap::error::ContextKind::InvalidValue) {
+            Some(clap::error::ContextValue::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+
+        let retried = invalid
+            .filter(|_| has_geometry && error.kind() == clap::error::ErrorKind::InvalidValue)
+            .and_then(|value| argv.iter().skip(1).position(|arg| *arg == *value))
+            .and_then(|index| {
+                let mut argv = argv.clone();
+                argv.insert(index + 1, "region".into());
+                Self::try_parse_from(argv).ok()
+            });
+
+        retried.unwrap_or_else(|| error.exit())
+    }
+
     pub fn parse_mode(&self) -> Option<CaptureMode> {
-        self.mode.as_ref().and_then(|m| m.parse().ok())
+        self.mode
+    }
+
+    pub fn parse_format(&self) -> crate::Result<Option<OutputFormat>> {
+        self.format
+            .as_ref()
+            .map(|f| {
+                f.parse()
+                    .map_err(|_| crate::ScreenshotError::UnsupportedFormat { format: f.clone() })
+            })
+            .transpose()
     }
 
-    pub fn parse_format(&self) -> Option<OutputFormat> {
-        self.format.as_ref().and_then(|f| f.parse().ok())
+    pub fn parse_link_format(&self) -> crate::Result<Option<LinkFormat>> {
+        self.format_link
+            .as_ref()
+            .map(|f| f.parse().map_err(crate::ScreenshotError::Config))
+            .transpose()
     }
 
-    pub fn parse_region(&self) -> Option<crate::Region> {
-        self.region.as_ref().and_then(|r| {
-            let parts: Vec<&str> = r.split(',').collect();
-            if parts.len() == 4 {
-                let x = parts[0].parse().ok()?;
-                let y = parts[1].parse().ok()?;
-                let width = parts[2].parse().ok()?;
-                let height = parts[3].parse().ok()?;
-                Some(crate::Region::new(x, y, width, height))
-            } else {
-                None
-            }
-        })
+    pub fn parse_window_matcher(&self) -> crate::Result<Option<WindowMatcher>> {
+        if let Some(app_id) = &self.app_id {
+            return Ok(Some(WindowMatcher::app_id(app_id)));
+        }
+
+        self.window_match
+            .as_ref()
+            .map(|pattern| pattern.parse().map_err(crate::ScreenshotError::Config))
+            .transpose()
+    }
+
+    pub fn has_percent_region(&self) -> crate::Result<bool> {
+        Ok(self.region_specs()?.iter().any(|entry| match entry {
+            RegionEntry::Spec(spec) => spec.contains('%'),
+            RegionEntry::Region(_) => false,
+        }))
+    }
+
+    pub fn parse_regions(
+        &self,
+        output: Option<&crate::Display>,
+    ) -> crate::Result<Vec<crate::LogicalRegion>> {
+        let bounds = output.map(|display| display.logical_region().0);
+
+        self.region_specs()?
+            .into_iter()
+            .map(|entry| match entry {
+                RegionEntry::Region(region) => Ok(crate::LogicalRegion(region)),
+                RegionEntry::Spec(spec) => crate::Region::parse_in(&spec, bounds.as_ref())
+                    .map(crate::LogicalRegion)
+                    .map_err(crate::ScreenshotError::InvalidRegion),
+            })
+            .collect()
+    }
+
+    fn region_specs(&self) -> crate::Result<Vec<RegionEntry>> {
+        let mut entries: Vec<_> = self.region.iter().cloned().map(RegionEntry::Spec).collect();
+
+        if let Some(path) = &self.regions {
+            let contents = std::fs::read_to_string(path)?;
+            entries.extend(serde_json::from_str::<Vec<RegionEntry>>(&contents)?);
+        }
+
+        Ok(entries)
+    }
+
+    pub fn parse_masks(&self) -> crate::Result<Vec<crate::Region>> {
+        parse_masks(&self.mask)
+    }
+
+    pub fn parse_region_of(&self) -> crate::Result<Option<RelativeRegion>> {
+        self.region_of
+            .as_ref()
+            .map(|r| r.parse().map_err(crate::ScreenshotError::InvalidRegion))
+            .transpose()
     }
 }
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RegionEntry {
+    Region(crate::Region),
+    Spec(String),
+}
+
+pub fn parse_masks(values: &[String]) -> crate::Result<Vec<crate::Region>> {
+    let mut masks = Vec::new();
+
+    for value in values {
+        if value.parse::<crate::Region>().is_ok() || !std::path::Path::new(value).is_file() {
+            masks.push(
+                value
+                    .parse()
+                    .map_err(crate::ScreenshotError::InvalidRegion)?,
+            );
+            continue;
+        }
+
+        let contents = std::fs::read_to_string(value)?;
+        for entry in serde_json::from_str::<Vec<RegionEntry>>(&contents)? {
+            masks.push(match entry {
+                RegionEntry::Region(region) => region,
+                RegionEntry::Spec(spec) => spec
+                    .parse()
+                    .map_err(crate::ScreenshotError::InvalidRegion)?,
+            });
+        }
+    }
+
+    Ok(masks)
+}
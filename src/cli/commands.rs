@@ -1,47 +1,365 @@
 use crate::cli::Args;
+use crate::cli::args::{AuthAction, Commands, FlameshotAction, ListTarget, SeqAction, parse_masks};
+use crate::cli::setup;
 use crate::{
-    CaptureMode, CaptureOptions, OutputFormat, Result, ScreenshotError, capture, config::Config,
-    export::Exporter, processing::ImageProcessor,
+    CaptureEvent, CaptureMode, CaptureOptions, LogicalRegion, OutputFormat, Region, Result,
+    Screenshot, ScreenshotBackend, ScreenshotError,
+    capture::{self, CaptureRequest, Compositor, FrameCache, WindowMatcher},
+    config::Config,
+    credentials::Credentials,
+    export::{
+        EmbeddedMetadata, ExportOptions, ExportTarget, ExportTargets, Exporter, RemoteTarget,
+        desktop,
+    },
+    history::History,
+    idle::IdleMonitor,
+    notify::{Notifier, publish},
+    processing::{
+        Adjustments, BackgroundStyle, BeautifyPreset, ImageProcessor, Pipeline, ProcessingStep,
+        ProcessorRegistry, diff, plugin, redact,
+    },
+    sequence::Sequence,
+    upload::UploadTarget,
 };
+use serde::Serialize;
 use tracing::{error, info};
 
-pub async fn execute(args: Args) -> Result<()> {
-    let config = Config::load().unwrap_or_default();
+pub async fn execute(mut args: Args) -> Result<()> {
+    #[cfg(feature = "gui")]
+    let interactive_command = matches!(args.command, None | Some(Commands::Gui));
+    #[cfg(not(feature = "gui"))]
+    let interactive_command = args.command.is_none();
+
+    let first_run = interactive_command && !args.headless && !args.quiet && !Config::exists();
+    let config = if first_run {
+        run_setup().await
+    } else {
+        Config::load().unwrap_or_default()
+    };
+
+    match args.command.take() {
+        Some(Commands::Seq {
+            action: SeqAction::Next,
+        }) => {
+            let mut sequence = Sequence::current()?.ok_or_else(|| {
+                ScreenshotError::Config(
+                    "No sequence in progress; run `wst seq start NAME` first".to_string(),
+                )
+            })?;
+
+            let format = args.parse_format()?.unwrap_or(config.default_format);
+            if format == OutputFormat::Clipboard {
+                return Err(ScreenshotError::Config(
+                    "Sequences are saved to disk and cannot target the clipboard".to_string(),
+                ));
+            }
 
-    if let Some(command) = args.command {
-        return execute_subcommand(command, &config).await;
+            std::fs::create_dir_all(&sequence.directory)?;
+            args.output = Some(sequence.next_path(format.extension())?);
+            args.headless = true;
+            execute_capture(args, config).await
+        }
+        Some(Commands::Watch {
+            interval,
+            count,
+            skip_idle,
+        }) => execute_watch(args, config, interval, count, skip_idle).await,
+        Some(Commands::Flameshot { action }) => execute_flameshot(args, config, action).await,
+        Some(command) => execute_subcommand(command, &config).await,
+        None => execute_capture(args, config).await,
+    }
+}
+
+// Maps flameshot's gui/full/screen and their -p/-c/-d/--raw flags onto a
+// regular capture, so existing keybindings keep working.
+async fn execute_flameshot(
+    mut args: Args,
+    mut config: Config,
+    action: FlameshotAction,
+) -> Result<()> {
+    let (mode, flameshot) = match action {
+        FlameshotAction::Gui(flameshot) => (CaptureMode::Region, flameshot),
+        FlameshotAction::Full(flameshot) => (CaptureMode::Screen, flameshot),
+        FlameshotAction::Screen {
+            number,
+            args: flameshot,
+        } => {
+            args.monitor = number;
+            (CaptureMode::Monitor, flameshot)
+        }
+    };
+
+    args.mode = Some(mode);
+    args.delay = flameshot.delay.map(|ms| ms.div_ceil(1000));
+
+    match &flameshot.path {
+        Some(path) if path.is_dir() => config.save_directory = path.clone(),
+        Some(path) => args.output = Some(path.clone()),
+        None => {}
+    }
+
+    if flameshot.raw {
+        args.outputs.push(std::path::PathBuf::from("-"));
+    }
+
+    // Like flameshot, -c on its own copies without saving.
+    if flameshot.clipboard && flameshot.path.is_none() && !flameshot.raw {
+        args.format = Some("clipboard".to_string());
+    } else {
+        args.clipboard |= flameshot.clipboard;
     }
 
     execute_capture(args, config).await
 }
 
-async fn execute_capture(args: Args, config: Config) -> Result<()> {
-    let mode = args.parse_mode().unwrap_or(config.default_mode);
+async fn run_setup() -> Config {
+    use std::io::IsTerminal;
+
+    if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        return setup::run(Config::default()).unwrap_or_else(|e| {
+            eprintln!("Setup failed: {}", e);
+            Config::default()
+        });
+    }
+
+    #[cfg(feature = "gui")]
+    {
+        if let Err(e) = crate::ui::launch_setup(Config::default()).await {
+            eprintln!("Setup failed: {}", e);
+        }
+        Config::load().unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "gui"))]
+    {
+        Config::default()
+    }
+}
+
+async fn execute_watch(
+    mut args: Args,
+    config: Config,
+    interval: Option<u64>,
+    count: Option<u32>,
+    skip_idle: Option<u64>,
+) -> Result<()> {
+    if args.parse_format()?.unwrap_or(config.default_format) == OutputFormat::Clipboard {
+        return Err(ScreenshotError::Config(
+            "Watch mode saves to disk and cannot target the clipboard".to_string(),
+        ));
+    }
+    args.headless = true;
+
+    let interval = interval.unwrap_or(config.watch.interval_seconds).max(1);
+    let idle = match skip_idle.or(config.watch.skip_when_idle_minutes) {
+        Some(minutes) => match IdleMonitor::spawn(std::time::Duration::from_secs(minutes * 60)) {
+            Ok(monitor) => Some(monitor),
+            Err(e) => {
+                eprintln!("Idle detection disabled: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval));
+    let mut taken = 0;
+
+    while count.is_none_or(|count| taken < count) {
+        ticker.tick().await;
+
+        if idle.as_ref().is_some_and(IdleMonitor::is_idle) {
+            info!("User is idle; skipping capture");
+            continue;
+        }
+
+        match execute_capture(args.clone(), config.clone()).await {
+            Ok(()) => taken += 1,
+            Err(e) => eprintln!("Capture failed: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn execute_capture(mut args: Args, config: Config) -> Result<()> {
+    resolve_geometry(&mut args)?;
+
+    let window_matcher = args.parse_window_matcher()?;
+    let link_format = args.parse_link_format()?;
+    let region_of = args.parse_region_of()?;
+    let mode = if window_matcher.is_some() || args.all_windows {
+        CaptureMode::Window
+    } else if args.widget.is_some() || region_of.is_some() {
+        CaptureMode::Region
+    } else {
+        args.parse_mode().unwrap_or(config.default_mode)
+    };
 
     #[cfg(feature = "gui")]
-    if !args.headless && (mode == CaptureMode::Region || mode == CaptureMode::Window) {
-        return crate::ui::launch_gui(config).await;
+    if !args.headless
+        && window_matcher.is_none()
+        && !args.all_windows
+        && args.widget.is_none()
+        && region_of.is_none()
+        && matches!(
+            mode,
+            CaptureMode::Region | CaptureMode::Window | CaptureMode::Freeform
+        )
+    {
+        return crate::ui::launch_gui(config, mode).await;
     }
 
-    let format = args.parse_format().unwrap_or(config.default_format);
+    let format = args.parse_format()?.unwrap_or(config.default_format);
+
+    let backend = capture::create_backend(&config.capture)?;
 
-    let options = CaptureOptions {
-        delay: args
-            .delay
-            .or(Some(config.delay_seconds))
-            .map(std::time::Duration::from_secs),
-        include_cursor: args.cursor || config.include_cursor,
-        monitor_index: args.monitor,
-        region: args.parse_region(),
+    let regions = if args.has_percent_region()? {
+        let displays = backend.get_displays().await?;
+        let output = args
+            .monitor
+            .and_then(|i| displays.get(i))
+            .or_else(|| displays.iter().find(|d| d.is_primary))
+            .or_else(|| displays.first());
+        args.parse_regions(output)?
+    } else {
+        args.parse_regions(None)?
+    };
+    let region = match regions.as_slice() {
+        [region] => Some(*region),
+        _ => None,
     };
 
-    let backend = capture::create_backend()?;
+    let mut options = CaptureOptions::builder()
+        .delay(
+            args.delay
+                .or(Some(config.delay_seconds))
+                .map(std::time::Duration::from_secs),
+        )
+        .cursor(args.cursor || config.include_cursor)
+        .monitor(args.monitor)
+        .region(region)
+        .build();
+
+    let capabilities = backend.capabilities();
+
+    if options.include_cursor && !capabilities.cursor {
+        warn_fallback(&args, "cursor capture", "capturing without the cursor");
+        options.include_cursor = false;
+    }
+
+    if (window_matcher.is_some() || args.all_windows || mode == CaptureMode::ActiveWindow)
+        && !capabilities.window
+    {
+        return Err(ScreenshotError::CaptureFailed(
+            "Your compositor does not expose its window list; use the interactive window mode instead"
+                .to_string(),
+        ));
+    }
+
+    let per_output_fallback = mode == CaptureMode::Monitor
+        && (args.all || options.monitor_index.is_some())
+        && !capabilities.per_output;
+    if per_output_fallback {
+        warn_fallback(&args, "per-output capture", "capturing the whole screen");
+        options.monitor_index = None;
+    }
+
+    if let Some(delay) = options.delay.filter(|d| !d.is_zero() && !args.silent_delay) {
+        Notifier::countdown(delay, &options).await;
+        options.delay = None;
+    }
 
     if !args.quiet {
         info!("Capturing screenshot in {:?} mode...", mode);
     }
 
-    let screenshot = backend.capture(mode, &options).await?;
+    if mode == CaptureMode::Monitor && args.all && !per_output_fallback {
+        return capture_all_monitors(backend.as_ref(), &options, &args, &config, format).await;
+    }
+
+    if args.all_windows {
+        return capture_all_windows(backend.as_ref(), &options, &args, &config, format).await;
+    }
+
+    if regions.len() > 1 {
+        return capture_regions(backend.as_ref(), &options, &regions, &args, &config, format).await;
+    }
+
+    let screenshot = match (&window_matcher, &args.widget) {
+        (Some(matcher), _) => {
+            capture_matching_window(
+                backend.as_ref(),
+                matcher,
+                &options,
+                args.include_decorations,
+                args.exclude_shadow,
+            )
+            .await?
+        }
+        (None, Some(query)) => capture_widget(backend.as_ref(), query, &options).await?,
+        (None, None) => match &region_of {
+            Some(relative) => {
+                let region = relative.resolve(backend.as_ref()).await?;
+                capture_logical_region(backend.as_ref(), &options, region.0, mode).await?
+            }
+            None => {
+                CaptureRequest::new(mode, options.clone())
+                    .run_with(backend.as_ref())
+                    .await?
+            }
+        },
+    };
+
+    let screenshot =
+        capture::mask_private_windows(backend.as_ref(), screenshot, &config.privacy).await?;
+
+    let screenshot = if args.auto_redact {
+        let (screenshot, count) = redact::auto_redact(&screenshot, &config.redact)?;
+        if !args.quiet {
+            info!("Redacted {} sensitive text region(s)", count);
+        }
+        screenshot
+    } else {
+        screenshot
+    };
+
+    let masks = args.parse_masks()?;
+    let screenshot = if masks.is_empty() {
+        screenshot
+    } else {
+        ImageProcessor::mask(&screenshot, &masks, image::Rgba([0, 0, 0, 255]))?
+    };
+
+    let screenshot = match &args.beautify {
+        Some(name) => {
+            ImageProcessor::beautify(&screenshot, &BeautifyPreset::find(name, &config.presets)?)?
+        }
+        None => screenshot,
+    };
+
+    let screenshot = match &args.pipeline {
+        Some(name) => {
+            Pipeline::find(name, &config.pipelines)?.apply(&screenshot, &config.presets)?
+        }
+        None => screenshot,
+    };
+
+    let screenshot = match args.edit_with.as_deref() {
+        Some(program) => Exporter::edit_with(&screenshot, program)?,
+        None => screenshot,
+    };
+
+    let script = args
+        .script
+        .clone()
+        .or_else(|| config.scripting.on_capture.clone());
+    let screenshot = match script {
+        Some(script) => run_script(&script, screenshot, &mut args, &config, format)?,
+        None => screenshot,
+    };
+
+    let screenshot = config.apply_watermark(screenshot)?;
 
     if !args.quiet {
         info!(
@@ -51,64 +369,651 @@ async fn execute_capture(args: Args, config: Config) -> Result<()> {
         );
     }
 
-    let output_path = args.output.unwrap_or_else(|| {
+    let output_path = args.output.clone().unwrap_or_else(|| {
         let filename = match format {
-            OutputFormat::Png => format!("{}.png", config.generate_filename()),
-            OutputFormat::Jpeg => format!("{}.jpg", config.generate_filename()),
-            OutputFormat::Webp => format!("{}.webp", config.generate_filename()),
             OutputFormat::Clipboard => "clipboard".to_string(),
+            _ => format!("{}.{}", config.generate_filename(), format.extension()),
         };
         config.save_directory.join(filename)
     });
 
-    if format != OutputFormat::Clipboard {
-        let _file_size = Exporter::save(&screenshot, &output_path, format)?;
+    #[cfg(feature = "gui")]
+    if !args.headless
+        && args.outputs.is_empty()
+        && format != OutputFormat::Clipboard
+        && config.gui.preview.enabled_for(screenshot.metadata.mode)
+    {
+        return crate::ui::launch_preview(screenshot, output_path, config).await;
+    }
+
+    let targets = export_targets(&args, &config, format, output_path)?;
+
+    // The profile follows the output the pixels came from, not --monitor.
+    let output_name = screenshot.metadata.monitor.as_deref();
+
+    let export_options = ExportOptions {
+        icc_profile: config.color.icc_profile_for(output_name)?,
+        srgb_convert: args.srgb_convert || config.color.srgb_convert,
+        palette: args.palette,
+        metadata: embedded_metadata(&args, &config, &screenshot, output_name),
+        quality: Some(config.jpeg_quality),
+    };
+
+    let file_format = match format {
+        OutputFormat::Clipboard => OutputFormat::Png,
+        format => format,
+    };
+    let outcome = targets.export(&screenshot, file_format, &export_options, &config)?;
+
+    // Paths and links must not end up inside an image written to stdout.
+    let report = |line: &str| {
+        if targets.writes_stdout() {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    };
+
+    for path in &outcome.files {
+        options.emit(CaptureEvent::Exported { path: path.clone() });
+
+        if let Err(e) = History::record(path, &screenshot) {
+            error!("Failed to record history: {}", e);
+        }
 
         if !args.quiet {
-            println!("{}", output_path.display());
+            report(&path.display().to_string());
+        }
+    }
 
-            if args.json {
-                let json = serde_json::to_string_pretty(&screenshot.metadata)?;
-                println!("{}", json);
-            }
+    if let Some(path) = outcome.files.first() {
+        if config.notify_on_capture {
+            Notifier::captured(&screenshot, path);
+        }
+
+        publish::publish(
+            &config.publish,
+            &screenshot,
+            path,
+            outcome.urls.first().map(String::as_str),
+        );
+
+        if args.open || config.open_after_save {
+            desktop::open(path)?;
+        }
+
+        if args.reveal || config.reveal_after_save {
+            desktop::reveal(path)?;
+        }
+    }
+
+    if !args.quiet && args.json && !outcome.files.is_empty() {
+        report(&serde_json::to_string_pretty(&screenshot.metadata)?);
+    }
+
+    if outcome.copied && !args.quiet {
+        info!("Copied to clipboard");
+    }
+
+    for remote in &outcome.remote {
+        report(remote);
+    }
+
+    for url in &outcome.urls {
+        report(url);
+
+        if config.copy_link {
+            Exporter::copy_text_to_clipboard(url)?;
+        }
+    }
+
+    if let (Some(link_format), Some(path)) = (link_format, outcome.files.first()) {
+        let link = link_format.format(path, config.notes_directory.as_deref());
+        report(&link);
+
+        if config.copy_link {
+            Exporter::copy_text_to_clipboard(&link)?;
         }
+    }
+
+    Ok(())
+}
+
+// A script may rewrite the image, pick the file name, request an upload or
+// abort the capture altogether.
+#[cfg(feature = "scripting")]
+fn run_script(
+    script: &std::path::Path,
+    screenshot: Screenshot,
+    args: &mut Args,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<Screenshot> {
+    let (screenshot, outcome) = crate::scripting::on_capture(script, screenshot)?;
+    if outcome.aborted {
+        return Err(ScreenshotError::Cancelled);
+    }
+
+    if let Some(name) = outcome.rename {
+        let directory = args
+            .output
+            .as_deref()
+            .and_then(std::path::Path::parent)
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_else(|| config.save_directory.clone());
+        args.output = Some(directory.join(format!(
+            "{}.{}",
+            sanitize_filename(&name),
+            format.extension()
+        )));
+    }
+
+    if args.upload.is_none() {
+        args.upload = outcome.upload;
+    }
 
-        if args.clipboard || config.auto_copy_to_clipboard {
-            Exporter::copy_to_clipboard(&screenshot)?;
-            if !args.quiet {
-                info!("Copied to clipboard");
+    Ok(screenshot)
+}
+
+#[cfg(not(feature = "scripting"))]
+fn run_script(
+    _script: &std::path::Path,
+    _screenshot: Screenshot,
+    _args: &mut Args,
+    _config: &Config,
+    _format: OutputFormat,
+) -> Result<Screenshot> {
+    Err(ScreenshotError::Config(
+        "Capture scripts require the scripting feature".to_string(),
+    ))
+}
+
+fn export_targets(
+    args: &Args,
+    config: &Config,
+    format: OutputFormat,
+    output_path: std::path::PathBuf,
+) -> Result<ExportTargets> {
+    let mut targets = ExportTargets::new();
+
+    if format == OutputFormat::Clipboard {
+        targets.push(ExportTarget::Clipboard);
+    } else if args.output.is_some() || args.outputs.is_empty() {
+        targets.push(ExportTarget::File(output_path));
+    }
+
+    for path in &args.outputs {
+        let spec = path.to_string_lossy();
+
+        if RemoteTarget::is_remote(&spec) {
+            targets.push(ExportTarget::Remote(RemoteTarget::parse(
+                &spec,
+                &config.remotes,
+            )?));
+        } else if path.as_os_str() == "-" {
+            targets.push(ExportTarget::Stdout(match format {
+                OutputFormat::Clipboard => OutputFormat::Png,
+                format => format,
+            }));
+        } else {
+            targets.push(ExportTarget::File(path.clone()));
+        }
+    }
+
+    if args.clipboard || config.auto_copy_to_clipboard {
+        targets.push(ExportTarget::Clipboard);
+    }
+
+    if let Some(name) = &args.upload {
+        targets.push(ExportTarget::Upload(
+            Some(name.clone()).filter(|n| !n.is_empty()),
+        ));
+    }
+
+    Ok(targets)
+}
+
+// grim/slurp compatibility: a geometry, either given with -g or picked with
+// slurp, turns into a non-interactive region capture. Without slurp installed
+// --slurp falls through to the built-in region overlay.
+fn resolve_geometry(args: &mut Args) -> Result<()> {
+    if args.slurp {
+        match std::process::Command::new("slurp").output() {
+            Ok(output) if output.status.success() => {
+                args.geometry = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+            }
+            Ok(_) => {
+                return Err(ScreenshotError::CaptureFailed(
+                    "Selection cancelled".to_string(),
+                ));
             }
+            Err(_) => args.mode = Some(CaptureMode::Region),
         }
+    }
+
+    // `grim -g GEOMETRY -` writes to stdout rather than to a file named "-".
+    if args.geometry.is_some() && args.output.as_deref() == Some(std::path::Path::new("-")) {
+        args.outputs.extend(args.output.take());
+    }
+
+    if let Some(geometry) = &args.geometry {
+        let region =
+            crate::Region::parse_in(geometry, None).map_err(ScreenshotError::InvalidRegion)?;
+        args.region = vec![format!(
+            "{},{},{},{}",
+            region.x, region.y, region.width, region.height
+        )];
+        args.mode = Some(CaptureMode::Region);
+        args.headless = true;
+    }
+
+    Ok(())
+}
+
+fn embedded_metadata(
+    args: &Args,
+    config: &Config,
+    screenshot: &Screenshot,
+    monitor: Option<&str>,
+) -> Option<EmbeddedMetadata> {
+    if args.no_metadata {
+        return None;
+    }
+
+    config
+        .embedded_metadata(screenshot, monitor)
+        .map(|metadata| match &args.comment {
+            Some(comment) => metadata.comment(Some(comment.clone())),
+            None => metadata,
+        })
+}
+
+fn warn_fallback(args: &Args, feature: &str, fallback: &str) {
+    if !args.quiet {
+        eprintln!(
+            "warning: this backend does not support {}, {}",
+            feature, fallback
+        );
+    }
+}
+
+async fn capture_all_monitors(
+    backend: &dyn ScreenshotBackend,
+    options: &CaptureOptions,
+    args: &Args,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Clipboard {
+        return Err(ScreenshotError::Config(
+            "Cannot copy multiple monitors to the clipboard".to_string(),
+        ));
+    }
+
+    let cache = FrameCache::new(backend, options);
+    let directory = args
+        .output
+        .clone()
+        .unwrap_or_else(|| config.save_directory.clone());
+
+    let mut outputs = Vec::new();
+
+    for display in cache.displays().await? {
+        let mut screenshot = cache.region(display.logical_region()).await?;
+        screenshot.metadata.mode = CaptureMode::Monitor;
+        screenshot.metadata.monitor = Some(display.name.clone());
+        let screenshot =
+            capture::mask_private_windows(backend, screenshot, &config.privacy).await?;
+
+        let filename = config.generate_filename_with(&[("monitor", display.name.as_str())]);
+        outputs.push((screenshot, filename, Some(display.name.clone())));
+    }
+
+    if args.combined {
+        let filename = config.generate_filename_with(&[("monitor", "combined")]);
+        let frame = cache.frame().await?.clone();
+        let frame = capture::mask_private_windows(backend, frame, &config.privacy).await?;
+        outputs.push((frame, filename, None));
+    }
+
+    for (screenshot, filename, monitor) in outputs {
+        let screenshot = config.apply_watermark(screenshot)?;
+        let path = directory.join(format!("{}.{}", filename, format.extension()));
+        let export_options = ExportOptions {
+            metadata: embedded_metadata(args, config, &screenshot, monitor.as_deref()),
+            quality: Some(config.jpeg_quality),
+            ..Default::default()
+        };
+        Exporter::save_async(&screenshot, &path, format, &export_options).await?;
+        options.emit(CaptureEvent::Exported { path: path.clone() });
+
+        if let Err(e) = History::record(&path, &screenshot) {
+            error!("Failed to record history: {}", e);
+        }
+        publish::publish(&config.publish, &screenshot, &path, None);
+
+        if !args.quiet {
+            println!("{}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn capture_regions(
+    backend: &dyn ScreenshotBackend,
+    options: &CaptureOptions,
+    regions: &[LogicalRegion],
+    args: &Args,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Clipboard {
+        return Err(ScreenshotError::Config(
+            "Cannot copy multiple regions to the clipboard".to_string(),
+        ));
+    }
+
+    let cache = FrameCache::new(backend, options);
+    let directory = args
+        .output
+        .clone()
+        .unwrap_or_else(|| config.save_directory.clone());
+    std::fs::create_dir_all(&directory)?;
+
+    let windows = if config.privacy.mask_apps.is_empty() {
+        Vec::new()
     } else {
-        Exporter::copy_to_clipboard(&screenshot)?;
+        backend.get_windows().await.unwrap_or_else(|e| {
+            eprintln!("warning: cannot mask private windows: {}", e);
+            Vec::new()
+        })
+    };
+
+    for (n, region) in regions.iter().enumerate() {
+        let mut screenshot = cache.region(*region).await?;
+        screenshot.metadata.mode = CaptureMode::Region;
+        let screenshot =
+            capture::mask_private_area(screenshot, &windows, &config.privacy, *region)?;
+        let screenshot = config.apply_watermark(screenshot)?;
+
+        let filename = config.generate_filename_with(&[("n", &(n + 1).to_string())]);
+        let path = directory.join(format!("{}.{}", filename, format.extension()));
+        let export_options = ExportOptions {
+            metadata: embedded_metadata(args, config, &screenshot, None),
+            quality: Some(config.jpeg_quality),
+            ..Default::default()
+        };
+        Exporter::save_async(&screenshot, &path, format, &export_options).await?;
+        options.emit(CaptureEvent::Exported { path: path.clone() });
+
+        if let Err(e) = History::record(&path, &screenshot) {
+            error!("Failed to record history: {}", e);
+        }
+        publish::publish(&config.publish, &screenshot, &path, None);
+
+        if !args.quiet {
+            println!("{}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct WindowIndexEntry {
+    file: String,
+    title: String,
+    app_id: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+async fn capture_all_windows(
+    backend: &dyn ScreenshotBackend,
+    options: &CaptureOptions,
+    args: &Args,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<()> {
+    if format == OutputFormat::Clipboard {
+        return Err(ScreenshotError::Config(
+            "Cannot copy multiple windows to the clipboard".to_string(),
+        ));
+    }
+
+    let windows = backend.get_windows().await?;
+    if windows.is_empty() {
+        return Err(ScreenshotError::CaptureFailed(
+            "The compositor reported no windows".to_string(),
+        ));
+    }
+
+    let cache = FrameCache::new(backend, options);
+    let directory = args
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| config.save_directory.clone());
+    std::fs::create_dir_all(&directory)?;
+
+    let mut index = Vec::new();
+    let mut used_names = std::collections::HashSet::new();
+
+    for window in &windows {
+        let mut screenshot = match cache.region(window.region().into()).await {
+            Ok(screenshot) => screenshot,
+            Err(ScreenshotError::InvalidRegion(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        screenshot.metadata.mode = CaptureMode::Window;
+        screenshot.metadata.window_title = Some(window.title.clone());
+        screenshot.metadata.app_id = Some(window.app_id.clone());
+        let screenshot = capture::mask_private_area(
+            screenshot,
+            &windows,
+            &config.privacy,
+            window.region().into(),
+        )?;
+        let screenshot = config.apply_watermark(screenshot)?;
+
+        let stem = sanitize_filename(&format!("{}-{}", window.app_id, window.title));
+        let mut filename = format!("{}.{}", stem, format.extension());
+        let mut counter = 2;
+        while !used_names.insert(filename.clone()) {
+            filename = format!("{}-{}.{}", stem, counter, format.extension());
+            counter += 1;
+        }
+
+        let path = directory.join(&filename);
+        let export_options = ExportOptions {
+            metadata: embedded_metadata(args, config, &screenshot, None),
+            quality: Some(config.jpeg_quality),
+            ..Default::default()
+        };
+        Exporter::save_async(&screenshot, &path, format, &export_options).await?;
+        options.emit(CaptureEvent::Exported { path: path.clone() });
+
+        if let Err(e) = History::record(&path, &screenshot) {
+            error!("Failed to record history: {}", e);
+        }
+        publish::publish(&config.publish, &screenshot, &path, None);
+
         if !args.quiet {
-            info!("Copied to clipboard");
+            println!("{}", path.display());
         }
+
+        index.push(WindowIndexEntry {
+            file: filename,
+            title: window.title.clone(),
+            app_id: window.app_id.clone(),
+            x: window.x,
+            y: window.y,
+            width: window.width,
+            height: window.height,
+        });
+    }
+
+    let index_path = directory.join("index.json");
+    std::fs::write(&index_path, serde_json::to_string_pretty(&index)?)?;
+
+    if !args.quiet {
+        info!("Wrote index to {}", index_path.display());
     }
 
     Ok(())
 }
 
-async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config) -> Result<()> {
-    use crate::cli::args::Commands;
+fn sanitize_filename(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(96)
+        .collect();
+
+    let sanitized = sanitized.trim_matches(['_', '.', '-']);
+    if sanitized.is_empty() {
+        "window".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+async fn capture_matching_window(
+    backend: &dyn ScreenshotBackend,
+    matcher: &WindowMatcher,
+    options: &CaptureOptions,
+    include_decorations: bool,
+    exclude_shadow: bool,
+) -> Result<Screenshot> {
+    let compositor = Compositor::detect();
+    let window = backend
+        .get_windows()
+        .await?
+        .into_iter()
+        .find(|w| matcher.matches(w))
+        .ok_or_else(|| {
+            ScreenshotError::CaptureFailed(format!("No window matches '{}'", matcher.value))
+        })?;
+
+    info!("Capturing window: {} ({})", window.title, window.app_id);
+
+    let mut region = window.region();
+    if include_decorations {
+        region = window.frame.unwrap_or(region);
+
+        if !exclude_shadow {
+            let shadow = compositor.shadow_extent();
+            region = Region::new(
+                region.x - shadow as i32,
+                region.y - shadow as i32,
+                region.width + shadow * 2,
+                region.height + shadow * 2,
+            );
+        }
+    }
+
+    let mut screenshot =
+        capture_logical_region(backend, options, region, CaptureMode::Window).await?;
+    screenshot.metadata.window_title = Some(window.title);
+    screenshot.metadata.app_id = Some(window.app_id);
+
+    Ok(screenshot)
+}
+
+#[cfg(feature = "accessibility")]
+async fn capture_widget(
+    backend: &dyn ScreenshotBackend,
+    query: &str,
+    options: &CaptureOptions,
+) -> Result<Screenshot> {
+    let query: capture::WidgetQuery = query.parse().map_err(ScreenshotError::Config)?;
+    let bus = capture::AccessibilityBus::connect().await?;
+    let region = bus.find_widget(&query).await?;
+
+    info!(
+        "Capturing widget at {}x{} @ ({}, {})",
+        region.width, region.height, region.x, region.y
+    );
 
+    capture_logical_region(backend, options, region, CaptureMode::Region).await
+}
+
+#[cfg(not(feature = "accessibility"))]
+async fn capture_widget(
+    _backend: &dyn ScreenshotBackend,
+    _query: &str,
+    _options: &CaptureOptions,
+) -> Result<Screenshot> {
+    Err(ScreenshotError::Config(
+        "Widget capture requires the accessibility feature".to_string(),
+    ))
+}
+
+async fn capture_logical_region(
+    backend: &dyn ScreenshotBackend,
+    options: &CaptureOptions,
+    region: Region,
+    mode: CaptureMode,
+) -> Result<Screenshot> {
+    let mut screenshot = FrameCache::new(backend, options)
+        .region(LogicalRegion(region))
+        .await?;
+    screenshot.metadata.mode = mode;
+
+    Ok(screenshot)
+}
+
+async fn execute_subcommand(command: Commands, config: &Config) -> Result<()> {
     match command {
         #[cfg(feature = "gui")]
         Commands::Gui => {
             info!("Launching GUI mode...");
-            crate::ui::launch_gui(config.clone()).await
+            crate::ui::launch_gui(config.clone(), CaptureMode::Region).await
         }
 
         #[cfg(feature = "gui")]
-        Commands::Edit { file } => {
-            info!("Opening editor for: {}", file.display());
-            crate::ui::launch_editor(file, config.clone()).await
+        Commands::Preferences => crate::ui::launch_preferences(config.clone()).await,
+
+        #[cfg(feature = "gui")]
+        Commands::Edit {
+            file,
+            annotations,
+            from_clipboard,
+        } => {
+            let file = if from_clipboard {
+                let image = Exporter::paste_from_clipboard()?;
+                let screenshot =
+                    crate::Screenshot::new(image, CaptureMode::Screen, OutputFormat::Png);
+                let path = std::env::temp_dir().join(format!(
+                    "wst-clipboard-{}.png",
+                    screenshot.metadata.timestamp.timestamp_millis()
+                ));
+                Exporter::save(&screenshot, &path, OutputFormat::Png)?;
+                Some(path)
+            } else {
+                file
+            };
+
+            match &file {
+                Some(file) => info!("Opening editor for: {}", file.display()),
+                None => info!("Opening recent screenshots"),
+            }
+            crate::ui::launch_editor(file, annotations, config.clone()).await
         }
 
         Commands::List { what } => {
-            let backend = capture::create_backend()?;
-            match what.as_str() {
-                "displays" | "monitors" => {
+            let backend = capture::create_backend(&config.capture)?;
+            match what {
+                ListTarget::Displays => {
                     let displays = backend.get_displays().await?;
                     for (i, display) in displays.iter().enumerate() {
                         println!(
@@ -123,10 +1028,44 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
                         );
                     }
                 }
-                "windows" => {
-                    if let Some(window) = backend.get_activate_window().await? {
+                ListTarget::Capabilities => {
+                    let capabilities = backend.capabilities();
+                    println!("cursor:          {}", capabilities.cursor);
+                    println!("per-output:      {}", capabilities.per_output);
+                    println!("window:          {}", capabilities.window);
+                    println!("non-interactive: {}", capabilities.non_interactive);
+                }
+                ListTarget::Presets => {
+                    for (name, preset) in BeautifyPreset::all(&config.presets) {
+                        println!("{:<12} {}", name, preset.describe());
+                    }
+                }
+                ListTarget::Processors => {
+                    for processor in ProcessorRegistry::all() {
+                        println!("{:<12} {}", processor.name(), processor.description());
+                        println!("{:<12} params: {}", "", processor.params());
+                    }
+                }
+                ListTarget::Windows => {
+                    let active = backend.get_activate_window().await?;
+                    let windows = backend.get_windows().await?;
+
+                    if windows.is_empty() {
+                        println!("No windows found");
+                    }
+
+                    for (i, window) in windows.iter().enumerate() {
+                        let is_active = active.as_ref().is_some_and(|a| {
+                            a.title == window.title
+                                && a.app_id == window.app_id
+                                && a.x == window.x
+                                && a.y == window.y
+                        });
+
                         println!(
-                            "Active: {} ({}) - {}x{} @ ({}, {})",
+                            "[{}]{} {} ({}) - {}x{} @ ({}, {})",
+                            i,
+                            if is_active { "*" } else { "" },
                             window.title,
                             window.app_id,
                             window.width,
@@ -134,13 +1073,8 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
                             window.x,
                             window.y
                         );
-                    } else {
-                        println!("No active window found");
                     }
                 }
-                _ => {
-                    error!("Unknown list type: {}", what);
-                }
             }
             Ok(())
         }
@@ -150,6 +1084,56 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
             Ok(())
         }
 
+        Commands::Watch { .. } => unreachable!("handled before dispatching subcommands"),
+
+        Commands::Seq { action } => match action {
+            SeqAction::Start { name, dir } => {
+                let directory = dir.unwrap_or_else(|| config.save_directory.clone());
+                let sequence = Sequence::start(&name, directory)?;
+                println!(
+                    "Started sequence {} in {}",
+                    sequence.name,
+                    sequence.directory.display()
+                );
+                Ok(())
+            }
+            SeqAction::Next => unreachable!("handled before dispatching subcommands"),
+            SeqAction::Stop => {
+                match Sequence::stop()? {
+                    Some(sequence) => println!(
+                        "Stopped sequence {} after {} captures",
+                        sequence.name, sequence.counter
+                    ),
+                    None => println!("No sequence in progress"),
+                }
+                Ok(())
+            }
+            SeqAction::Status => {
+                match Sequence::current()? {
+                    Some(sequence) => println!(
+                        "{}: {} captures in {}",
+                        sequence.name,
+                        sequence.counter,
+                        sequence.directory.display()
+                    ),
+                    None => println!("No sequence in progress"),
+                }
+                Ok(())
+            }
+        },
+
+        Commands::Path { last, dir: _ } => {
+            if last {
+                let entry = History::last()?.ok_or_else(|| {
+                    ScreenshotError::Config("No screenshot has been saved yet".to_string())
+                })?;
+                println!("{}", entry.path.display());
+            } else {
+                println!("{}", config.save_directory.display());
+            }
+            Ok(())
+        }
+
         Commands::Config {
             show: _,
             reset,
@@ -179,6 +1163,234 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
             Ok(())
         }
 
+        Commands::Diff {
+            expected,
+            actual,
+            output,
+            threshold,
+            aa_tolerance,
+            max_diff_pixels,
+            max_diff_ratio,
+            ignore,
+            mask,
+        } => {
+            let mut options = config.diff.clone();
+            options.threshold = threshold.unwrap_or(options.threshold);
+            options.aa_tolerance = aa_tolerance.unwrap_or(options.aa_tolerance);
+            options.max_diff_pixels = max_diff_pixels.unwrap_or(options.max_diff_pixels);
+            options.max_diff_ratio = max_diff_ratio.or(options.max_diff_ratio);
+            for region in ignore {
+                options
+                    .ignore
+                    .push(region.parse().map_err(ScreenshotError::InvalidRegion)?);
+            }
+            options.ignore.extend(parse_masks(&mask)?);
+
+            let result = diff::diff(
+                &image::open(&expected)?.to_rgba8(),
+                &image::open(&actual)?.to_rgba8(),
+                &options,
+            )?;
+
+            if let Some(output) = output {
+                result.image.save(&output)?;
+            }
+
+            println!(
+                "{} of {} pixels differ ({:.4}%), {} anti-aliased",
+                result.differing_pixels,
+                result.total_pixels,
+                result.ratio() * 100.0,
+                result.anti_aliased_pixels
+            );
+
+            if !result.passes(&options) {
+                return Err(ScreenshotError::DiffExceeded {
+                    differing: result.differing_pixels,
+                });
+            }
+
+            Ok(())
+        }
+
+        Commands::Montage {
+            inputs,
+            output,
+            columns,
+            spacing,
+            no_labels,
+        } => {
+            let mut screenshots = Vec::with_capacity(inputs.len());
+            let mut labels = Vec::with_capacity(inputs.len());
+            for input in &inputs {
+                screenshots.push(Screenshot::from_path(input)?);
+                if !no_labels {
+                    labels.push(
+                        input
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+
+            let columns = columns.unwrap_or(inputs.len().min(4) as u32);
+            let sheet = Exporter::montage_labeled(&screenshots, &labels, columns, spacing)?;
+
+            let format = OutputFormat::from_path(&output).unwrap_or(OutputFormat::Png);
+            Exporter::save(&sheet, &output, format)?;
+            println!("{}", output.display());
+
+            Ok(())
+        }
+
+        Commands::Auth { action } => match action {
+            AuthAction::Login { target } => {
+                let mqtt = target == "mqtt" && config.publish.mqtt.is_some();
+                if !mqtt && !config.uploads.contains_key(&target) {
+                    eprintln!("warning: no upload target named {} in the config", target);
+                }
+
+                let secret = read_secret(&format!("Token or password for {}: ", target))?;
+                if secret.is_empty() {
+                    return Err(ScreenshotError::Cancelled);
+                }
+
+                Credentials::store(&target, &secret)?;
+                println!("Stored credentials for {}", target);
+                Ok(())
+            }
+            AuthAction::Logout { target } => {
+                Credentials::clear(&target)?;
+                println!("Removed credentials for {}", target);
+                Ok(())
+            }
+        },
+
+        Commands::Upload { file, to } => {
+            let screenshot = Screenshot::new(
+                image::open(&file)?.to_rgba8(),
+                CaptureMode::Screen,
+                OutputFormat::Png,
+            );
+            let (name, upload) = UploadTarget::find(
+                to.as_deref(),
+                config.default_upload.as_deref(),
+                &config.uploads,
+            )?;
+            let url = upload.upload(name, &file, &screenshot)?;
+            println!("{}", url);
+
+            if config.copy_link {
+                Exporter::copy_text_to_clipboard(&url)?;
+            }
+
+            Ok(())
+        }
+
+        Commands::Daemon => crate::daemon::run(config).await,
+
+        Commands::Serve { listen, token } => crate::daemon::serve(config, listen, token).await,
+
+        Commands::Flameshot { .. } => unreachable!("handled before dispatching subcommands"),
+
+        Commands::Print { file } => {
+            let file = match file {
+                Some(file) => file,
+                None => History::entries()?
+                    .into_iter()
+                    .rev()
+                    .map(|entry| entry.path)
+                    .find(|path| path.is_file())
+                    .ok_or_else(|| {
+                        ScreenshotError::Config("No screenshot in history to print".to_string())
+                    })?,
+            };
+
+            #[cfg(feature = "gui")]
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                return crate::ui::launch_print(file).await;
+            }
+
+            let status = std::process::Command::new("lp")
+                .args(["-o", "fit-to-page"])
+                .arg(&file)
+                .status()
+                .map_err(|e| ScreenshotError::ToolUnavailable {
+                    tool: "lp".to_string(),
+                    reason: e.to_string(),
+                })?;
+
+            if !status.success() {
+                return Err(ScreenshotError::Unknown(format!(
+                    "lp exited with {}",
+                    status
+                )));
+            }
+
+            Ok(())
+        }
+
+        Commands::Scan {
+            file,
+            region,
+            open,
+            copy,
+        } => {
+            let screenshot = match file {
+                Some(file) => Screenshot::new(
+                    image::open(&file)?.to_rgba8(),
+                    CaptureMode::Region,
+                    OutputFormat::Png,
+                ),
+                None => {
+                    let region = region
+                        .map(|r| r.parse::<Region>())
+                        .transpose()
+                        .map_err(ScreenshotError::InvalidRegion)?;
+                    let options = CaptureOptions::builder()
+                        .region(region.map(LogicalRegion))
+                        .build();
+                    let mode = if region.is_some() {
+                        CaptureMode::Screen
+                    } else {
+                        CaptureMode::Region
+                    };
+                    capture::create_backend(&config.capture)?
+                        .capture(mode, &options)
+                        .await?
+                }
+            };
+
+            let payloads = ImageProcessor::decode_barcodes(&screenshot)?;
+            if payloads.is_empty() {
+                return Err(ScreenshotError::CaptureFailed(
+                    "No QR code or barcode found (1D barcodes need zbarimg)".to_string(),
+                ));
+            }
+
+            for payload in &payloads {
+                println!("{}", payload);
+            }
+
+            if copy {
+                Exporter::copy_text_to_clipboard(&payloads.join("\n"))?;
+            }
+
+            if open {
+                for url in payloads
+                    .iter()
+                    .filter(|p| p.starts_with("http://") || p.starts_with("https://"))
+                {
+                    if let Err(e) = std::process::Command::new("xdg-open").arg(url).spawn() {
+                        eprintln!("Failed to open {}: {}", url, e);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
         Commands::Process {
             input,
             output,
@@ -186,20 +1398,56 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
             shadow,
             resize,
             blur,
+            rotate,
+            flip,
+            auto_trim,
+            brightness,
+            contrast,
+            saturation,
+            gamma,
+            grayscale,
+            invert,
+            round_corners,
+            padding,
+            background,
+            beautify,
+            pipeline,
+            processors,
+            frame,
+            frame_url,
+            chrome,
+            chrome_title,
+            merge_pdf,
         } => {
             info!("Processing image: {}", input.display());
 
-            let img = image::open(&input)?;
-            let mut screenshot =
-                crate::Screenshot::new(img.to_rgba8(), CaptureMode::Screen, OutputFormat::Png);
+            // "-" reads an encoded image from stdin, e.g. `wl-paste | wst process - out.png`.
+            let screenshot = if input.as_os_str() == "-" {
+                let mut bytes = Vec::new();
+                std::io::Read::read_to_end(&mut std::io::stdin(), &mut bytes)?;
+                crate::Screenshot::from_bytes(&bytes)?
+            } else {
+                crate::Screenshot::from_path(&input)?
+            };
+
+            let mut steps = match pipeline {
+                Some(name) => Pipeline::find(&name, &config.pipelines)?,
+                None => Pipeline::default(),
+            };
+
+            if let Some(tolerance) = auto_trim {
+                steps.push(ProcessingStep::AutoTrim { tolerance });
+            }
 
             if let Some(width) = border {
-                screenshot =
-                    ImageProcessor::add_border(&screenshot, width, image::Rgba([0, 0, 0, 255]))?;
+                steps.push(ProcessingStep::Border {
+                    width,
+                    color: "#000000".to_string(),
+                });
             }
 
             if let Some(offset) = shadow {
-                screenshot = ImageProcessor::add_shadow(&screenshot, offset)?;
+                steps.push(ProcessingStep::Shadow { offset });
             }
 
             if let Some(size_str) = resize {
@@ -211,23 +1459,116 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
                     let height = parts[1]
                         .parse()
                         .map_err(|_| ScreenshotError::Config("Invalid height".to_string()))?;
-                    screenshot = ImageProcessor::resize(&screenshot, width, height)?;
+                    steps.push(ProcessingStep::Resize { width, height });
                 }
             }
 
             if let Some(sigma) = blur {
-                screenshot = ImageProcessor::blur(&screenshot, sigma)?;
+                steps.push(ProcessingStep::Blur {
+                    sigma,
+                    region: None,
+                });
             }
 
-            let format = if output.extension().and_then(|e| e.to_str()) == Some("jpg") {
-                OutputFormat::Jpeg
-            } else if output.extension().and_then(|e| e.to_str()) == Some("webp") {
-                OutputFormat::Webp
-            } else {
-                OutputFormat::Png
+            if let Some(degrees) = rotate {
+                steps.push(ProcessingStep::Rotate { degrees });
+            }
+
+            if let Some(direction) = flip {
+                steps.push(ProcessingStep::Flip {
+                    direction: direction.parse().map_err(ScreenshotError::Config)?,
+                });
+            }
+
+            let defaults = Adjustments::default();
+            let adjustments = Adjustments {
+                brightness: brightness.unwrap_or(defaults.brightness),
+                contrast: contrast.unwrap_or(defaults.contrast),
+                saturation: saturation.unwrap_or(defaults.saturation),
+                gamma: gamma.unwrap_or(defaults.gamma),
             };
+            if !adjustments.is_identity() {
+                steps.push(ProcessingStep::Adjust { adjustments });
+            }
+
+            if grayscale {
+                steps.push(ProcessingStep::Grayscale);
+            }
+
+            if invert {
+                steps.push(ProcessingStep::Invert);
+            }
 
-            Exporter::save(&screenshot, &output, format)?;
+            if let Some(radius) = round_corners {
+                steps.push(ProcessingStep::RoundCorners { radius });
+            }
+
+            if let Some(padding) = padding {
+                let background = match background {
+                    Some(spec) => spec.parse().map_err(ScreenshotError::Config)?,
+                    None => BackgroundStyle::Solid {
+                        color: "#ffffff".to_string(),
+                    },
+                };
+                steps.push(ProcessingStep::Pad {
+                    padding,
+                    background,
+                });
+            }
+
+            for spec in &processors {
+                let (name, params) = plugin::parse_spec(spec)?;
+                steps.push(ProcessingStep::Plugin { name, params });
+            }
+
+            if let Some(style) = chrome {
+                let mut chrome = config.chrome.clone();
+                if !style.is_empty() {
+                    chrome.style = style.parse().map_err(ScreenshotError::Config)?;
+                }
+                chrome.title = chrome_title.or(chrome.title);
+                steps.push(ProcessingStep::Chrome { chrome });
+            }
+
+            // Framing goes before beautify so the background surrounds the device.
+            if let Some(name) = frame {
+                let params = match config.frames.get(&name) {
+                    Some(asset) => serde_json::to_value(asset)?,
+                    None => serde_json::json!({ "style": name, "url": frame_url }),
+                };
+                steps.push(ProcessingStep::Plugin {
+                    name: "frame".to_string(),
+                    params,
+                });
+            }
+
+            if let Some(preset) = beautify {
+                steps.push(ProcessingStep::Beautify { preset });
+            }
+
+            let screenshot = steps.apply(&screenshot, &config.presets)?;
+            let format = OutputFormat::from_path(&output).unwrap_or(OutputFormat::Png);
+
+            if merge_pdf.is_empty() {
+                Exporter::save(&screenshot, &output, format)?;
+            } else {
+                if format != OutputFormat::Pdf {
+                    return Err(ScreenshotError::Config(
+                        "--merge-pdf needs a .pdf output".to_string(),
+                    ));
+                }
+
+                let mut pages = vec![screenshot];
+                for page in &merge_pdf {
+                    let page = Screenshot::new(
+                        image::open(page)?.to_rgba8(),
+                        CaptureMode::Screen,
+                        OutputFormat::Pdf,
+                    );
+                    pages.push(steps.apply(&page, &config.presets)?);
+                }
+                Exporter::save_pdf(&pages, &output)?;
+            }
             info!("Saved to: {}", output.display());
 
             Ok(())
@@ -235,6 +1576,28 @@ async fn execute_subcommand(command: crate::cli::args::Commands, config: &Config
     }
 }
 
+fn read_secret(prompt: &str) -> Result<String> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        eprint!("{}", prompt);
+        std::io::stderr().flush()?;
+        let _ = std::process::Command::new("stty").arg("-echo").status();
+    }
+
+    let mut secret = String::new();
+    let read = std::io::stdin().lock().read_line(&mut secret);
+
+    if interactive {
+        let _ = std::process::Command::new("stty").arg("echo").status();
+        eprintln!();
+    }
+
+    read?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
 fn generate_completions(shell: &str) {
     use clap::CommandFactory;
     use clap_complete::{Shell, generate};
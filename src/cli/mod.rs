@@ -1,5 +1,6 @@
 pub mod args;
 pub mod commands;
+pub mod setup;
 
 pub use args::Args;
 pub use commands::execute;
@@ -0,0 +1,85 @@
+use crate::{OutputFormat, Result, config::Config};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+pub fn run(mut config: Config) -> Result<Config> {
+    println!("Welcome to wst! A few questions to get you started.");
+    println!("Press Enter to keep the suggested value.\n");
+
+    let directory = prompt(
+        "Save screenshots to",
+        &config.save_directory.display().to_string(),
+    )?;
+    config.save_directory = expand_home(&directory);
+
+    config.default_format = loop {
+        let format = prompt(
+            "Default format (png, jpg, webp, clipboard)",
+            config.default_format.extension(),
+        )?;
+        match format.parse::<OutputFormat>() {
+            Ok(format) => break format,
+            Err(e) => eprintln!("{}", e),
+        }
+    };
+
+    if config.default_format != OutputFormat::Clipboard {
+        config.auto_copy_to_clipboard = confirm(
+            "Also copy every capture to the clipboard?",
+            config.auto_copy_to_clipboard,
+        )?;
+    }
+
+    config.notify_on_capture = confirm(
+        "Show a notification after each capture?",
+        config.notify_on_capture,
+    )?;
+
+    config.save()?;
+    println!("\nConfiguration saved. Run `wst config --edit` to change it later.\n");
+
+    Ok(config)
+}
+
+fn prompt(question: &str, default: &str) -> Result<String> {
+    let answer = ask(&format!("{} [{}]: ", question, default))?;
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer
+    })
+}
+
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+
+    loop {
+        match ask(&format!("{} [{}]: ", question, hint))?
+            .to_lowercase()
+            .as_str()
+        {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("Please answer yes or no"),
+        }
+    }
+}
+
+fn ask(question: &str) -> Result<String> {
+    print!("{}", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
@@ -1,21 +1,228 @@
-use crate::{CaptureMode, OutputFormat, Result};
+use crate::export::{ClipboardTarget, EmbeddedMetadata, RemoteHost};
+use crate::notify::PublishConfig;
+use crate::upload::UploadTarget;
+use crate::{
+    CaptureMode, OutputFormat, Result, Screenshot, WindowInfo,
+    processing::{
+        BeautifyPreset, DiffOptions, FrameAsset, Pipeline, RedactOptions, Watermark, WindowChrome,
+    },
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub default_mode: CaptureMode,
     pub default_format: OutputFormat,
+    pub jpeg_quality: u8,
     pub save_directory: PathBuf,
     pub filename_template: String,
     pub auto_copy_to_clipboard: bool,
     pub delay_seconds: u64,
     pub include_cursor: bool,
+    pub notes_directory: Option<PathBuf>,
+    pub copy_link: bool,
+    pub notify_on_capture: bool,
+    pub open_after_save: bool,
+    pub reveal_after_save: bool,
+    pub external_editor: Option<String>,
 
     #[cfg(feature = "gui")]
     pub gui: GuiConfig,
 
     pub shortcuts: ShortcutConfig,
+
+    pub presets: BTreeMap<String, BeautifyPreset>,
+
+    #[serde(rename = "pipeline")]
+    pub pipelines: BTreeMap<String, Pipeline>,
+
+    #[serde(rename = "frame")]
+    pub frames: BTreeMap<String, FrameAsset>,
+
+    pub chrome: WindowChrome,
+
+    pub color: ColorConfig,
+
+    pub clipboard: ClipboardConfig,
+
+    pub clipboard_targets: Vec<ClipboardTarget>,
+
+    pub diff: DiffOptions,
+
+    pub redact: RedactOptions,
+
+    pub watermark: Option<Watermark>,
+
+    pub watch: WatchConfig,
+
+    pub metadata: MetadataConfig,
+
+    pub capture: CaptureConfig,
+
+    pub privacy: PrivacyConfig,
+
+    pub daemon: DaemonConfig,
+
+    pub serve: ServeConfig,
+
+    pub publish: PublishConfig,
+
+    pub scripting: ScriptingConfig,
+
+    pub default_upload: Option<String>,
+
+    #[serde(rename = "upload")]
+    pub uploads: BTreeMap<String, UploadTarget>,
+
+    #[serde(rename = "remote")]
+    pub remotes: BTreeMap<String, RemoteHost>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataConfig {
+    pub embed: bool,
+    pub comment: Option<String>,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            embed: true,
+            comment: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    pub portal_timeout_secs: u64,
+    pub portal_retries: u32,
+    pub interactive_timeout_secs: u64,
+    pub keep_portal_files: bool,
+    pub window_decorations: bool,
+    pub fallback_command: FallbackCommandConfig,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            portal_timeout_secs: 30,
+            portal_retries: 1,
+            interactive_timeout_secs: 300,
+            keep_portal_files: false,
+            window_decorations: true,
+            fallback_command: FallbackCommandConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FallbackCommandConfig {
+    pub enabled: bool,
+    pub prefer: bool,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl Default for FallbackCommandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            prefer: false,
+            command: "grim".to_string(),
+            args: vec!["-t".to_string(), "png".to_string(), "-".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    pub interval_seconds: u64,
+    pub skip_when_idle_minutes: Option<u64>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 60,
+            skip_when_idle_minutes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyStyle {
+    #[default]
+    Black,
+    Pixelate,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    pub mask_apps: Vec<String>,
+    pub style: PrivacyStyle,
+}
+
+impl PrivacyConfig {
+    pub fn hides(&self, window: &WindowInfo) -> bool {
+        let title = window.title.to_lowercase();
+        self.mask_apps.iter().any(|app| {
+            window.app_id.eq_ignore_ascii_case(app) || title.contains(&app.to_lowercase())
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    pub tray: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptingConfig {
+    pub on_capture: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    pub listen: String,
+    pub token: Option<String>,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self {
+            listen: "127.0.0.1:8787".to_string(),
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub max_dimension: Option<u32>,
+    pub max_bytes: Option<u64>,
+    pub persist: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorConfig {
+    pub icc_profiles: BTreeMap<String, PathBuf>,
+    pub default_icc_profile: Option<PathBuf>,
+    pub srgb_convert: bool,
 }
 
 #[cfg(feature = "gui")]
@@ -24,11 +231,102 @@ pub struct GuiConfig {
     pub animation: AnimationConfig,
     pub css_classes: std::collections::HashMap<String, String>,
     pub editor_enabled: bool,
+    #[serde(default)]
+    pub mouse_bindings: MouseBindings,
+    #[serde(default)]
+    pub hud: HudConfig,
+    #[serde(default)]
+    pub preview: PreviewConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    #[default]
+    Auto,
+    Dark,
+    Light,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub dim_opacity: Option<f64>,
+    pub selection_color: Option<String>,
+    pub selection_width: f64,
+    pub handle_size: f64,
+    pub toolbar_background: Option<String>,
+    pub toolbar_border: Option<String>,
+    pub toolbar_text: Option<String>,
+    pub font: String,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    pub modes: Vec<CaptureMode>,
+    pub timeout_seconds: u64,
+    pub timeout_action: PreviewAction,
+}
+
+#[cfg(feature = "gui")]
+impl PreviewConfig {
+    pub fn enabled_for(&self, mode: CaptureMode) -> bool {
+        self.modes.contains(&mode)
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewAction {
+    #[default]
+    Save,
+    Discard,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudConfig {
+    pub enabled: bool,
+    pub show_size: bool,
+    pub show_position: bool,
+    pub show_aspect_ratio: bool,
+    pub show_snap_target: bool,
+    pub font_size: f64,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseBindings {
+    pub right: MouseAction,
+    pub middle: MouseAction,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MouseAction {
+    #[default]
+    None,
+    Cancel,
+    CaptureOutput,
+    ToggleWindowSnap,
 }
 
 #[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnimationConfig {
+    pub enabled: bool,
     pub duration_ms: u64,
     pub easing: String,
     pub start_shape: String,
@@ -51,29 +349,80 @@ impl Default for Config {
         Self {
             default_mode: CaptureMode::Region,
             default_format: OutputFormat::Png,
+            jpeg_quality: 75,
             save_directory: pictures_dir,
             filename_template: "screenshot_%Y%m%d_%H%M%S".to_string(),
             auto_copy_to_clipboard: false,
             delay_seconds: 0,
             include_cursor: false,
+            notes_directory: None,
+            copy_link: false,
+            notify_on_capture: false,
+            open_after_save: false,
+            reveal_after_save: false,
+            external_editor: None,
 
             #[cfg(feature = "gui")]
             gui: GuiConfig::default(),
 
             shortcuts: ShortcutConfig::default(),
+
+            presets: BTreeMap::new(),
+
+            pipelines: BTreeMap::new(),
+
+            frames: BTreeMap::new(),
+
+            chrome: WindowChrome::default(),
+
+            color: ColorConfig::default(),
+
+            clipboard: ClipboardConfig::default(),
+
+            clipboard_targets: vec![ClipboardTarget::Image],
+
+            diff: DiffOptions::default(),
+
+            redact: RedactOptions::default(),
+
+            watermark: None,
+
+            watch: WatchConfig::default(),
+
+            metadata: MetadataConfig::default(),
+
+            capture: CaptureConfig::default(),
+
+            privacy: PrivacyConfig::default(),
+
+            daemon: DaemonConfig::default(),
+
+            serve: ServeConfig::default(),
+
+            publish: PublishConfig::default(),
+
+            scripting: ScriptingConfig::default(),
+
+            default_upload: None,
+
+            uploads: BTreeMap::new(),
+
+            remotes: BTreeMap::new(),
         }
     }
 }
 
 impl Config {
     fn get_pictures_directory() -> PathBuf {
-        directories::UserDirs::new()
-            .and_then(|dirs| dirs.picture_dir().map(|p| p.to_path_buf()))
-            .unwrap_or_else(|| {
-                std::env::var("HOME")
-                    .map(|h| PathBuf::from(h).join("Pictures"))
-                    .unwrap_or_else(|_| PathBuf::from("/tmp"))
-            })
+        crate::paths::pictures_dir().unwrap_or_else(|| {
+            std::env::var("HOME")
+                .map(|h| PathBuf::from(h).join("Pictures"))
+                .unwrap_or_else(|_| PathBuf::from("/tmp"))
+        })
+    }
+
+    pub fn exists() -> bool {
+        Self::config_file_path().is_ok_and(|path| path.exists())
     }
 
     pub fn load() -> Result<Self> {
@@ -100,8 +449,8 @@ impl Config {
     }
 
     fn config_file_path() -> Result<PathBuf> {
-        directories::ProjectDirs::from("com", "wayland", "just-a-simple-wayland-screenshot-tool")
-            .map(|dirs| dirs.config_dir().join("config.toml"))
+        crate::paths::config_dir()
+            .map(|dir| dir.join("config.toml"))
             .ok_or_else(|| {
                 crate::error::ScreenshotError::Config(
                     "Cannot determine config directory".to_string(),
@@ -109,10 +458,66 @@ impl Config {
             })
     }
 
+    pub fn apply_watermark(&self, screenshot: Screenshot) -> Result<Screenshot> {
+        match &self.watermark {
+            Some(watermark) => watermark.apply(&screenshot),
+            None => Ok(screenshot),
+        }
+    }
+
+    pub fn embedded_metadata(
+        &self,
+        screenshot: &Screenshot,
+        monitor: Option<&str>,
+    ) -> Option<EmbeddedMetadata> {
+        self.metadata.embed.then(|| {
+            EmbeddedMetadata::new(screenshot)
+                .monitor(monitor)
+                .comment(self.metadata.comment.clone())
+        })
+    }
+
     pub fn generate_filename(&self) -> String {
-        chrono::Local::now()
-            .format(&self.filename_template)
-            .to_string()
+        self.generate_filename_with(&[])
+    }
+
+    pub fn generate_filename_with(&self, tokens: &[(&str, &str)]) -> String {
+        let mut template = self.filename_template.clone();
+
+        for (key, _) in tokens {
+            if !template.contains(&format!("{{{}}}", key)) {
+                template = format!("{}_{{{}}}", template, key);
+            }
+        }
+
+        expand_template(&template, chrono::Local::now(), tokens)
+    }
+}
+
+pub fn expand_template(
+    template: &str,
+    timestamp: chrono::DateTime<chrono::Local>,
+    tokens: &[(&str, &str)],
+) -> String {
+    let mut template = template.to_string();
+
+    for (key, value) in tokens {
+        template = template.replace(&format!("{{{}}}", key), &value.replace('%', "%%"));
+    }
+
+    timestamp.format(&template).to_string()
+}
+
+impl ColorConfig {
+    pub fn icc_profile_for(&self, output: Option<&str>) -> Result<Option<Vec<u8>>> {
+        let path = output
+            .and_then(|name| self.icc_profiles.get(name))
+            .or(self.default_icc_profile.as_ref());
+
+        match path {
+            Some(path) => Ok(Some(std::fs::read(path)?)),
+            None => Ok(None),
+        }
     }
 }
 
@@ -123,6 +528,51 @@ impl Default for GuiConfig {
             animation: AnimationConfig::default(),
             css_classes: Self::default_css_classes(),
             editor_enabled: true,
+            mouse_bindings: MouseBindings::default(),
+            hud: HudConfig::default(),
+            preview: PreviewConfig::default(),
+            theme: ThemeConfig::default(),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            preset: ThemePreset::Auto,
+            dim_opacity: None,
+            selection_color: None,
+            selection_width: 2.0,
+            handle_size: 0.0,
+            toolbar_background: None,
+            toolbar_border: None,
+            toolbar_text: None,
+            font: "Sans".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            show_size: true,
+            show_position: true,
+            show_aspect_ratio: false,
+            show_snap_target: true,
+            font_size: 12.0,
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Default for MouseBindings {
+    fn default() -> Self {
+        Self {
+            right: MouseAction::Cancel,
+            middle: MouseAction::CaptureOutput,
         }
     }
 }
@@ -147,6 +597,7 @@ impl GuiConfig {
 impl Default for AnimationConfig {
     fn default() -> Self {
         Self {
+            enabled: true,
             duration_ms: 800,
             easing: "ease-in-out".to_string(),
             start_shape: "circle".to_string(),
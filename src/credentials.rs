@@ -0,0 +1,74 @@
+use crate::{Result, ScreenshotError};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SERVICE: &str = "wst";
+
+// Secrets live in the freedesktop Secret Service (GNOME Keyring, KWallet,
+// KeePassXC, ...) through libsecret's secret-tool, keyed by target name.
+pub struct Credentials;
+
+impl Credentials {
+    pub fn store(target: &str, secret: &str) -> Result<()> {
+        let mut child = Command::new("secret-tool")
+            .arg("store")
+            .arg(format!("--label=wst: {}", target))
+            .args(["service", SERVICE, "target", target])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ScreenshotError::ToolUnavailable {
+                tool: "secret-tool".to_string(),
+                reason: e.to_string(),
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(secret.as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(ScreenshotError::Config(format!(
+                "Failed to store credentials: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn lookup(target: &str) -> Option<String> {
+        let output = Command::new("secret-tool")
+            .arg("lookup")
+            .args(["service", SERVICE, "target", target])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() || output.stdout.is_empty() {
+            return None;
+        }
+
+        String::from_utf8(output.stdout).ok()
+    }
+
+    pub fn clear(target: &str) -> Result<()> {
+        let status = Command::new("secret-tool")
+            .arg("clear")
+            .args(["service", SERVICE, "target", target])
+            .status()
+            .map_err(|e| ScreenshotError::ToolUnavailable {
+                tool: "secret-tool".to_string(),
+                reason: e.to_string(),
+            })?;
+
+        if !status.success() {
+            return Err(ScreenshotError::Config(format!(
+                "No stored credentials for {}",
+                target
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+mod serve;
+#[cfg(feature = "tray")]
+mod tray;
+
+pub use serve::serve;
+
+use crate::{Result, config::Config};
+use tracing::info;
+
+pub async fn run(config: &Config) -> Result<()> {
+    if config.daemon.tray {
+        #[cfg(feature = "tray")]
+        tray::spawn();
+
+        #[cfg(not(feature = "tray"))]
+        eprintln!("daemon.tray is set, but wst was built without the tray feature");
+    }
+
+    info!("Daemon running; press Ctrl+C to stop");
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
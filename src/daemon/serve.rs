@@ -0,0 +1,333 @@
+use crate::{
+    CaptureMode, CaptureOptions, LogicalRegion, OutputFormat, Region, Result, ScreenshotError,
+    capture::{self, CaptureRequest},
+    config::Config,
+    export::Exporter,
+    history::History,
+    notify::publish,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tracing::{error, info};
+
+const MAX_HEAD: usize = 16 * 1024;
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: &'static str, value: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: &'static str, message: impl std::fmt::Display) -> Self {
+        Self::json(status, json!({ "error": message.to_string() }))
+    }
+}
+
+struct Server {
+    config: Config,
+    token: String,
+    // The portal handles one request at a time; queue captures instead of
+    // racing them.
+    capture: Mutex<()>,
+}
+
+// A deliberately small HTTP/1.1 endpoint: one request per connection, no
+// request bodies, everything the handlers need comes from the query string.
+pub async fn serve(config: &Config, listen: Option<String>, token: Option<String>) -> Result<()> {
+    let listen = listen.unwrap_or_else(|| config.serve.listen.clone());
+    let token = match token.or_else(|| config.serve.token.clone()) {
+        Some(token) => token,
+        None => {
+            let token = random_token()?;
+            eprintln!("No serve.token configured; using {}", token);
+            token
+        }
+    };
+
+    let listener = TcpListener::bind(&listen).await?;
+    info!("Serving on http://{}", listener.local_addr()?);
+
+    let server = Arc::new(Server {
+        config: config.clone(),
+        token,
+        capture: Mutex::new(()),
+    });
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let server = server.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = server.handle(stream).await {
+                        error!("Request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+impl Server {
+    async fn handle(&self, mut stream: TcpStream) -> Result<()> {
+        let response = match read_request(&mut stream).await {
+            Ok(Some(request)) if !self.authorized(&request) => {
+                Response::error("401 Unauthorized", "Missing or invalid token")
+            }
+            Ok(Some(request)) => self.route(&request).await,
+            Ok(None) => Response::error("400 Bad Request", "Malformed request"),
+            Err(e) => Response::error("400 Bad Request", e),
+        };
+
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            response.status,
+            response.content_type,
+            response.body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&response.body).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let supplied = request
+            .headers
+            .get("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| request.query.get("token").map(String::as_str))
+            .unwrap_or("");
+
+        // Compare every byte so the time taken does not leak the prefix.
+        supplied.len() == self.token.len()
+            && supplied
+                .bytes()
+                .zip(self.token.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    async fn route(&self, request: &Request) -> Response {
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/capture") => self.capture(request).await,
+            ("GET", "/last") => last(),
+            ("GET", "/history") => history(),
+            (_, "/capture" | "/last" | "/history") => {
+                return Response::error("405 Method Not Allowed", "Method not allowed");
+            }
+            _ => return Response::error("404 Not Found", "Not found"),
+        };
+
+        result.unwrap_or_else(|e| match e {
+            ScreenshotError::InvalidRegion(_)
+            | ScreenshotError::UnsupportedFormat { .. }
+            | ScreenshotError::Config(_) => Response::error("400 Bad Request", e),
+            e => Response::error("500 Internal Server Error", e),
+        })
+    }
+
+    // POST /capture?mode=screen&region=X,Y,W,H&monitor=N&save=1
+    async fn capture(&self, request: &Request) -> Result<Response> {
+        let region = request
+            .query
+            .get("region")
+            .map(|r| Region::parse_in(r, None).map_err(ScreenshotError::InvalidRegion))
+            .transpose()?;
+        let mode = match request.query.get("mode") {
+            Some(mode) => mode.parse().map_err(ScreenshotError::Config)?,
+            None if region.is_some() => CaptureMode::Region,
+            None => CaptureMode::Screen,
+        };
+        // Nobody is at the screen to pick a window or drag out a region.
+        if matches!(mode, CaptureMode::Window | CaptureMode::Freeform)
+            || (mode == CaptureMode::Region && region.is_none())
+        {
+            return Err(ScreenshotError::Config(format!(
+                "mode={} needs a user at the screen; pass region=X,Y,W,H instead",
+                request.query.get("mode").map_or("region", String::as_str)
+            )));
+        }
+        let monitor = request
+            .query
+            .get("monitor")
+            .map(|m| {
+                m.parse()
+                    .map_err(|_| ScreenshotError::Config(format!("Invalid monitor: {}", m)))
+            })
+            .transpose()?;
+
+        let options = CaptureOptions::builder()
+            .cursor(self.config.include_cursor)
+            .monitor(monitor)
+            .region(region.map(LogicalRegion))
+            .build();
+
+        let screenshot = {
+            let _guard = self.capture.lock().await;
+            let backend = capture::create_backend(&self.config.capture)?;
+            let screenshot = CaptureRequest::new(mode, options)
+                .run_with(backend.as_ref())
+                .await?;
+            capture::mask_private_windows(backend.as_ref(), screenshot, &self.config.privacy)
+                .await?
+        };
+        let screenshot = self.config.apply_watermark(screenshot)?;
+
+        if request.query.get("save").is_some_and(|save| save != "0") {
+            let path = self.config.save_directory.join(format!(
+                "{}.{}",
+                self.config.generate_filename(),
+                OutputFormat::Png.extension()
+            ));
+            Exporter::save_async(&screenshot, &path, OutputFormat::Png, &Default::default())
+                .await?;
+            if let Err(e) = History::record(&path, &screenshot) {
+                error!("Failed to record history: {}", e);
+            }
+            publish::publish(&self.config.publish, &screenshot, &path, None);
+
+            return Ok(Response::json(
+                "201 Created",
+                json!({ "path": path, "metadata": screenshot.metadata }),
+            ));
+        }
+
+        Ok(Response {
+            status: "200 OK",
+            content_type: "image/png",
+            body: screenshot.encode(OutputFormat::Png, &Default::default())?,
+        })
+    }
+}
+
+fn last() -> Result<Response> {
+    let Some(entry) = History::entries()?
+        .into_iter()
+        .rev()
+        .find(|entry| entry.path.is_file())
+    else {
+        return Ok(Response::error("404 Not Found", "No screenshot in history"));
+    };
+
+    let content_type = match entry.metadata.format {
+        OutputFormat::Jpeg => "image/jpeg",
+        OutputFormat::Webp => "image/webp",
+        OutputFormat::Pdf => "application/pdf",
+        _ => "image/png",
+    };
+
+    Ok(Response {
+        status: "200 OK",
+        content_type,
+        body: std::fs::read(&entry.path)?,
+    })
+}
+
+fn history() -> Result<Response> {
+    Ok(Response::json(
+        "200 OK",
+        serde_json::to_value(History::entries()?)?,
+    ))
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Request>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+
+    let end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD {
+            return Ok(None);
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..end]);
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(Some(Request {
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        query,
+        headers,
+    }))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn random_token() -> Result<String> {
+    let mut bytes = [0; 16];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut bytes))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
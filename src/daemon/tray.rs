@@ -0,0 +1,78 @@
+use crate::{export::desktop, history::History};
+use ksni::menu::{MenuItem, StandardItem};
+use std::process::Command;
+
+struct WstTray;
+
+impl ksni::Tray for WstTray {
+    fn id(&self) -> String {
+        "wst".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Screenshot Tool".to_string()
+    }
+
+    fn icon_name(&self) -> String {
+        "applets-screenshooter".to_string()
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        run_wst(&["region"]);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        vec![
+            item("Capture Region", |_| run_wst(&["region"])),
+            item("Capture Screen", |_| run_wst(&["screen"])),
+            item("Capture Window", |_| run_wst(&["window"])),
+            MenuItem::Separator,
+            item("Open Last", |_| open_last()),
+            item("History", |_| run_wst(&["edit"])),
+            item("Settings", |_| open_settings()),
+        ]
+    }
+}
+
+pub fn spawn() {
+    ksni::TrayService::new(WstTray).spawn();
+}
+
+fn item(label: &str, activate: fn(&mut WstTray)) -> MenuItem<WstTray> {
+    StandardItem {
+        label: label.to_string(),
+        activate: Box::new(activate),
+        ..Default::default()
+    }
+    .into()
+}
+
+// Menu actions run as separate wst processes so a capture or editor window
+// never blocks the tray.
+fn run_wst(args: &[&str]) {
+    let spawned = std::env::current_exe().and_then(|exe| Command::new(exe).args(args).spawn());
+
+    if let Err(e) = spawned {
+        eprintln!("Failed to run wst {}: {}", args.join(" "), e);
+    }
+}
+
+fn open_settings() {
+    #[cfg(feature = "gui")]
+    run_wst(&["preferences"]);
+
+    #[cfg(not(feature = "gui"))]
+    run_wst(&["config", "--edit"]);
+}
+
+fn open_last() {
+    match History::last() {
+        Ok(Some(entry)) => {
+            if let Err(e) = desktop::open(&entry.path) {
+                eprintln!("{}", e);
+            }
+        }
+        Ok(None) => eprintln!("No screenshot has been saved yet"),
+        Err(e) => eprintln!("{}", e),
+    }
+}
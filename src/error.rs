@@ -16,6 +16,24 @@ pub enum ScreenshotError {
     #[error("Portal error: {0}")]
     Portal(String),
 
+    #[error("The screenshot portal denied the request")]
+    PortalDenied,
+
+    #[error("Portal request timed out after {0}s")]
+    PortalTimeout(u64),
+
+    #[error("Clipboard unavailable: {tool} is not installed or failed")]
+    ClipboardUnavailable { tool: String },
+
+    #[error("{tool} not available: {reason}")]
+    ToolUnavailable { tool: String, reason: String },
+
+    #[error("{0}")]
+    RemoteFailed(String),
+
+    #[error("Unsupported format: {format}")]
+    UnsupportedFormat { format: String },
+
     #[error("Invalid configuration: {0}")]
     Config(String),
 
@@ -25,6 +43,9 @@ pub enum ScreenshotError {
     #[error("Invalid region: {0}")]
     InvalidRegion(String),
 
+    #[error("Images differ: {differing} pixels are over the threshold")]
+    DiffExceeded { differing: u64 },
+
     #[error("No display found")]
     NoDisplay,
 
@@ -42,6 +63,37 @@ pub enum ScreenshotError {
     Unknown(String),
 }
 
+impl ScreenshotError {
+    // Exit status for the CLI: 1 general or remote failure, 3 permission
+    // denied, 4 timed out, 5 backend or tool unavailable, 6 invalid input,
+    // 7 cancelled. 2 is left to clap for invalid usage.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::PortalDenied => 3,
+            Self::PortalTimeout(_) => 4,
+            Self::BackendUnavailable
+            | Self::ClipboardUnavailable { .. }
+            | Self::ToolUnavailable { .. } => 5,
+            Self::UnsupportedFormat { .. }
+            | Self::InvalidRegion(_)
+            | Self::Config(_)
+            | Self::ConfigParse(_) => 6,
+            Self::Cancelled => 7,
+            _ => 1,
+        }
+    }
+}
+
+impl From<ashpd::Error> for ScreenshotError {
+    fn from(value: ashpd::Error) -> Self {
+        match value {
+            ashpd::Error::Response(ashpd::desktop::ResponseError::Cancelled) => Self::Cancelled,
+            ashpd::Error::Response(ashpd::desktop::ResponseError::Other) => Self::PortalDenied,
+            e => Self::Portal(e.to_string()),
+        }
+    }
+}
+
 impl From<anyhow::Error> for ScreenshotError {
     fn from(err: anyhow::Error) -> Self {
         ScreenshotError::Unknown(err.to_string())
@@ -0,0 +1,20 @@
+use crate::CaptureMode;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone)]
+pub enum CaptureEvent {
+    DelayStarted { delay: Duration },
+    DelayTick { remaining: Duration },
+    CaptureStarted { mode: CaptureMode },
+    CaptureFinished { width: u32, height: u32 },
+    Exported { path: PathBuf },
+}
+
+pub type EventSender = broadcast::Sender<CaptureEvent>;
+pub type EventReceiver = broadcast::Receiver<CaptureEvent>;
+
+pub fn channel() -> (EventSender, EventReceiver) {
+    broadcast::channel(32)
+}
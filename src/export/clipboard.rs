@@ -0,0 +1,298 @@
+use super::desktop::file_uri;
+use crate::{Result, ScreenshotError};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use wayland_client::globals::{GlobalListContents, registry_queue_init};
+use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, event_created_child};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    zwlr_data_control_source_v1::{self, ZwlrDataControlSourceV1},
+};
+
+const TEXT_TYPES: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "UTF8_STRING",
+    "STRING",
+    "TEXT",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardTarget {
+    Image,
+    UriList,
+    Text,
+}
+
+impl std::str::FromStr for ClipboardTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "image" => Ok(Self::Image),
+            "uri-list" | "uri" => Ok(Self::UriList),
+            "text" | "path" => Ok(Self::Text),
+            _ => Err(format!("Invalid clipboard target: {}", s)),
+        }
+    }
+}
+
+pub type Offer = (String, Vec<u8>);
+
+pub fn offers(
+    image: (Vec<u8>, &str),
+    path: Option<&Path>,
+    targets: &[ClipboardTarget],
+) -> Vec<Offer> {
+    let mut offers = Vec::new();
+    let mut image = Some(image);
+
+    for target in targets {
+        match (target, path) {
+            (ClipboardTarget::Image, _) => {
+                if let Some((bytes, mime_type)) = image.take() {
+                    offers.push((mime_type.to_string(), bytes));
+                }
+            }
+            (ClipboardTarget::UriList, Some(path)) => {
+                offers.push((
+                    "text/uri-list".to_string(),
+                    format!("{}\r\n", file_uri(path)).into_bytes(),
+                ));
+            }
+            (ClipboardTarget::Text, Some(path)) => {
+                let text = path.to_string_lossy();
+                for mime_type in TEXT_TYPES {
+                    offers.push((mime_type.to_string(), text.as_bytes().to_vec()));
+                }
+            }
+            (_, None) => {}
+        }
+    }
+
+    offers
+}
+
+// A selection with several mime types needs a client that outlives us, so a
+// double-forked child serves it until another client takes over. The child
+// reports back once the compositor has accepted the selection.
+pub fn serve_detached(offers: Vec<Offer>) -> Result<()> {
+    if !has_data_control()? {
+        return Err(ScreenshotError::ClipboardUnavailable {
+            tool: "wlr-data-control".to_string(),
+        });
+    }
+
+    let (mut reader, writer) = std::io::pipe()?;
+
+    match unsafe { libc::fork() } {
+        -1 => return Err(std::io::Error::last_os_error().into()),
+        0 => {
+            drop(reader);
+            let status = match unsafe { libc::fork() } {
+                0 => {
+                    detach();
+                    i32::from(serve(offers, writer).is_err())
+                }
+                -1 => 1,
+                _ => 0,
+            };
+            unsafe { libc::_exit(status) };
+        }
+        child => {
+            drop(writer);
+            unsafe { libc::waitpid(child, std::ptr::null_mut(), 0) };
+        }
+    }
+
+    // Nothing written means the child could not take the selection.
+    let mut ready = [0; 1];
+    match reader.read(&mut ready)? {
+        1 => Ok(()),
+        _ => Err(ScreenshotError::ClipboardUnavailable {
+            tool: "wlr-data-control".to_string(),
+        }),
+    }
+}
+
+fn has_data_control() -> Result<bool> {
+    let connection = Connection::connect_to_env().map_err(|e| wayland_error(&e))?;
+    let (globals, _) = registry_queue_init::<Server>(&connection).map_err(|e| wayland_error(&e))?;
+
+    Ok(globals.contents().with_list(|globals| {
+        globals
+            .iter()
+            .any(|global| global.interface == ZwlrDataControlManagerV1::interface().name)
+    }))
+}
+
+// Leaves the caller's session and stdio, so neither a launcher killing its
+// process group nor a pipe waiting on our stdout is held up by the server.
+fn detach() {
+    unsafe {
+        libc::setsid();
+        let null = libc::open(c"/dev/null".as_ptr(), libc::O_RDWR);
+        if null >= 0 {
+            for fd in 0..3 {
+                libc::dup2(null, fd);
+            }
+        }
+    }
+}
+
+fn wayland_error(e: &dyn std::fmt::Display) -> ScreenshotError {
+    ScreenshotError::Config(format!("Wayland clipboard error: {}", e))
+}
+
+struct Server {
+    offers: Vec<Offer>,
+    cancelled: bool,
+}
+
+// Blocks until another client takes over the selection.
+fn serve(offers: Vec<Offer>, mut ready: std::io::PipeWriter) -> Result<()> {
+    let connection = Connection::connect_to_env().map_err(|e| wayland_error(&e))?;
+    let (globals, mut queue) =
+        registry_queue_init::<Server>(&connection).map_err(|e| wayland_error(&e))?;
+    let qh = queue.handle();
+
+    let manager: ZwlrDataControlManagerV1 =
+        globals
+            .bind(&qh, 1..=2, ())
+            .map_err(|_| ScreenshotError::ClipboardUnavailable {
+                tool: "wlr-data-control".to_string(),
+            })?;
+    let seat: WlSeat = globals
+        .bind(&qh, 1..=8, ())
+        .map_err(|e| wayland_error(&e))?;
+
+    let source = manager.create_data_source(&qh, ());
+    for (mime_type, _) in &offers {
+        source.offer(mime_type.clone());
+    }
+
+    let device = manager.get_data_device(&seat, &qh, ());
+    device.set_selection(Some(&source));
+
+    let mut server = Server {
+        offers,
+        cancelled: false,
+    };
+    queue
+        .roundtrip(&mut server)
+        .map_err(|e| wayland_error(&e))?;
+    if !server.cancelled {
+        ready.write_all(&[1])?;
+    }
+    drop(ready);
+
+    while !server.cancelled {
+        queue
+            .blocking_dispatch(&mut server)
+            .map_err(|e| wayland_error(&e))?;
+    }
+
+    device.destroy();
+    Ok(())
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for Server {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for Server {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlManagerV1, ()> for Server {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlManagerV1,
+        _: <ZwlrDataControlManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for Server {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_device_v1::Event::Selection { id: Some(offer) }
+            | zwlr_data_control_device_v1::Event::PrimarySelection { id: Some(offer) } => {
+                offer.destroy();
+            }
+            zwlr_data_control_device_v1::Event::Finished => state.cancelled = true,
+            _ => {}
+        }
+    }
+
+    event_created_child!(Server, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrDataControlOfferV1, ()> for Server {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlOfferV1,
+        _: <ZwlrDataControlOfferV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlSourceV1, ()> for Server {
+    fn event(
+        state: &mut Self,
+        source: &ZwlrDataControlSourceV1,
+        event: zwlr_data_control_source_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_source_v1::Event::Send { mime_type, fd } => {
+                if let Some((_, bytes)) = state.offers.iter().find(|(m, _)| *m == mime_type) {
+                    let _ = std::fs::File::from(fd).write_all(bytes);
+                }
+            }
+            zwlr_data_control_source_v1::Event::Cancelled => {
+                source.destroy();
+                state.cancelled = true;
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{Result, ScreenshotError};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub fn file_uri(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    uri
+}
+
+pub fn open(path: &Path) -> Result<()> {
+    Command::new("xdg-open")
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| ScreenshotError::ToolUnavailable {
+            tool: "xdg-open".to_string(),
+            reason: e.to_string(),
+        })?;
+    Ok(())
+}
+
+// Asks the file manager to select the file through org.freedesktop.FileManager1;
+// without one on the bus, the containing directory is opened instead.
+pub fn reveal(path: &Path) -> Result<()> {
+    let status = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.FileManager1",
+            "--object-path",
+            "/org/freedesktop/FileManager1",
+            "--method",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("['{}']", file_uri(path)))
+        .arg("")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        _ => open(path.parent().unwrap_or(Path::new("."))),
+    }
+}
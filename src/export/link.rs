@@ -0,0 +1,72 @@
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkFormat {
+    Org,
+    Markdown,
+}
+
+impl std::str::FromStr for LinkFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "org" | "org-mode" => Ok(Self::Org),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!("Invalid link format: {}", s)),
+        }
+    }
+}
+
+impl LinkFormat {
+    pub fn format(&self, path: &Path, base: Option<&Path>) -> String {
+        let target = base
+            .and_then(|base| relative_path(path, base))
+            .unwrap_or_else(|| path.to_path_buf());
+        let target = target.display().to_string();
+
+        match self {
+            Self::Org => format!("[[file:{}]]", target),
+            Self::Markdown => {
+                let alt = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                if target.contains(' ') {
+                    format!("![{}](<{}>)", alt, target)
+                } else {
+                    format!("![{}]({})", alt, target)
+                }
+            }
+        }
+    }
+}
+
+fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    let base = base.canonicalize().ok()?;
+
+    let path_components: Vec<Component> = path.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if common == 0 {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base_components.len() {
+        relative.push("..");
+    }
+    for component in &path_components[common..] {
+        relative.push(component.as_os_str());
+    }
+
+    Some(relative)
+}
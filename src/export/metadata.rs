@@ -0,0 +1,299 @@
+use crate::{CaptureMode, OutputFormat, Screenshot};
+use chrono::{DateTime, Local};
+
+const SOFTWARE: &str = concat!("wst ", env!("CARGO_PKG_VERSION"));
+const XMP_NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+#[derive(Debug, Clone)]
+pub struct EmbeddedMetadata {
+    pub timestamp: DateTime<Local>,
+    pub mode: CaptureMode,
+    pub monitor: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl EmbeddedMetadata {
+    pub fn new(screenshot: &Screenshot) -> Self {
+        Self {
+            timestamp: screenshot.metadata.timestamp,
+            mode: screenshot.metadata.mode,
+            monitor: None,
+            comment: None,
+        }
+    }
+
+    pub fn monitor(mut self, monitor: Option<&str>) -> Self {
+        self.monitor = monitor.map(str::to_string);
+        self
+    }
+
+    pub fn comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment.filter(|c| !c.is_empty());
+        self
+    }
+
+    fn description(&self) -> String {
+        let mode = format!("{:?}", self.mode).to_lowercase();
+        match &self.monitor {
+            Some(monitor) => format!("{} capture on {}", mode, monitor),
+            None => format!("{} capture", mode),
+        }
+    }
+}
+
+pub fn embed(encoded: Vec<u8>, format: OutputFormat, metadata: &EmbeddedMetadata) -> Vec<u8> {
+    match format {
+        OutputFormat::Png => embed_png(encoded, metadata),
+        OutputFormat::Jpeg => embed_jpeg(encoded, metadata),
+        OutputFormat::Webp | OutputFormat::Pdf | OutputFormat::Clipboard => {
+            tracing::debug!("Metadata embedding is not supported for {:?}", format);
+            encoded
+        }
+    }
+}
+
+fn embed_png(encoded: Vec<u8>, metadata: &EmbeddedMetadata) -> Vec<u8> {
+    // IEND is always the final 12 bytes; the new chunks go right before it.
+    let Some(iend) = encoded.len().checked_sub(12) else {
+        return encoded;
+    };
+    if &encoded[iend + 4..iend + 8] != b"IEND" {
+        return encoded;
+    }
+
+    let mut chunks = Vec::new();
+    png_chunk(&mut chunks, b"tEXt", &text_chunk("Software", SOFTWARE));
+    png_chunk(
+        &mut chunks,
+        b"tEXt",
+        &text_chunk("Creation Time", &metadata.timestamp.to_rfc2822()),
+    );
+    png_chunk(
+        &mut chunks,
+        b"tEXt",
+        &text_chunk("Description", &metadata.description()),
+    );
+    if let Some(comment) = &metadata.comment {
+        png_chunk(&mut chunks, b"iTXt", &itxt_chunk("Comment", comment));
+    }
+    png_chunk(
+        &mut chunks,
+        b"iTXt",
+        &itxt_chunk("XML:com.adobe.xmp", &xmp_packet(metadata)),
+    );
+
+    let mut output = encoded;
+    output.splice(iend..iend, chunks);
+    output
+}
+
+fn text_chunk(keyword: &str, text: &str) -> Vec<u8> {
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+    data.extend(
+        text.chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' }),
+    );
+    data
+}
+
+fn itxt_chunk(keyword: &str, text: &str) -> Vec<u8> {
+    let mut data = keyword.as_bytes().to_vec();
+    // Null separator, uncompressed, no language tag, no translated keyword.
+    data.extend([0, 0, 0, 0, 0]);
+    data.extend(text.as_bytes());
+    data
+}
+
+fn png_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+
+    output.extend((data.len() as u32).to_be_bytes());
+    output.extend(kind);
+    output.extend(data);
+    output.extend(crc.finalize().to_be_bytes());
+}
+
+fn embed_jpeg(encoded: Vec<u8>, metadata: &EmbeddedMetadata) -> Vec<u8> {
+    if encoded.len() < 4 || encoded[..2] != [0xFF, 0xD8] {
+        return encoded;
+    }
+
+    // Keep the JFIF APP0 segment first, as readers expect.
+    let mut position = 2;
+    if encoded[2..4] == [0xFF, 0xE0] && encoded.len() >= 6 {
+        position += 2 + u16::from_be_bytes([encoded[4], encoded[5]]) as usize;
+    }
+    position = position.min(encoded.len());
+
+    let mut segments = Vec::new();
+
+    let mut exif = b"Exif\0\0".to_vec();
+    exif.extend(exif_tiff(metadata));
+    app1_segment(&mut segments, &exif);
+
+    let mut xmp = XMP_NAMESPACE.to_vec();
+    xmp.extend(xmp_packet(metadata).as_bytes());
+    app1_segment(&mut segments, &xmp);
+
+    let mut output = encoded;
+    output.splice(position..position, segments);
+    output
+}
+
+fn app1_segment(output: &mut Vec<u8>, payload: &[u8]) {
+    if payload.len() + 2 > u16::MAX as usize {
+        tracing::warn!("Metadata segment too large; skipped");
+        return;
+    }
+
+    output.extend([0xFF, 0xE1]);
+    output.extend(((payload.len() + 2) as u16).to_be_bytes());
+    output.extend(payload);
+}
+
+struct IfdEntry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    data: Vec<u8>,
+}
+
+impl IfdEntry {
+    fn ascii(tag: u16, value: &str) -> Self {
+        let mut data: Vec<u8> = value
+            .chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .collect();
+        data.push(0);
+        Self {
+            tag,
+            kind: 2,
+            count: data.len() as u32,
+            data,
+        }
+    }
+
+    fn long(tag: u16, value: u32) -> Self {
+        Self {
+            tag,
+            kind: 4,
+            count: 1,
+            data: value.to_be_bytes().to_vec(),
+        }
+    }
+
+    fn user_comment(value: &str) -> Self {
+        let mut data = b"UNICODE\0".to_vec();
+        data.extend(value.encode_utf16().flat_map(u16::to_be_bytes));
+        Self {
+            tag: 0x9286,
+            kind: 7,
+            count: data.len() as u32,
+            data,
+        }
+    }
+}
+
+fn exif_tiff(metadata: &EmbeddedMetadata) -> Vec<u8> {
+    const EXIF_IFD_POINTER: u16 = 0x8769;
+
+    let date = metadata.timestamp.format("%Y:%m:%d %H:%M:%S").to_string();
+
+    let ifd0 = [
+        IfdEntry::ascii(0x010E, &metadata.description()),
+        IfdEntry::ascii(0x0131, SOFTWARE),
+        IfdEntry::ascii(0x0132, &date),
+        IfdEntry::long(EXIF_IFD_POINTER, 0),
+    ];
+
+    let mut exif_ifd = vec![IfdEntry::ascii(0x9003, &date)];
+    if let Some(comment) = &metadata.comment {
+        exif_ifd.push(IfdEntry::user_comment(comment));
+    }
+
+    // Big-endian TIFF header with IFD0 straight after it.
+    let mut tiff = b"MM\0\x2A\0\0\0\x08".to_vec();
+    let ifd0_start = write_ifd(&mut tiff, &ifd0);
+    let exif_start = write_ifd(&mut tiff, &exif_ifd) as u32;
+
+    let pointer_index = ifd0
+        .iter()
+        .position(|e| e.tag == EXIF_IFD_POINTER)
+        .unwrap_or_default();
+    let pointer = ifd0_start + 2 + pointer_index * 12 + 8;
+    tiff[pointer..pointer + 4].copy_from_slice(&exif_start.to_be_bytes());
+
+    tiff
+}
+
+fn write_ifd(tiff: &mut Vec<u8>, entries: &[IfdEntry]) -> usize {
+    let start = tiff.len();
+    let data_start = start + 2 + entries.len() * 12 + 4;
+    let mut data = Vec::new();
+
+    tiff.extend((entries.len() as u16).to_be_bytes());
+    for entry in entries {
+        tiff.extend(entry.tag.to_be_bytes());
+        tiff.extend(entry.kind.to_be_bytes());
+        tiff.extend(entry.count.to_be_bytes());
+
+        if entry.data.len() <= 4 {
+            let mut value = entry.data.clone();
+            value.resize(4, 0);
+            tiff.extend(value);
+        } else {
+            tiff.extend(((data_start + data.len()) as u32).to_be_bytes());
+            data.extend(&entry.data);
+            if data.len() % 2 == 1 {
+                data.push(0);
+            }
+        }
+    }
+    tiff.extend(0u32.to_be_bytes());
+    tiff.extend(data);
+
+    start
+}
+
+fn xmp_packet(metadata: &EmbeddedMetadata) -> String {
+    let comment = metadata
+        .comment
+        .as_deref()
+        .map(|comment| {
+            format!(
+                "<dc:description><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:description>",
+                escape_xml(comment)
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        concat!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">",
+            "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">",
+            "<rdf:Description rdf:about=\"\"",
+            " xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"",
+            " xmlns:dc=\"http://purl.org/dc/elements/1.1/\"",
+            " xmp:CreatorTool=\"{}\" xmp:CreateDate=\"{}\">",
+            "<dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:title>",
+            "{}",
+            "</rdf:Description></rdf:RDF></x:xmpmeta>",
+            "<?xpacket end=\"r\"?>"
+        ),
+        SOFTWARE,
+        metadata.timestamp.to_rfc3339(),
+        escape_xml(&metadata.description()),
+        comment
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,6 +1,34 @@
-use crate::{OutputFormat, Result, Screenshot, ScreenshotError};
+pub mod clipboard;
+pub mod desktop;
+pub mod link;
+pub mod metadata;
+mod montage;
+mod pdf;
+pub mod remote;
+pub mod targets;
+
+pub use clipboard::ClipboardTarget;
+pub use link::LinkFormat;
+pub use metadata::EmbeddedMetadata;
+pub use remote::{RemoteHost, RemoteTarget};
+pub use targets::{ExportOutcome, ExportTarget, ExportTargets};
+
+use crate::{OutputFormat, Result, Screenshot, ScreenshotError, config::ClipboardConfig};
+use image::codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder};
+use image::{ExtendedColorType, ImageEncoder, RgbaImage};
+use std::borrow::Cow;
+use std::io::Write;
 use std::path::Path;
 
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub icc_profile: Option<Vec<u8>>,
+    pub srgb_convert: bool,
+    pub palette: Option<u32>,
+    pub metadata: Option<EmbeddedMetadata>,
+    pub quality: Option<u8>,
+}
+
 pub struct Exporter;
 
 impl Exporter {
@@ -8,65 +36,457 @@ impl Exporter {
         screenshot: &Screenshot,
         path: P,
         format: OutputFormat,
+    ) -> Result<u64> {
+        Self::save_with_options(screenshot, path, format, &ExportOptions::default())
+    }
+
+    pub fn save_with_options<P: AsRef<Path>>(
+        screenshot: &Screenshot,
+        path: P,
+        format: OutputFormat,
+        options: &ExportOptions,
     ) -> Result<u64> {
         let path = path.as_ref();
 
+        if format == OutputFormat::Clipboard {
+            return Err(ScreenshotError::Config(
+                "Use copy_to_clipboard instead".to_string(),
+            ));
+        }
+
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        Self::write_to(screenshot, format, options, &mut writer)?;
+        writer.flush()?;
+
+        let metadata = std::fs::metadata(path)?;
+        Ok(metadata.len())
+    }
+
+    pub async fn save_async<P: AsRef<Path>>(
+        screenshot: &Screenshot,
+        path: P,
+        format: OutputFormat,
+        options: &ExportOptions,
+    ) -> Result<u64> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Encoding is CPU-bound, so it runs off the async worker threads.
+        let screenshot = screenshot.clone();
+        let options = options.clone();
+        let encoded =
+            tokio::task::spawn_blocking(move || Self::encode(&screenshot, format, &options))
+                .await
+                .map_err(|e| ScreenshotError::Unknown(format!("Encoding task failed: {}", e)))??;
+
+        tokio::fs::write(path, &encoded).await?;
+        Ok(encoded.len() as u64)
+    }
+
+    pub fn encode(
+        screenshot: &Screenshot,
+        format: OutputFormat,
+        options: &ExportOptions,
+    ) -> Result<Vec<u8>> {
+        let mut encoded = Vec::new();
+        Self::write_to(screenshot, format, options, &mut encoded)?;
+        Ok(encoded)
+    }
+
+    pub fn write_to<W: Write>(
+        screenshot: &Screenshot,
+        format: OutputFormat,
+        options: &ExportOptions,
+        mut writer: W,
+    ) -> Result<()> {
+        let (data, icc_profile) = Self::apply_color_management(&screenshot.data, options)?;
+        let data = match options.palette {
+            Some(levels) => Cow::Owned(crate::processing::quantize(&data, levels)),
+            None => data,
+        };
+
+        match &options.metadata {
+            // Metadata is spliced into the finished file, so this path buffers.
+            Some(embedded) => {
+                let mut encoded = Vec::new();
+                Self::write_encoded(&data, format, icc_profile, options.quality, &mut encoded)?;
+                writer.write_all(&metadata::embed(encoded, format, embedded))?;
+            }
+            None => Self::write_encoded(&data, format, icc_profile, options.quality, &mut writer)?,
+        }
+
+        Ok(())
+    }
+
+    fn apply_color_management<'a>(
+        data: &'a RgbaImage,
+        options: &ExportOptions,
+    ) -> Result<(Cow<'a, RgbaImage>, Option<Vec<u8>>)> {
+        match (&options.icc_profile, options.srgb_convert) {
+            (Some(profile), true) => Ok((Cow::Owned(convert_to_srgb(data, profile)?), None)),
+            (profile, _) => Ok((Cow::Borrowed(data), profile.clone())),
+        }
+    }
+
+    fn write_encoded<W: Write>(
+        data: &RgbaImage,
+        format: OutputFormat,
+        icc_profile: Option<Vec<u8>>,
+        quality: Option<u8>,
+        writer: W,
+    ) -> Result<()> {
+        let (width, height) = data.dimensions();
+
         match format {
             OutputFormat::Png => {
-                screenshot
-                    .data
-                    .save_with_format(path, image::ImageFormat::Png)?;
+                let mut encoder = PngEncoder::new(writer);
+                embed_icc_profile(&mut encoder, icc_profile);
+                encoder.write_image(data.as_raw(), width, height, ExtendedColorType::Rgba8)?;
             }
             OutputFormat::Jpeg => {
-                let rgb = image::DynamicImage::ImageRgba8(screenshot.data.clone()).to_rgb8();
-                rgb.save_with_format(path, image::ImageFormat::Jpeg)?;
+                let rgb = image::DynamicImage::ImageRgba8(data.clone()).to_rgb8();
+                let mut encoder = JpegEncoder::new_with_quality(writer, quality.unwrap_or(75));
+                embed_icc_profile(&mut encoder, icc_profile);
+                encoder.write_image(rgb.as_raw(), width, height, ExtendedColorType::Rgb8)?;
             }
             OutputFormat::Webp => {
-                screenshot
-                    .data
-                    .save_with_format(path, image::ImageFormat::WebP)?;
+                let mut encoder = WebPEncoder::new_lossless(writer);
+                embed_icc_profile(&mut encoder, icc_profile);
+                encoder.write_image(data.as_raw(), width, height, ExtendedColorType::Rgba8)?;
             }
+            OutputFormat::Pdf => pdf::write_pdf(&[data], writer)?,
             OutputFormat::Clipboard => {
-                return Err(ScreenshotError::Config(
-                    "Use copy_to_clipboard instead".to_string(),
-                ));
+                return Err(ScreenshotError::UnsupportedFormat {
+                    format: "clipboard".to_string(),
+                });
             }
         }
 
-        let metadata = std::fs::metadata(path)?;
-        Ok(metadata.len())
+        Ok(())
     }
 
     pub fn copy_to_clipboard(screenshot: &Screenshot) -> Result<()> {
-        let temp_dir = std::env::temp_dir();
-        let temp_file = temp_dir.join("wst_clipboard.png");
+        Self::copy_to_clipboard_with(screenshot, &ClipboardConfig::default())
+    }
+
+    pub fn copy_to_clipboard_with(
+        screenshot: &Screenshot,
+        clipboard: &ClipboardConfig,
+    ) -> Result<()> {
+        let (bytes, mime_type) = Self::encode_for_clipboard(&screenshot.data, clipboard)?;
+
+        if clipboard.persist {
+            Self::wl_copy_detached(&bytes, mime_type)
+        } else {
+            Self::wl_copy(&bytes, mime_type)
+        }
+    }
+
+    pub fn copy_to_clipboard_targets(
+        screenshot: &Screenshot,
+        clipboard: &ClipboardConfig,
+        targets: &[ClipboardTarget],
+        path: Option<&Path>,
+    ) -> Result<()> {
+        if targets == [ClipboardTarget::Image] {
+            return Self::copy_to_clipboard_with(screenshot, clipboard);
+        }
+
+        let image = if targets.contains(&ClipboardTarget::Image) {
+            Self::encode_for_clipboard(&screenshot.data, clipboard)?
+        } else {
+            (Vec::new(), "image/png")
+        };
+
+        let offers = clipboard::offers(image, path, targets);
+        if offers.is_empty() {
+            return Err(ScreenshotError::Config(
+                "No clipboard targets apply without a saved file".to_string(),
+            ));
+        }
+
+        // Without wlr-data-control only wl-copy's single mime type is left.
+        let image = offers
+            .iter()
+            .find(|(mime_type, _)| mime_type.starts_with("image/"))
+            .cloned();
+        match (clipboard::serve_detached(offers), image) {
+            (Err(ScreenshotError::ClipboardUnavailable { .. }), Some((mime_type, bytes))) => {
+                tracing::warn!("wlr-data-control is unavailable; copying the image only");
+                Self::wl_copy_detached(&bytes, &mime_type)
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn encode_for_clipboard(
+        data: &RgbaImage,
+        clipboard: &ClipboardConfig,
+    ) -> Result<(Vec<u8>, &'static str)> {
+        let mut image = match clipboard.max_dimension {
+            Some(max) if data.width() > max || data.height() > max => {
+                let scale = max as f64 / data.width().max(data.height()) as f64;
+                image::imageops::resize(
+                    data,
+                    ((data.width() as f64 * scale).round() as u32).max(1),
+                    ((data.height() as f64 * scale).round() as u32).max(1),
+                    image::imageops::FilterType::Triangle,
+                )
+            }
+            _ => data.clone(),
+        };
+
+        let mut png = Vec::new();
+        Self::write_encoded(&image, OutputFormat::Png, None, None, &mut png)?;
+
+        let Some(max_bytes) = clipboard.max_bytes else {
+            return Ok((png, "image/png"));
+        };
+
+        if png.len() as u64 <= max_bytes {
+            return Ok((png, "image/png"));
+        }
+
+        let mut jpeg = Vec::new();
 
-        screenshot
-            .data
-            .save_with_format(&temp_file, image::ImageFormat::Png)?;
+        for quality in [90, 80, 70, 60] {
+            Self::encode_jpeg(&image, quality, &mut jpeg)?;
 
+            if jpeg.len() as u64 <= max_bytes {
+                return Ok((jpeg, "image/jpeg"));
+            }
+        }
+
+        while jpeg.len() as u64 > max_bytes && image.width() > 64 && image.height() > 64 {
+            image = image::imageops::resize(
+                &image,
+                image.width() * 3 / 4,
+                image.height() * 3 / 4,
+                image::imageops::FilterType::Triangle,
+            );
+            Self::encode_jpeg(&image, 70, &mut jpeg)?;
+        }
+
+        Ok((jpeg, "image/jpeg"))
+    }
+
+    fn encode_jpeg(image: &RgbaImage, quality: u8, buffer: &mut Vec<u8>) -> Result<()> {
+        let rgb = image::DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+
+        buffer.clear();
+        JpegEncoder::new_with_quality(buffer, quality).write_image(
+            rgb.as_raw(),
+            rgb.width(),
+            rgb.height(),
+            ExtendedColorType::Rgb8,
+        )?;
+
+        Ok(())
+    }
+
+    fn wl_copy(bytes: &[u8], mime_type: &str) -> Result<()> {
         let output = std::process::Command::new("wl-copy")
             .arg("--type")
-            .arg("image/png")
+            .arg(mime_type)
             .stdin(std::process::Stdio::piped())
             .spawn()
             .and_then(|mut child| {
-                use std::io::Write;
                 if let Some(mut stdin) = child.stdin.take() {
-                    let bytes = std::fs::read(&temp_file)?;
-                    stdin.write_all(&bytes)?;
+                    stdin.write_all(bytes)?;
                 }
                 child.wait()
             });
 
         match output {
             Ok(status) if status.success() => Ok(()),
-            _ => Err(ScreenshotError::Config("wl-copy not available".to_string())),
+            _ => Err(ScreenshotError::ClipboardUnavailable {
+                tool: "wl-copy".to_string(),
+            }),
+        }
+    }
+
+    // wl-copy's own fork stays in our session and process group, so it dies
+    // with launchers that kill the whole group on exit. Serve from a separate
+    // group instead, until another client takes the selection.
+    fn wl_copy_detached(bytes: &[u8], mime_type: &str) -> Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        let mut child = std::process::Command::new("wl-copy")
+            .arg("--foreground")
+            .arg("--type")
+            .arg(mime_type)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map_err(|_| ScreenshotError::ClipboardUnavailable {
+                tool: "wl-copy".to_string(),
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(bytes)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn paste_from_clipboard() -> Result<RgbaImage> {
+        let types = std::process::Command::new("wl-paste")
+            .arg("--list-types")
+            .output()
+            .map_err(|_| ScreenshotError::ClipboardUnavailable {
+                tool: "wl-paste".to_string(),
+            })?;
+
+        let types = String::from_utf8_lossy(&types.stdout);
+        let mime_type = types
+            .lines()
+            .find(|t| *t == "image/png")
+            .or_else(|| types.lines().find(|t| t.starts_with("image/")))
+            .ok_or_else(|| ScreenshotError::Config("Clipboard holds no image".to_string()))?;
+
+        let output = std::process::Command::new("wl-paste")
+            .arg("--no-newline")
+            .arg("--type")
+            .arg(mime_type)
+            .output()
+            .map_err(|_| ScreenshotError::ClipboardUnavailable {
+                tool: "wl-paste".to_string(),
+            })?;
+
+        if !output.status.success() {
+            return Err(ScreenshotError::Config(format!(
+                "wl-paste failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(image::load_from_memory(&output.stdout)?.to_rgba8())
+    }
+
+    pub fn copy_text_to_clipboard(text: &str) -> Result<()> {
+        Self::wl_copy(text.as_bytes(), "text/plain")
+    }
+
+    // satty and swappy write their result to a separate file; when the user
+    // closes them without saving, the original capture is kept.
+    pub fn edit_with(screenshot: &Screenshot, command: &str) -> Result<Screenshot> {
+        let command = match command {
+            "auto" => ["satty", "swappy"]
+                .into_iter()
+                .find(|program| on_path(program))
+                .ok_or_else(|| {
+                    ScreenshotError::Config("Neither satty nor swappy is installed".to_string())
+                })?,
+            command => command,
+        };
+
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| ScreenshotError::Config("No external editor given".to_string()))?;
+
+        let stem = format!(
+            "wst-edit-{}-{}",
+            std::process::id(),
+            screenshot.metadata.timestamp.timestamp_millis()
+        );
+        let path = std::env::temp_dir().join(format!("{}.png", stem));
+        Self::save(screenshot, &path, OutputFormat::Png)?;
+
+        let mut cmd = std::process::Command::new(program);
+        cmd.args(parts);
+        let output = match program {
+            "satty" => {
+                let output = std::env::temp_dir().join(format!("{}-out.png", stem));
+                cmd.arg("--filename")
+                    .arg(&path)
+                    .arg("--output-filename")
+                    .arg(&output)
+                    .arg("--early-exit");
+                output
+            }
+            "swappy" => {
+                let output = std::env::temp_dir().join(format!("{}-out.png", stem));
+                cmd.arg("-f").arg(&path).arg("-o").arg(&output);
+                output
+            }
+            _ => {
+                cmd.arg(&path);
+                path.clone()
+            }
+        };
+
+        let status = cmd.status();
+        let cleanup = || {
+            let _ = std::fs::remove_file(&path);
+            let _ = std::fs::remove_file(&output);
+        };
+
+        let edited = match status {
+            Ok(status) if status.success() && output.exists() => {
+                image::open(&output).map(|i| i.to_rgba8())
+            }
+            Ok(status) if status.success() => Ok(screenshot.data.clone()),
+            Ok(status) => {
+                cleanup();
+                return Err(ScreenshotError::Config(format!(
+                    "{} exited with {}",
+                    program, status
+                )));
+            }
+            Err(e) => {
+                cleanup();
+                return Err(ScreenshotError::Config(format!(
+                    "Failed to launch {}: {}",
+                    program, e
+                )));
+            }
+        };
+        cleanup();
+
+        let mut result = Screenshot::new(
+            edited?,
+            screenshot.metadata.mode,
+            screenshot.metadata.format,
+        );
+        result.metadata.timestamp = screenshot.metadata.timestamp;
+        Ok(result)
+    }
+
+    pub fn save_pdf<P: AsRef<Path>>(screenshots: &[Screenshot], path: P) -> Result<u64> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
         }
+
+        let pages: Vec<&RgbaImage> = screenshots.iter().map(|s| &s.data).collect();
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        pdf::write_pdf(&pages, &mut writer)?;
+        writer.flush()?;
+
+        Ok(std::fs::metadata(path)?.len())
+    }
+
+    pub fn montage(screenshots: &[Screenshot], columns: u32, spacing: u32) -> Result<Screenshot> {
+        montage::montage(screenshots, &[], columns, spacing)
+    }
+
+    pub fn montage_labeled(
+        screenshots: &[Screenshot],
+        labels: &[String],
+        columns: u32,
+        spacing: u32,
+    ) -> Result<Screenshot> {
+        montage::montage(screenshots, labels, columns, spacing)
     }
 
     pub fn export_metadata<P: AsRef<Path>>(screenshot: &Screenshot, path: P) -> Result<()> {
@@ -75,3 +495,55 @@ impl Exporter {
         Ok(())
     }
 }
+
+fn embed_icc_profile<E: ImageEncoder>(encoder: &mut E, icc_profile: Option<Vec<u8>>) {
+    if let Some(profile) = icc_profile
+        && let Err(e) = encoder.set_icc_profile(profile)
+    {
+        tracing::warn!("ICC profile not embedded: {}", e);
+    }
+}
+
+#[cfg(feature = "color-management")]
+fn convert_to_srgb(data: &RgbaImage, icc_profile: &[u8]) -> Result<RgbaImage> {
+    use lcms2::{Intent, PixelFormat, Profile, Transform};
+
+    let source =
+        Profile::new_icc(icc_profile).map_err(|e| ScreenshotError::Config(e.to_string()))?;
+    let target = Profile::new_srgb();
+    let transform: Transform<[u8; 4], [u8; 4]> = Transform::new(
+        &source,
+        PixelFormat::RGBA_8,
+        &target,
+        PixelFormat::RGBA_8,
+        Intent::Perceptual,
+    )
+    .map_err(|e| ScreenshotError::Config(e.to_string()))?;
+
+    let mut pixels: Vec<[u8; 4]> = data.pixels().map(|p| p.0).collect();
+    transform.transform_in_place(&mut pixels);
+
+    let mut converted = data.clone();
+    for (pixel, converted_pixel) in converted.pixels_mut().zip(pixels) {
+        pixel.0 = [
+            converted_pixel[0],
+            converted_pixel[1],
+            converted_pixel[2],
+            pixel[3],
+        ];
+    }
+
+    Ok(converted)
+}
+
+#[cfg(not(feature = "color-management"))]
+fn convert_to_srgb(_data: &RgbaImage, _icc_profile: &[u8]) -> Result<RgbaImage> {
+    Err(ScreenshotError::Config(
+        "sRGB conversion requires the color-management feature".to_string(),
+    ))
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
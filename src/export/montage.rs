@@ -0,0 +1,84 @@
+use crate::processing::text::{TextStyle, draw_text, text_size};
+use crate::{CaptureMode, OutputFormat, Result, Screenshot, ScreenshotError};
+use image::{Rgba, RgbaImage};
+
+const LABEL_SIZE: f32 = 16.0;
+const LABEL_GAP: u32 = 6;
+const BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_COLOR: Rgba<u8> = Rgba([51, 51, 51, 255]);
+
+pub fn montage(
+    screenshots: &[Screenshot],
+    labels: &[String],
+    columns: u32,
+    spacing: u32,
+) -> Result<Screenshot> {
+    if screenshots.is_empty() {
+        return Err(ScreenshotError::Config(
+            "A montage needs at least one image".to_string(),
+        ));
+    }
+
+    let columns = columns.clamp(1, screenshots.len() as u32) as usize;
+    let rows = screenshots.len().div_ceil(columns);
+
+    let font = if labels.iter().any(|l| !l.is_empty()) {
+        match TextStyle::default().load_font() {
+            Ok(font) => Some(font),
+            Err(e) => {
+                tracing::warn!("Montage labels disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let label_height = if font.is_some() {
+        LABEL_SIZE as u32 + LABEL_GAP
+    } else {
+        0
+    };
+
+    let mut column_widths = vec![0u32; columns];
+    let mut row_heights = vec![0u32; rows];
+    for (i, screenshot) in screenshots.iter().enumerate() {
+        let (column, row) = (i % columns, i / columns);
+        column_widths[column] = column_widths[column].max(screenshot.width());
+        row_heights[row] = row_heights[row].max(screenshot.height() + label_height);
+    }
+
+    let width = column_widths.iter().sum::<u32>() + spacing * (columns as u32 + 1);
+    let height = row_heights.iter().sum::<u32>() + spacing * (rows as u32 + 1);
+    let mut canvas = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    for (i, screenshot) in screenshots.iter().enumerate() {
+        let (column, row) = (i % columns, i / columns);
+        let cell_x = spacing * (column as u32 + 1) + column_widths[..column].iter().sum::<u32>();
+        let cell_y = spacing * (row as u32 + 1) + row_heights[..row].iter().sum::<u32>();
+        let x = cell_x + (column_widths[column] - screenshot.width()) / 2;
+
+        image::imageops::overlay(&mut canvas, &screenshot.data, x as i64, cell_y as i64);
+
+        let (Some(font), Some(label)) = (&font, labels.get(i)) else {
+            continue;
+        };
+        let (text_width, _) = text_size(label, font, LABEL_SIZE);
+        let text_x = cell_x as i32 + (column_widths[column] as i32 - text_width as i32) / 2;
+        let text_y = (cell_y + screenshot.height() + LABEL_GAP) as i32;
+        draw_text(
+            &mut canvas,
+            label,
+            text_x.max(cell_x as i32),
+            text_y,
+            font,
+            LABEL_SIZE,
+            LABEL_COLOR,
+        );
+    }
+
+    Ok(Screenshot::new(
+        canvas,
+        CaptureMode::Screen,
+        OutputFormat::Png,
+    ))
+}
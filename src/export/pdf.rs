@@ -0,0 +1,145 @@
+use crate::Result;
+use flate2::{Compression, write::ZlibEncoder};
+use image::RgbaImage;
+use std::io::Write;
+
+// Pages are sized so one image pixel maps to one CSS pixel (96 dpi).
+const POINTS_PER_PIXEL: f64 = 0.75;
+
+#[derive(Default)]
+struct Document {
+    buffer: Vec<u8>,
+    offsets: Vec<Option<usize>>,
+}
+
+impl Document {
+    fn reserve(&mut self) -> usize {
+        self.offsets.push(None);
+        self.offsets.len()
+    }
+
+    fn object(&mut self, id: usize, body: &[u8]) {
+        self.offsets[id - 1] = Some(self.buffer.len());
+        self.buffer
+            .extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+        self.buffer.extend_from_slice(body);
+        self.buffer.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, id: usize, dictionary: &str, data: &[u8]) {
+        let mut body =
+            format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.object(id, &body);
+    }
+
+    fn finish(mut self, root: usize) -> Vec<u8> {
+        let xref = self.buffer.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            table.push_str(&format!("{:010} 00000 n \n", offset.unwrap_or_default()));
+        }
+        table.push_str(&format!(
+            "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.offsets.len() + 1,
+            root,
+            xref
+        ));
+
+        self.buffer.extend_from_slice(table.as_bytes());
+        self.buffer
+    }
+}
+
+pub fn write_pdf<W: Write>(pages: &[&RgbaImage], mut writer: W) -> Result<()> {
+    let mut document = Document::default();
+    document
+        .buffer
+        .extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    let catalog = document.reserve();
+    let page_tree = document.reserve();
+    let mut kids = Vec::with_capacity(pages.len());
+
+    for image in pages {
+        let (width, height) = image.dimensions();
+        let page_width = width as f64 * POINTS_PER_PIXEL;
+        let page_height = height as f64 * POINTS_PER_PIXEL;
+
+        let page = document.reserve();
+        let contents = document.reserve();
+        let xobject = document.reserve();
+
+        let rgb: Vec<u8> = image.pixels().flat_map(|p| [p[0], p[1], p[2]]).collect();
+        let opaque = image.pixels().all(|p| p[3] == 255);
+
+        let soft_mask = if opaque {
+            String::new()
+        } else {
+            let mask = document.reserve();
+            let alpha: Vec<u8> = image.pixels().map(|p| p[3]).collect();
+            document.stream(
+                mask,
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode",
+                    width, height
+                ),
+                &deflate(&alpha)?,
+            );
+            format!(" /SMask {} 0 R", mask)
+        };
+
+        document.stream(
+            xobject,
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode{}",
+                width, height, soft_mask
+            ),
+            &deflate(&rgb)?,
+        );
+
+        document.stream(
+            contents,
+            "",
+            format!(
+                "q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q",
+                page_width, page_height
+            )
+            .as_bytes(),
+        );
+
+        document.object(
+            page,
+            format!(
+                "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                page_tree, page_width, page_height, xobject, contents
+            )
+            .as_bytes(),
+        );
+        kids.push(format!("{} 0 R", page));
+    }
+
+    document.object(
+        page_tree,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            kids.len()
+        )
+        .as_bytes(),
+    );
+    document.object(
+        catalog,
+        format!("<< /Type /Catalog /Pages {} 0 R >>", page_tree).as_bytes(),
+    );
+
+    writer.write_all(&document.finish(catalog))?;
+    Ok(())
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
@@ -0,0 +1,134 @@
+use crate::{Result, ScreenshotError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteHost {
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: String,
+    pub identity_file: Option<PathBuf>,
+    pub public_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTarget {
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: String,
+    pub identity_file: Option<PathBuf>,
+    pub public_url: Option<String>,
+}
+
+impl RemoteTarget {
+    pub fn is_remote(spec: &str) -> bool {
+        spec.starts_with("sftp://") || spec.starts_with("scp://")
+    }
+
+    // sftp://[user@]host[:port]/path, where host may also name a [remote.NAME]
+    // entry from the config; a trailing slash keeps the local file name.
+    pub fn parse(spec: &str, hosts: &BTreeMap<String, RemoteHost>) -> Result<Self> {
+        let invalid = || {
+            ScreenshotError::Config(format!(
+                "Invalid remote target: {} (expected sftp://[user@]host[:port]/path)",
+                spec
+            ))
+        };
+
+        let rest = spec
+            .strip_prefix("sftp://")
+            .or_else(|| spec.strip_prefix("scp://"))
+            .ok_or_else(invalid)?;
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if authority.is_empty() {
+            return Err(invalid());
+        }
+
+        if let Some(configured) = hosts.get(authority) {
+            let path = if path.is_empty() {
+                configured.path.clone()
+            } else {
+                format!("{}/{}", configured.path.trim_end_matches('/'), path)
+            };
+
+            return Ok(Self {
+                host: configured.host.clone(),
+                port: configured.port,
+                path,
+                identity_file: configured.identity_file.clone(),
+                public_url: configured.public_url.clone(),
+            });
+        }
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse().map_err(|_| invalid())?)),
+            None => (authority, None),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: format!("/{}", path),
+            identity_file: None,
+            public_url: None,
+        })
+    }
+
+    pub fn remote_path(&self, local: &Path) -> String {
+        if self.path.is_empty() || self.path.ends_with('/') {
+            let name = local
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("{}{}", self.path, name)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    // BatchMode stops scp from prompting, so authentication has to come
+    // from ssh-agent or the configured identity file.
+    pub fn send(&self, local: &Path) -> Result<String> {
+        let remote_path = self.remote_path(local);
+
+        let mut cmd = Command::new("scp");
+        cmd.args(["-q", "-o", "BatchMode=yes"]);
+        if let Some(port) = self.port {
+            cmd.arg("-P").arg(port.to_string());
+        }
+        if let Some(identity) = &self.identity_file {
+            cmd.arg("-i").arg(identity);
+        }
+
+        let output = cmd
+            .arg(local)
+            .arg(format!("{}:{}", self.host, remote_path))
+            .output()
+            .map_err(|e| ScreenshotError::ToolUnavailable {
+                tool: "scp".to_string(),
+                reason: e.to_string(),
+            })?;
+
+        if !output.status.success() {
+            return Err(ScreenshotError::RemoteFailed(format!(
+                "Copy to {} failed: {}",
+                self.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(match &self.public_url {
+            Some(url) => {
+                let name = Path::new(&remote_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{}/{}", url.trim_end_matches('/'), name)
+            }
+            None => format!("{}:{}", self.host, remote_path),
+        })
+    }
+}
@@ -0,0 +1,154 @@
+use super::{ExportOptions, Exporter, RemoteTarget};
+use crate::{
+    OutputFormat, Result, Screenshot, ScreenshotError, config::Config, upload::UploadTarget,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportTarget {
+    File(PathBuf),
+    Clipboard,
+    Upload(Option<String>),
+    Remote(RemoteTarget),
+    Stdout(OutputFormat),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportTargets {
+    targets: Vec<ExportTarget>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportOutcome {
+    pub files: Vec<PathBuf>,
+    pub urls: Vec<String>,
+    pub remote: Vec<String>,
+    pub copied: bool,
+}
+
+impl ExportTargets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, target: ExportTarget) {
+        if !self.targets.contains(&target) {
+            self.targets.push(target);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn contains(&self, target: &ExportTarget) -> bool {
+        self.targets.contains(target)
+    }
+
+    pub fn writes_stdout(&self) -> bool {
+        self.targets
+            .iter()
+            .any(|t| matches!(t, ExportTarget::Stdout(_)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ExportTarget> {
+        self.targets.iter()
+    }
+
+    // Every format is encoded once and the bytes reused for all targets that
+    // share it; uploads go out from the first file written.
+    pub fn export(
+        &self,
+        screenshot: &Screenshot,
+        format: OutputFormat,
+        options: &ExportOptions,
+        config: &Config,
+    ) -> Result<ExportOutcome> {
+        let mut encoded: HashMap<OutputFormat, Vec<u8>> = HashMap::new();
+        let mut encode = |format: OutputFormat| -> Result<Vec<u8>> {
+            if let Some(bytes) = encoded.get(&format) {
+                return Ok(bytes.clone());
+            }
+            let bytes = Exporter::encode(screenshot, format, options)?;
+            encoded.insert(format, bytes.clone());
+            Ok(bytes)
+        };
+
+        let mut outcome = ExportOutcome::default();
+
+        for target in &self.targets {
+            match target {
+                ExportTarget::File(path) => {
+                    let format = OutputFormat::from_path(path).unwrap_or(format);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(path, encode(format)?)?;
+                    outcome.files.push(path.clone());
+                }
+                ExportTarget::Stdout(format) => {
+                    let mut stdout = std::io::stdout().lock();
+                    stdout.write_all(&encode(*format)?)?;
+                    stdout.flush()?;
+                }
+                ExportTarget::Clipboard | ExportTarget::Upload(_) | ExportTarget::Remote(_) => {}
+            }
+        }
+
+        if self.contains(&ExportTarget::Clipboard) {
+            Exporter::copy_to_clipboard_targets(
+                screenshot,
+                &config.clipboard,
+                &config.clipboard_targets,
+                outcome.files.first().map(PathBuf::as_path),
+            )?;
+            outcome.copied = true;
+        }
+
+        for target in &self.targets {
+            let ExportTarget::Remote(remote) = target else {
+                continue;
+            };
+
+            match outcome.files.first() {
+                Some(file) => outcome.remote.push(remote.send(file)?),
+                None => {
+                    let format = OutputFormat::from_path(Path::new(&remote.path)).unwrap_or(format);
+                    let file = std::env::temp_dir().join(format!(
+                        "{}.{}",
+                        config.generate_filename(),
+                        format.extension()
+                    ));
+                    std::fs::write(&file, encode(format)?)?;
+                    let sent = remote.send(&file);
+                    let _ = std::fs::remove_file(&file);
+                    outcome.remote.push(sent?);
+                }
+            }
+        }
+
+        for target in &self.targets {
+            let ExportTarget::Upload(name) = target else {
+                continue;
+            };
+
+            let file = outcome.files.first().ok_or_else(|| {
+                ScreenshotError::Config(
+                    "Uploads need a saved file and cannot target only the clipboard".to_string(),
+                )
+            })?;
+
+            let (name, upload) = UploadTarget::find(
+                name.as_deref(),
+                config.default_upload.as_deref(),
+                &config.uploads,
+            )?;
+            let url = upload.upload(name, file, screenshot)?;
+            outcome.urls.push(url);
+        }
+
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{Result, Screenshot, ScreenshotMetadata, error::ScreenshotError};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    pub metadata: ScreenshotMetadata,
+}
+
+pub struct History;
+
+impl History {
+    pub fn state_directory() -> Result<PathBuf> {
+        crate::paths::state_dir()
+            .ok_or_else(|| ScreenshotError::Config("Cannot determine state directory".to_string()))
+    }
+
+    fn history_file_path() -> Result<PathBuf> {
+        Ok(Self::state_directory()?.join("history.jsonl"))
+    }
+
+    pub fn record<P: AsRef<Path>>(path: P, screenshot: &Screenshot) -> Result<()> {
+        let history_path = Self::history_file_path()?;
+
+        if let Some(parent) = history_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let path = path.as_ref();
+        let entry = HistoryEntry {
+            path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+            metadata: screenshot.metadata.clone(),
+        };
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(history_path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        Ok(())
+    }
+
+    pub fn entries() -> Result<Vec<HistoryEntry>> {
+        let history_path = Self::history_file_path()?;
+
+        if !history_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = std::fs::read_to_string(history_path)?;
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    pub fn remove<P: AsRef<Path>>(path: P) -> Result<()> {
+        let history_path = Self::history_file_path()?;
+
+        if !history_path.exists() {
+            return Ok(());
+        }
+
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let contents = std::fs::read_to_string(&history_path)?;
+        let kept: String = contents
+            .lines()
+            .filter(|line| {
+                serde_json::from_str::<HistoryEntry>(line).map_or(true, |entry| entry.path != path)
+            })
+            .map(|line| format!("{}\n", line))
+            .collect();
+        std::fs::write(history_path, kept)?;
+
+        Ok(())
+    }
+
+    pub fn last() -> Result<Option<HistoryEntry>> {
+        Ok(Self::entries()?.pop())
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Result, ScreenshotError};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use wayland_client::globals::{GlobalListContents, registry_queue_init};
+use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+// The compositor reports idle and resume through ext-idle-notify; the
+// events are read on a thread of their own so the watch loop never blocks.
+pub struct IdleMonitor {
+    idle: Arc<AtomicBool>,
+}
+
+struct State {
+    idle: Arc<AtomicBool>,
+}
+
+impl IdleMonitor {
+    pub fn spawn(timeout: Duration) -> Result<Self> {
+        let wayland_error =
+            |e: &dyn std::fmt::Display| ScreenshotError::Config(format!("Wayland error: {}", e));
+
+        let connection = Connection::connect_to_env().map_err(|e| wayland_error(&e))?;
+        let (globals, mut queue) =
+            registry_queue_init::<State>(&connection).map_err(|e| wayland_error(&e))?;
+        let qh = queue.handle();
+
+        let notifier: ExtIdleNotifierV1 = globals.bind(&qh, 1..=1, ()).map_err(|_| {
+            ScreenshotError::Config("Compositor does not support ext-idle-notify".to_string())
+        })?;
+        let seat: WlSeat = globals
+            .bind(&qh, 1..=8, ())
+            .map_err(|e| wayland_error(&e))?;
+
+        let millis = timeout.as_millis().clamp(1000, u32::MAX as u128) as u32;
+        let notification = notifier.get_idle_notification(millis, &seat, &qh, ());
+
+        let idle = Arc::new(AtomicBool::new(false));
+        let mut state = State { idle: idle.clone() };
+        std::thread::spawn(move || {
+            let _notification = notification;
+            while queue.blocking_dispatch(&mut state).is_ok() {}
+            tracing::warn!("Lost the Wayland connection; idle detection stopped");
+        });
+
+        Ok(Self { idle })
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle.load(Ordering::Relaxed)
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_idle_notification_v1::Event::Idled => state.idle.store(true, Ordering::Relaxed),
+            ext_idle_notification_v1::Event::Resumed => state.idle.store(false, Ordering::Relaxed),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: <ExtIdleNotifierV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
@@ -1,9 +1,22 @@
+pub mod annotation;
 pub mod capture;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod config;
+pub mod credentials;
+pub mod daemon;
 pub mod error;
+pub mod events;
 pub mod export;
+pub mod history;
+pub mod idle;
+pub mod notify;
+mod paths;
 pub mod processing;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod sequence;
+pub mod upload;
 
 #[cfg(feature = "gui")]
 pub mod ui;
@@ -18,14 +31,25 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 pub use error::{Result, ScreenshotError};
+pub use events::CaptureEvent;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename_all = "lowercase")]
 pub enum CaptureMode {
+    #[cfg_attr(feature = "cli", value(aliases = ["fullscreen", "full"]))]
     Screen,
+    #[cfg_attr(feature = "cli", value(alias = "win"))]
     Window,
+    #[cfg_attr(feature = "cli", value(aliases = ["area", "selection", "select"]))]
     Region,
+    #[cfg_attr(feature = "cli", value(alias = "display"))]
     Monitor,
+    #[serde(rename = "active-window", alias = "active")]
+    #[cfg_attr(feature = "cli", value(alias = "active"))]
+    ActiveWindow,
+    #[cfg_attr(feature = "cli", value(aliases = ["lasso", "freehand"]))]
+    Freeform,
 }
 
 impl std::str::FromStr for CaptureMode {
@@ -37,20 +61,45 @@ impl std::str::FromStr for CaptureMode {
             "window" | "win" => Ok(Self::Window),
             "region" | "area" | "selection" | "select" => Ok(Self::Region),
             "monitor" | "display" => Ok(Self::Monitor),
+            "active-window" | "active" => Ok(Self::ActiveWindow),
+            "freeform" | "lasso" | "freehand" => Ok(Self::Freeform),
             _ => Err(format!("Invalid capture mode: {}", s)),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Png,
     Jpeg,
     Webp,
+    Pdf,
     Clipboard,
 }
 
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+            Self::Pdf => "pdf",
+            Self::Clipboard => "png",
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
 impl std::str::FromStr for OutputFormat {
     type Err = String;
 
@@ -59,6 +108,7 @@ impl std::str::FromStr for OutputFormat {
             "png" => Ok(Self::Png),
             "jpg" | "jpeg" => Ok(Self::Jpeg),
             "webp" => Ok(Self::Webp),
+            "pdf" => Ok(Self::Pdf),
             "clip" | "clipboard" => Ok(Self::Clipboard),
             _ => Err(format!("Invalid format: {}", s)),
         }
@@ -73,6 +123,28 @@ pub struct ScreenshotMetadata {
     pub height: u32,
     pub format: OutputFormat,
     pub file_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compositor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_space: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendCapabilities {
+    pub cursor: bool,
+    pub per_output: bool,
+    pub window: bool,
+    pub non_interactive: bool,
 }
 
 #[async_trait]
@@ -80,6 +152,8 @@ pub trait ScreenshotBackend: Send + Sync {
     async fn capture(&self, mode: CaptureMode, options: &CaptureOptions) -> Result<Screenshot>;
     async fn get_displays(&self) -> Result<Vec<Display>>;
     async fn get_activate_window(&self) -> Result<Option<WindowInfo>>;
+    async fn get_windows(&self) -> Result<Vec<WindowInfo>>;
+    fn capabilities(&self) -> BackendCapabilities;
 }
 
 #[derive(Clone)]
@@ -88,6 +162,16 @@ pub struct Screenshot {
     pub metadata: ScreenshotMetadata,
 }
 
+// The pixel buffer is left out; its dimensions are enough to tell captures apart.
+impl std::fmt::Debug for Screenshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Screenshot")
+            .field("dimensions", &self.data.dimensions())
+            .field("metadata", &self.metadata)
+            .finish()
+    }
+}
+
 impl Screenshot {
     pub fn new(data: image::RgbaImage, mode: CaptureMode, format: OutputFormat) -> Self {
         let (width, height) = data.dimensions();
@@ -101,10 +185,73 @@ impl Screenshot {
                 height,
                 format,
                 file_size: None,
+                monitor: None,
+                window_title: None,
+                app_id: None,
+                compositor: None,
+                scale: None,
+                color_space: None,
+                capture_duration_ms: None,
             },
         }
     }
 
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let format = match image::guess_format(bytes)? {
+            image::ImageFormat::Jpeg => OutputFormat::Jpeg,
+            image::ImageFormat::WebP => OutputFormat::Webp,
+            _ => OutputFormat::Png,
+        };
+        let data = image::load_from_memory(bytes)?.to_rgba8();
+
+        let mut screenshot = Self::new(data, CaptureMode::Screen, format);
+        screenshot.metadata.file_size = Some(bytes.len() as u64);
+        Ok(screenshot)
+    }
+
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn encode(&self, format: OutputFormat, options: &export::ExportOptions) -> Result<Vec<u8>> {
+        export::Exporter::encode(self, format, options)
+    }
+
+    pub fn crop(&self, region: Region) -> Result<Screenshot> {
+        let inside = region.x >= 0
+            && region.y >= 0
+            && region.width > 0
+            && region.height > 0
+            && region.x as u64 + region.width as u64 <= self.width() as u64
+            && region.y as u64 + region.height as u64 <= self.height() as u64;
+        if !inside {
+            return Err(ScreenshotError::InvalidRegion(format!(
+                "{}x{}+{}+{} is outside the {}x{} image",
+                region.width,
+                region.height,
+                region.x,
+                region.y,
+                self.width(),
+                self.height()
+            )));
+        }
+
+        let data = image::imageops::crop_imm(
+            &self.data,
+            region.x as u32,
+            region.y as u32,
+            region.width,
+            region.height,
+        )
+        .to_image();
+
+        let mut metadata = self.metadata.clone();
+        metadata.width = region.width;
+        metadata.height = region.height;
+        metadata.file_size = None;
+        Ok(Screenshot { data, metadata })
+    }
+
     pub fn width(&self) -> u32 {
         self.data.width()
     }
@@ -112,14 +259,170 @@ impl Screenshot {
     pub fn height(&self) -> u32 {
         self.data.height()
     }
+
+    pub fn thumbnail(&self, max_dimension: u32) -> Screenshot {
+        let (width, height) = self.data.dimensions();
+        let max_dimension = max_dimension.max(1);
+
+        let data = if width <= max_dimension && height <= max_dimension {
+            self.data.clone()
+        } else {
+            let scale = max_dimension as f64 / width.max(height) as f64;
+            image::imageops::thumbnail(
+                &self.data,
+                ((width as f64 * scale).round() as u32).max(1),
+                ((height as f64 * scale).round() as u32).max(1),
+            )
+        };
+
+        Screenshot {
+            metadata: ScreenshotMetadata {
+                width: data.width(),
+                height: data.height(),
+                file_size: None,
+                ..self.metadata.clone()
+            },
+            data,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct CaptureOptions {
     pub delay: Option<std::time::Duration>,
     pub include_cursor: bool,
     pub monitor_index: Option<usize>,
-    pub region: Option<Region>,
+    pub region: Option<LogicalRegion>,
+    pub events: Option<events::EventSender>,
+}
+
+impl CaptureOptions {
+    pub fn builder() -> CaptureOptionsBuilder {
+        CaptureOptionsBuilder::default()
+    }
+
+    pub fn emit(&self, event: CaptureEvent) {
+        if let Some(sender) = &self.events {
+            let _ = sender.send(event);
+        }
+    }
+
+    pub async fn wait_delay(&self) {
+        let Some(delay) = self.delay.filter(|d| !d.is_zero()) else {
+            return;
+        };
+
+        self.emit(CaptureEvent::DelayStarted { delay });
+
+        let mut remaining = delay;
+        while !remaining.is_zero() {
+            self.emit(CaptureEvent::DelayTick { remaining });
+
+            let step = remaining.min(std::time::Duration::from_secs(1));
+            tokio::time::sleep(step).await;
+            remaining -= step;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CaptureOptionsBuilder {
+    options: CaptureOptions,
+}
+
+impl CaptureOptionsBuilder {
+    pub fn delay(mut self, delay: impl Into<Option<std::time::Duration>>) -> Self {
+        self.options.delay = delay.into();
+        self
+    }
+
+    pub fn cursor(mut self, include_cursor: bool) -> Self {
+        self.options.include_cursor = include_cursor;
+        self
+    }
+
+    pub fn monitor(mut self, index: impl Into<Option<usize>>) -> Self {
+        self.options.monitor_index = index.into();
+        self
+    }
+
+    pub fn region(mut self, region: impl Into<Option<LogicalRegion>>) -> Self {
+        self.options.region = region.into();
+        self
+    }
+
+    pub fn events(mut self, sender: impl Into<Option<events::EventSender>>) -> Self {
+        self.options.events = sender.into();
+        self
+    }
+
+    pub fn build(self) -> CaptureOptions {
+        self.options
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    #[default]
+    Normal,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    Flipped,
+    Flipped90,
+    Flipped180,
+    Flipped270,
+}
+
+impl Transform {
+    // Same ordering as the wl_output.transform enum.
+    pub fn from_wl(value: u32) -> Self {
+        match value {
+            1 => Self::Rotate90,
+            2 => Self::Rotate180,
+            3 => Self::Rotate270,
+            4 => Self::Flipped,
+            5 => Self::Flipped90,
+            6 => Self::Flipped180,
+            7 => Self::Flipped270,
+            _ => Self::Normal,
+        }
+    }
+
+    pub fn is_rotated(&self) -> bool {
+        matches!(
+            self,
+            Self::Rotate90 | Self::Rotate270 | Self::Flipped90 | Self::Flipped270
+        )
+    }
+
+    pub fn apply(&self, width: u32, height: u32) -> (u32, u32) {
+        if self.is_rotated() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+}
+
+impl std::str::FromStr for Transform {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "normal" | "0" => Ok(Self::Normal),
+            "90" => Ok(Self::Rotate90),
+            "180" => Ok(Self::Rotate180),
+            "270" => Ok(Self::Rotate270),
+            "flipped" => Ok(Self::Flipped),
+            "flipped-90" => Ok(Self::Flipped90),
+            "flipped-180" => Ok(Self::Flipped180),
+            "flipped-270" => Ok(Self::Flipped270),
+            _ => Err(format!("Invalid output transform: {}", s)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,6 +434,28 @@ pub struct Display {
     pub y: i32,
     pub scale: f64,
     pub is_primary: bool,
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+impl Display {
+    // width and height are the unrotated mode size; this is the size the
+    // output actually presents in the global layout.
+    pub fn pixel_size(&self) -> (u32, u32) {
+        self.transform.apply(self.width, self.height)
+    }
+
+    pub fn logical_region(&self) -> LogicalRegion {
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+        let (width, height) = self.pixel_size();
+
+        LogicalRegion(Region::new(
+            self.x,
+            self.y,
+            (width as f64 / scale).round() as u32,
+            (height as f64 / scale).round() as u32,
+        ))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,9 +466,17 @@ pub struct WindowInfo {
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<Region>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl WindowInfo {
+    pub fn region(&self) -> Region {
+        Region::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Region {
     pub x: i32,
     pub y: i32,
@@ -151,7 +484,71 @@ pub struct Region {
     pub height: u32,
 }
 
+impl std::str::FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_in(s, None)
+    }
+}
+
 impl Region {
+    // Accepts "x,y,w,h", X11-style "WxH+X+Y" and slurp's "X,Y WxH". Any part
+    // may be a percentage, which is resolved against `bounds` (the output the
+    // region refers to); plain numbers stay global coordinates.
+    pub fn parse_in(s: &str, bounds: Option<&Region>) -> std::result::Result<Self, String> {
+        let s = s.trim();
+        let [x, y, width, height] = split_geometry(s).ok_or_else(|| {
+            format!(
+                "Invalid region: {} (expected x,y,width,height, WxH+X+Y or \"X,Y WxH\")",
+                s
+            )
+        })?;
+
+        let resolve = |value: &str,
+                       part: &str,
+                       origin: i32,
+                       length: u32|
+         -> std::result::Result<i64, String> {
+            let value = value.trim();
+            let invalid = || format!("Invalid {} in region {}: {}", part, s, value);
+
+            match value.strip_suffix('%') {
+                Some(_) if bounds.is_none() => Err(format!(
+                    "Region {} uses percentages, which need an output to resolve against",
+                    s
+                )),
+                Some(percent) => {
+                    let percent: f64 = percent.trim().parse().map_err(|_| invalid())?;
+                    Ok(origin as i64 + (length as f64 * percent / 100.0).round() as i64)
+                }
+                None => value.parse::<i64>().map_err(|_| invalid()),
+            }
+        };
+
+        let (origin_x, origin_y, bounds_width, bounds_height) = bounds
+            .map(|b| (b.x, b.y, b.width, b.height))
+            .unwrap_or_default();
+        let x = resolve(x, "x", origin_x, bounds_width)?;
+        let y = resolve(y, "y", origin_y, bounds_height)?;
+        let width = resolve(width, "width", 0, bounds_width)?;
+        let height = resolve(height, "height", 0, bounds_height)?;
+
+        let coordinate = |value: i64, part: &str| {
+            i32::try_from(value).map_err(|_| format!("Region {} has an out of range {}", s, part))
+        };
+        let extent = |value: i64, part: &str| {
+            u32::try_from(value).map_err(|_| format!("Region {} has a negative {}", s, part))
+        };
+
+        Ok(Self::new(
+            coordinate(x, "x")?,
+            coordinate(y, "y")?,
+            extent(width, "width")?,
+            extent(height, "height")?,
+        ))
+    }
+
     pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
         Self {
             x,
@@ -161,15 +558,269 @@ impl Region {
         }
     }
 
-    pub fn normalize(&self) -> Self {
-        Self {
-            x: self.x.min(self.x + self.width as i32),
-            y: self.y.min(self.y + self.height as i32),
-            width: self.width,
-            height: self.height,
+    // Width and height are unsigned, so a region dragged up or to the left
+    // has to be built from its corners rather than fixed up afterwards.
+    pub fn from_points(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        Self::new(
+            x1.min(x2),
+            y1.min(y2),
+            (x2 as i64 - x1 as i64).unsigned_abs() as u32,
+            (y2 as i64 - y1 as i64).unsigned_abs() as u32,
+        )
+    }
+
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && (x as i64) < self.right() && (y as i64) < self.bottom()
+    }
+
+    pub fn intersect(&self, other: &Region) -> Option<Region> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        (right > left as i64 && bottom > top as i64).then(|| {
+            Region::new(
+                left,
+                top,
+                (right - left as i64) as u32,
+                (bottom - top as i64) as u32,
+            )
+        })
+    }
+
+    pub fn union(&self, other: &Region) -> Region {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
         }
+
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+
+        Region::new(
+            left,
+            top,
+            (right - left as i64).min(u32::MAX as i64) as u32,
+            (bottom - top as i64).min(u32::MAX as i64) as u32,
+        )
+    }
+
+    pub fn clamp_to(&self, display: &Display) -> Option<Region> {
+        self.intersect(&display.logical_region().0)
+    }
+}
+
+fn split_geometry(s: &str) -> Option<[&str; 4]> {
+    if let Some((position, size)) = s.split_once(' ') {
+        let (x, y) = position.split_once(',')?;
+        let (width, height) = size.trim().split_once('x')?;
+        return Some([x, y, width, height]);
+    }
+
+    if let Some((width, rest)) = s.split_once('x') {
+        let (height, offsets) = rest.split_once('+')?;
+        let (x, y) = offsets.split_once('+')?;
+        return Some([x, y, width, height]);
+    }
+
+    let parts: Vec<&str> = s.split(',').collect();
+    match parts[..] {
+        [x, y, width, height] => Some([x, y, width, height]),
+        _ => None,
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LogicalRegion(pub Region);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PhysicalRegion(pub Region);
+
+impl From<Region> for LogicalRegion {
+    fn from(region: Region) -> Self {
+        Self(region)
+    }
+}
+
+impl LogicalRegion {
+    pub fn to_physical(&self, scale: f64) -> PhysicalRegion {
+        PhysicalRegion(scale_region(&self.0, scale))
+    }
+}
+
+impl PhysicalRegion {
+    pub fn to_logical(&self, scale: f64) -> LogicalRegion {
+        let scale = if scale > 0.0 { scale } else { 1.0 };
+        LogicalRegion(scale_region(&self.0, 1.0 / scale))
+    }
+}
+
+fn scale_region(region: &Region, scale: f64) -> Region {
+    let left = (region.x as f64 * scale).round();
+    let top = (region.y as f64 * scale).round();
+    let right = ((region.x + region.width as i32) as f64 * scale).round();
+    let bottom = ((region.y + region.height as i32) as f64 * scale).round();
+
+    Region::new(
+        left as i32,
+        top as i32,
+        (right - left).max(0.0) as u32,
+        (bottom - top).max(0.0) as u32,
+    )
+}
+
 #[cfg(feature = "gui")]
 pub use ui::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(x: i32, y: i32, width: u32, height: u32, scale: f64) -> Display {
+        Display {
+            name: "TEST-1".to_string(),
+            width,
+            height,
+            x,
+            y,
+            scale,
+            is_primary: true,
+            transform: Transform::Normal,
+        }
+    }
+
+    #[test]
+    fn from_points_normalizes_reversed_drags() {
+        let expected = Region::new(10, 20, 30, 40);
+        assert_eq!(Region::from_points(10, 20, 40, 60), expected);
+        assert_eq!(Region::from_points(40, 60, 10, 20), expected);
+        assert_eq!(Region::from_points(40, 20, 10, 60), expected);
+        assert_eq!(
+            Region::from_points(-5, -5, -5, -5),
+            Region::new(-5, -5, 0, 0)
+        );
+    }
+
+    #[test]
+    fn contains_excludes_the_far_edges() {
+        let region = Region::new(0, 0, 10, 10);
+        assert!(region.contains(0, 0));
+        assert!(region.contains(9, 9));
+        assert!(!region.contains(10, 5));
+        assert!(!region.contains(5, 10));
+        assert!(!region.contains(-1, 5));
+    }
+
+    #[test]
+    fn intersect_overlapping_disjoint_and_touching() {
+        let a = Region::new(0, 0, 100, 100);
+        assert_eq!(
+            a.intersect(&Region::new(50, 50, 100, 100)),
+            Some(Region::new(50, 50, 50, 50))
+        );
+        assert_eq!(a.intersect(&Region::new(200, 200, 10, 10)), None);
+        assert_eq!(a.intersect(&Region::new(100, 0, 10, 10)), None);
+        assert_eq!(a.intersect(&Region::new(0, 100, 10, 10)), None);
+    }
+
+    #[test]
+    fn union_spans_both_and_skips_empty() {
+        let a = Region::new(0, 0, 10, 10);
+        let b = Region::new(-20, 30, 5, 5);
+        assert_eq!(a.union(&b), Region::new(-20, 0, 30, 35));
+        assert_eq!(a.union(&Region::new(0, 0, 0, 0)), a);
+        assert_eq!(Region::new(0, 0, 0, 0).union(&b), b);
+    }
+
+    #[test]
+    fn clamp_to_uses_the_logical_display_size() {
+        let output = display(1920, 0, 3840, 2160, 2.0);
+        assert_eq!(
+            Region::new(1800, -100, 400, 400).clamp_to(&output),
+            Some(Region::new(1920, 0, 280, 300))
+        );
+        assert_eq!(Region::new(0, 0, 100, 100).clamp_to(&output), None);
+    }
+
+    #[test]
+    fn parse_in_accepts_all_three_geometry_forms() {
+        let expected = Region::new(10, 20, 300, 200);
+        assert_eq!(Region::parse_in("10,20,300,200", None), Ok(expected));
+        assert_eq!(Region::parse_in("300x200+10+20", None), Ok(expected));
+        assert_eq!(Region::parse_in("10,20 300x200", None), Ok(expected));
+        assert_eq!(Region::parse_in("  10,20 300x200\n", None), Ok(expected));
+    }
+
+    #[test]
+    fn parse_in_keeps_negative_offsets() {
+        let expected = Region::new(-1920, -40, 800, 600);
+        assert_eq!(Region::parse_in("-1920,-40,800,600", None), Ok(expected));
+        assert_eq!(Region::parse_in("800x600+-1920+-40", None), Ok(expected));
+        assert_eq!(Region::parse_in("-1920,-40 800x600", None), Ok(expected));
+    }
+
+    #[test]
+    fn parse_in_resolves_percentages_against_the_output() {
+        let output = Region::new(1920, 0, 2560, 1440);
+        assert_eq!(
+            Region::parse_in("50%,50%,25%,25%", Some(&output)),
+            Ok(Region::new(3200, 720, 640, 360))
+        );
+        assert_eq!(
+            Region::parse_in("0%,0% 100%x100%", Some(&output)),
+            Ok(output)
+        );
+        // Plain numbers stay global even when an output is given.
+        assert_eq!(
+            Region::parse_in("100,100,50%,10", Some(&output)),
+            Ok(Region::new(100, 100, 1280, 10))
+        );
+    }
+
+    #[test]
+    fn parse_in_explains_malformed_regions() {
+        assert_eq!(
+            Region::parse_in("10,20,30", None),
+            Err(
+                "Invalid region: 10,20,30 (expected x,y,width,height, WxH+X+Y or \"X,Y WxH\")"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            Region::parse_in("10,20,wide,40", None),
+            Err("Invalid width in region 10,20,wide,40: wide".to_string())
+        );
+        assert_eq!(
+            Region::parse_in("0,0,50%,50%", None),
+            Err(
+                "Region 0,0,50%,50% uses percentages, which need an output to resolve against"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            Region::parse_in("0,0,-5,10", None),
+            Err("Region 0,0,-5,10 has a negative width".to_string())
+        );
+        assert_eq!(
+            Region::parse_in("4000000000,0,10,10", None),
+            Err("Region 4000000000,0,10,10 has an out of range x".to_string())
+        );
+    }
+}
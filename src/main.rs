@@ -1,9 +1,8 @@
-use clap::Parser;
-use just_a_simple_wayland_screenshot_tool::{Result, cli};
+use just_a_simple_wayland_screenshot_tool::cli;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args = cli::Args::parse();
+async fn main() {
+    let args = cli::Args::parse_with_grim_compat();
 
     if args.verbose {
         tracing_subscriber::fmt()
@@ -14,5 +13,8 @@ async fn main() -> Result<()> {
             .init();
     }
 
-    cli::execute(args).await
+    if let Err(e) = cli::execute(args).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(e.exit_code());
+    }
 }
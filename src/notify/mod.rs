@@ -0,0 +1,128 @@
+pub mod publish;
+
+pub use publish::PublishConfig;
+
+use crate::{
+    CaptureEvent, CaptureOptions, OutputFormat, Screenshot, export::Exporter, history::History,
+};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+pub struct Notifier;
+
+impl Notifier {
+    pub fn send(
+        summary: &str,
+        body: &str,
+        replace_id: Option<u32>,
+        expire: Option<Duration>,
+    ) -> Option<u32> {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=wst")
+            .arg("--print-id")
+            .arg("--hint=int:transient:1");
+
+        if let Some(id) = replace_id {
+            cmd.arg(format!("--replace-id={}", id));
+        }
+
+        if let Some(expire) = expire {
+            cmd.arg(format!("--expire-time={}", expire.as_millis()));
+        }
+
+        let output = cmd
+            .arg(summary)
+            .arg(body)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    pub fn captured(screenshot: &Screenshot, path: &Path) {
+        let icon = Self::write_thumbnail(screenshot);
+
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=wst");
+
+        if let Some(icon) = icon {
+            cmd.arg(format!("--icon={}", icon.display()));
+        }
+
+        let _ = cmd
+            .arg("Screenshot saved")
+            .arg(path.display().to_string())
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+
+    fn write_thumbnail(screenshot: &Screenshot) -> Option<PathBuf> {
+        let directory = History::state_directory().ok()?;
+        std::fs::create_dir_all(&directory).ok()?;
+
+        let icon = directory.join("notification-thumbnail.png");
+        Exporter::save(&screenshot.thumbnail(256), &icon, OutputFormat::Png).ok()?;
+        Some(icon)
+    }
+
+    pub async fn countdown(delay: Duration, options: &CaptureOptions) {
+        options.emit(CaptureEvent::DelayStarted { delay });
+
+        let mut remaining = delay.as_secs();
+        let mut notification_id = None;
+        let mut use_stderr = false;
+
+        while remaining > 0 {
+            options.emit(CaptureEvent::DelayTick {
+                remaining: Duration::from_secs(remaining),
+            });
+
+            let body = format!("Capturing in {}…", remaining);
+
+            // Each tick expires before the next one so nothing is left on screen
+            // when the capture fires.
+            if !use_stderr {
+                let message = body.clone();
+                let sent = tokio::task::spawn_blocking(move || {
+                    Self::send(
+                        "Screenshot",
+                        &message,
+                        notification_id,
+                        Some(Duration::from_millis(900)),
+                    )
+                })
+                .await;
+                match sent.ok().flatten() {
+                    Some(id) => notification_id = Some(id),
+                    None => use_stderr = true,
+                }
+            }
+
+            if use_stderr {
+                eprint!("\r{}", body);
+                let _ = std::io::stderr().flush();
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            remaining -= 1;
+        }
+
+        if use_stderr {
+            eprintln!();
+        }
+
+        let fraction = delay - Duration::from_secs(delay.as_secs());
+        if !fraction.is_zero() {
+            tokio::time::sleep(fraction).await;
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+const APPLICATION: &str = "just-a-simple-wayland-screenshot-tool";
+
+#[cfg(feature = "directories")]
+fn project() -> Option<directories::ProjectDirs> {
+    directories::ProjectDirs::from("com", "wayland", APPLICATION)
+}
+
+#[cfg(feature = "directories")]
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+#[cfg(feature = "directories")]
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    project().map(|dirs| {
+        dirs.state_dir()
+            .unwrap_or_else(|| dirs.data_local_dir())
+            .to_path_buf()
+    })
+}
+
+#[cfg(feature = "directories")]
+pub(crate) fn pictures_dir() -> Option<PathBuf> {
+    directories::UserDirs::new().and_then(|dirs| dirs.picture_dir().map(|p| p.to_path_buf()))
+}
+
+// Library-only builds skip the directories crate and read the XDG base
+// directory variables directly.
+#[cfg(not(feature = "directories"))]
+fn xdg(variable: &str, fallback: &str) -> Option<PathBuf> {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+}
+
+#[cfg(not(feature = "directories"))]
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    xdg("XDG_CONFIG_HOME", ".config").map(|dir| dir.join(APPLICATION))
+}
+
+#[cfg(not(feature = "directories"))]
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    xdg("XDG_STATE_HOME", ".local/state").map(|dir| dir.join(APPLICATION))
+}
+
+#[cfg(not(feature = "directories"))]
+pub(crate) fn pictures_dir() -> Option<PathBuf> {
+    xdg("XDG_PICTURES_DIR", "Pictures")
+}
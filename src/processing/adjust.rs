@@ -0,0 +1,69 @@
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Adjustments {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub gamma: f32,
+}
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl Adjustments {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub(crate) fn apply(&self, image: &RgbaImage) -> RgbaImage {
+        let gamma = if self.gamma > 0.0 { self.gamma } else { 1.0 };
+        let lut: Vec<f32> = (0..=255u32)
+            .map(|value| {
+                let v = value as f32 / 255.0 + self.brightness;
+                let v = (v - 0.5) * self.contrast + 0.5;
+                v.clamp(0.0, 1.0).powf(1.0 / gamma) * 255.0
+            })
+            .collect();
+
+        let mut output = image.clone();
+        for pixel in output.pixels_mut() {
+            let [r, g, b] = [0, 1, 2].map(|i| lut[pixel[i] as usize]);
+            let grey = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+            for (i, value) in [r, g, b].into_iter().enumerate() {
+                let saturated = grey + (value - grey) * self.saturation;
+                pixel[i] = saturated.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+        output
+    }
+}
+
+pub(crate) fn grayscale(image: &RgbaImage) -> RgbaImage {
+    Adjustments {
+        saturation: 0.0,
+        ..Adjustments::default()
+    }
+    .apply(image)
+}
+
+pub(crate) fn invert(image: &RgbaImage) -> RgbaImage {
+    let mut output = image.clone();
+    for pixel in output.pixels_mut() {
+        for i in 0..3 {
+            pixel[i] = 255 - pixel[i];
+        }
+    }
+    output
+}
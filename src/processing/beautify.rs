@@ -0,0 +1,243 @@
+use super::color::{lerp_color, parse_color};
+use crate::{Result, Screenshot, ScreenshotError};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const BUILTIN_PRESETS: &str = include_str!("presets.toml");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackgroundStyle {
+    Solid {
+        color: String,
+    },
+    Gradient {
+        from: String,
+        to: String,
+        angle: f64,
+    },
+    Image {
+        path: PathBuf,
+    },
+}
+
+impl std::str::FromStr for BackgroundStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if !s.starts_with('#') {
+            return Ok(Self::Image {
+                path: PathBuf::from(s),
+            });
+        }
+
+        let parts: Vec<&str> = s.split(':').collect();
+        let valid = |color: &str| {
+            parse_color(color)
+                .map(|_| color.to_string())
+                .ok_or_else(|| format!("Invalid color: {}", color))
+        };
+
+        match parts.as_slice() {
+            [color] => Ok(Self::Solid {
+                color: valid(color)?,
+            }),
+            [from, to] | [from, to, _] => Ok(Self::Gradient {
+                from: valid(from)?,
+                to: valid(to)?,
+                angle: match parts.get(2) {
+                    Some(angle) => angle
+                        .parse()
+                        .map_err(|_| format!("Invalid gradient angle: {}", angle))?,
+                    None => 135.0,
+                },
+            }),
+            _ => Err(format!(
+                "Invalid background: {} (use #rrggbb, #from:#to[:angle] or an image path)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeautifyPreset {
+    pub padding: u32,
+    pub corner_radius: u32,
+    pub shadow: u32,
+    pub background: BackgroundStyle,
+    #[serde(default)]
+    pub palette: Option<u32>,
+}
+
+impl BeautifyPreset {
+    pub fn builtin() -> BTreeMap<String, BeautifyPreset> {
+        toml::from_str(BUILTIN_PRESETS).expect("built-in presets are valid TOML")
+    }
+
+    pub fn all(overrides: &BTreeMap<String, BeautifyPreset>) -> BTreeMap<String, BeautifyPreset> {
+        let mut presets = Self::builtin();
+        presets.extend(overrides.clone());
+        presets
+    }
+
+    pub fn find(name: &str, overrides: &BTreeMap<String, BeautifyPreset>) -> Result<Self> {
+        Self::all(overrides)
+            .remove(name)
+            .ok_or_else(|| ScreenshotError::Config(format!("Unknown beautify preset: {}", name)))
+    }
+
+    pub fn describe(&self) -> String {
+        let background = match &self.background {
+            BackgroundStyle::Solid { color } => format!("solid {}", color),
+            BackgroundStyle::Gradient { from, to, angle } => {
+                format!("gradient {} → {} ({}°)", from, to, angle)
+            }
+            BackgroundStyle::Image { path } => format!("image {}", path.display()),
+        };
+
+        let description = format!(
+            "{}, padding {}px, radius {}px, shadow {}px",
+            background, self.padding, self.corner_radius, self.shadow
+        );
+
+        match self.palette {
+            Some(levels) => format!("{}, {} grey levels", description, levels),
+            None => description,
+        }
+    }
+}
+
+pub(crate) fn beautify(screenshot: &Screenshot, preset: &BeautifyPreset) -> Result<Screenshot> {
+    let rounded = round_corners(&screenshot.data, preset.corner_radius);
+    let mut canvas =
+        pad_with_background(&rounded, preset.padding, preset.shadow, &preset.background)?;
+
+    if let Some(levels) = preset.palette {
+        canvas = super::quantize::quantize(&canvas, levels);
+    }
+
+    let mut new_screenshot = screenshot.clone();
+    new_screenshot.metadata.width = canvas.width();
+    new_screenshot.metadata.height = canvas.height();
+    new_screenshot.data = canvas;
+
+    Ok(new_screenshot)
+}
+
+pub(crate) fn pad_with_background(
+    image: &RgbaImage,
+    padding: u32,
+    shadow: u32,
+    background: &BackgroundStyle,
+) -> Result<RgbaImage> {
+    let width = image.width() + padding * 2;
+    let height = image.height() + padding * 2;
+
+    let mut canvas = fill_background(width, height, background)?;
+
+    if shadow > 0 {
+        let shadow = shadow_layer(image, width, height, padding, shadow);
+        image::imageops::overlay(&mut canvas, &shadow, 0, 0);
+    }
+
+    image::imageops::overlay(&mut canvas, image, padding as i64, padding as i64);
+
+    Ok(canvas)
+}
+
+pub(crate) fn round_corners(image: &RgbaImage, radius: u32) -> RgbaImage {
+    let mut rounded = image.clone();
+    let (width, height) = rounded.dimensions();
+    let radius = radius.min(width / 2).min(height / 2) as f64;
+
+    if radius <= 0.0 {
+        return rounded;
+    }
+
+    for (x, y, pixel) in rounded.enumerate_pixels_mut() {
+        let px = x as f64 + 0.5;
+        let py = y as f64 + 0.5;
+
+        let cx = if px < radius {
+            radius
+        } else if px > width as f64 - radius {
+            width as f64 - radius
+        } else {
+            continue;
+        };
+
+        let cy = if py < radius {
+            radius
+        } else if py > height as f64 - radius {
+            height as f64 - radius
+        } else {
+            continue;
+        };
+
+        let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+        pixel[3] = (pixel[3] as f64 * coverage).round() as u8;
+    }
+
+    rounded
+}
+
+pub(crate) fn fill_background(
+    width: u32,
+    height: u32,
+    background: &BackgroundStyle,
+) -> Result<RgbaImage> {
+    let invalid = |color: &str| ScreenshotError::Config(format!("Invalid color: {}", color));
+
+    match background {
+        BackgroundStyle::Solid { color } => {
+            let color = parse_color(color).ok_or_else(|| invalid(color))?;
+            Ok(RgbaImage::from_pixel(width, height, color))
+        }
+        BackgroundStyle::Gradient { from, to, angle } => {
+            let from = parse_color(from).ok_or_else(|| invalid(from))?;
+            let to = parse_color(to).ok_or_else(|| invalid(to))?;
+
+            let (sin, cos) = angle.to_radians().sin_cos();
+            let extent = (width as f64 * cos).abs() + (height as f64 * sin).abs();
+            let center_x = width as f64 / 2.0;
+            let center_y = height as f64 / 2.0;
+
+            Ok(RgbaImage::from_fn(width, height, |x, y| {
+                let projection = (x as f64 - center_x) * cos + (y as f64 - center_y) * sin;
+                let t = if extent > 0.0 {
+                    projection / extent + 0.5
+                } else {
+                    0.0
+                };
+                lerp_color(from, to, t)
+            }))
+        }
+        BackgroundStyle::Image { path } => {
+            let image = image::open(path)?;
+            Ok(image
+                .resize_to_fill(width, height, image::imageops::FilterType::Lanczos3)
+                .to_rgba8())
+        }
+    }
+}
+
+fn shadow_layer(image: &RgbaImage, width: u32, height: u32, padding: u32, size: u32) -> RgbaImage {
+    let mut layer = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+    let offset = size / 3;
+
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let target_x = x + padding;
+        let target_y = y + padding + offset;
+
+        if target_x < width && target_y < height {
+            let alpha = (pixel[3] as f64 * 0.45).round() as u8;
+            layer.put_pixel(target_x, target_y, Rgba([0, 0, 0, alpha]));
+        }
+    }
+
+    image::imageops::blur(&layer, size as f32 / 2.0)
+}
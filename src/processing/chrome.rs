@@ -0,0 +1,143 @@
+use super::{
+    beautify::round_corners,
+    color::parse_color,
+    frame::{color, panel, traffic_lights},
+    text::{TextStyle, draw_text, text_size},
+};
+use crate::{Result, Screenshot, ScreenshotError};
+use image::{Rgba, RgbaImage, imageops};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChromeStyle {
+    #[default]
+    Macos,
+    Gnome,
+}
+
+impl std::str::FromStr for ChromeStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "macos" | "mac" => Ok(Self::Macos),
+            "gnome" | "adwaita" => Ok(Self::Gnome),
+            _ => Err(format!("Invalid chrome style: {} (use macos or gnome)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowChrome {
+    pub style: ChromeStyle,
+    pub title: Option<String>,
+    pub bar_color: Option<String>,
+    pub title_color: Option<String>,
+    pub corner_radius: u32,
+}
+
+impl Default for WindowChrome {
+    fn default() -> Self {
+        Self {
+            style: ChromeStyle::Macos,
+            title: None,
+            bar_color: None,
+            title_color: None,
+            corner_radius: 10,
+        }
+    }
+}
+
+impl WindowChrome {
+    // Without a configured title the captured window's own title is used.
+    pub fn apply(&self, screenshot: &Screenshot) -> Result<Screenshot> {
+        let (width, height) = screenshot.data.dimensions();
+        let bar = match self.style {
+            ChromeStyle::Macos => 36,
+            ChromeStyle::Gnome => 46,
+        };
+
+        let bar_color = self.color(
+            &self.bar_color,
+            match self.style {
+                ChromeStyle::Macos => "#ececec",
+                ChromeStyle::Gnome => "#ebebeb",
+            },
+        )?;
+        let mut canvas = RgbaImage::from_pixel(width, height + bar, bar_color);
+        imageops::overlay(&mut canvas, &screenshot.data, 0, bar as i64);
+
+        match self.style {
+            ChromeStyle::Macos => traffic_lights(&mut canvas, 14, bar / 2, 6),
+            ChromeStyle::Gnome => close_button(&mut canvas, bar),
+        }
+
+        let title = self
+            .title
+            .as_deref()
+            .or(screenshot.metadata.window_title.as_deref())
+            .filter(|title| !title.is_empty());
+        if let Some(title) = title {
+            let title_color = self.color(&self.title_color, "#3d3d3d")?;
+            if let Err(e) = draw_title(&mut canvas, title, bar, title_color) {
+                tracing::warn!("Could not draw the window title: {}", e);
+            }
+        }
+
+        let mut framed = screenshot.clone();
+        framed.data = round_corners(&canvas, self.corner_radius);
+        framed.metadata.width = framed.data.width();
+        framed.metadata.height = framed.data.height();
+        Ok(framed)
+    }
+
+    fn color(&self, configured: &Option<String>, fallback: &str) -> Result<Rgba<u8>> {
+        match configured {
+            Some(hex) => parse_color(hex)
+                .ok_or_else(|| ScreenshotError::Config(format!("Invalid color: {}", hex))),
+            None => Ok(color(fallback)),
+        }
+    }
+}
+
+fn close_button(canvas: &mut RgbaImage, bar: u32) {
+    let radius = bar / 4;
+    let center_x = canvas.width().saturating_sub(bar / 2);
+    let center_y = bar / 2;
+    let button = panel(radius * 2, radius * 2, radius, color("#dadada"));
+    imageops::overlay(
+        canvas,
+        &button,
+        center_x as i64 - radius as i64,
+        (center_y - radius) as i64,
+    );
+
+    let arm = radius as f32 / 2.5;
+    let (cx, cy) = (center_x as f32, center_y as f32);
+    for offset in [-0.5, 0.0, 0.5] {
+        imageproc::drawing::draw_line_segment_mut(
+            canvas,
+            (cx - arm + offset, cy - arm),
+            (cx + arm + offset, cy + arm),
+            color("#2e2e2e"),
+        );
+        imageproc::drawing::draw_line_segment_mut(
+            canvas,
+            (cx - arm + offset, cy + arm),
+            (cx + arm + offset, cy - arm),
+            color("#2e2e2e"),
+        );
+    }
+}
+
+fn draw_title(canvas: &mut RgbaImage, title: &str, bar: u32, fill: Rgba<u8>) -> Result<()> {
+    let font = TextStyle::default().load_font()?;
+    let size = bar as f32 * 0.4;
+    let (text_width, text_height) = text_size(title, &font, size);
+    let x = (canvas.width() as i32 - text_width as i32) / 2;
+    let y = (bar as i32 - text_height as i32) / 2;
+    draw_text(canvas, title, x.max(0), y.max(0), &font, size, fill);
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use image::Rgba;
+
+pub fn parse_color(value: &str) -> Option<Rgba<u8>> {
+    let hex = value.trim().strip_prefix('#')?;
+
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    match hex.len() {
+        6 => Some(Rgba([channel(0)?, channel(2)?, channel(4)?, 255])),
+        8 => Some(Rgba([channel(0)?, channel(2)?, channel(4)?, channel(6)?])),
+        _ => None,
+    }
+}
+
+pub fn lerp_color(from: Rgba<u8>, to: Rgba<u8>, t: f64) -> Rgba<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+    Rgba([
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+        mix(from[3], to[3]),
+    ])
+}
@@ -0,0 +1,194 @@
+use crate::{Region, Result, ScreenshotError};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    pub threshold: f64,
+    pub aa_tolerance: u32,
+    pub max_diff_pixels: u64,
+    pub max_diff_ratio: Option<f64>,
+    pub ignore: Vec<Region>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.1,
+            aa_tolerance: 1,
+            max_diff_pixels: 0,
+            max_diff_ratio: None,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PixelState {
+    Same,
+    Ignored,
+    AntiAliased,
+    Different,
+}
+
+pub struct DiffResult {
+    pub differing_pixels: u64,
+    pub anti_aliased_pixels: u64,
+    pub total_pixels: u64,
+    pub image: RgbaImage,
+}
+
+impl DiffResult {
+    pub fn ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            0.0
+        } else {
+            self.differing_pixels as f64 / self.total_pixels as f64
+        }
+    }
+
+    pub fn passes(&self, options: &DiffOptions) -> bool {
+        self.differing_pixels <= options.max_diff_pixels
+            || options
+                .max_diff_ratio
+                .is_some_and(|ratio| self.ratio() <= ratio)
+    }
+}
+
+pub fn diff(expected: &RgbaImage, actual: &RgbaImage, options: &DiffOptions) -> Result<DiffResult> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(ScreenshotError::Config(format!(
+            "Image sizes differ: {}x{} vs {}x{}",
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height()
+        )));
+    }
+
+    let (width, height) = expected.dimensions();
+    let compare_row = |y: u32| -> Vec<PixelState> {
+        (0..width)
+            .map(|x| classify(expected, actual, x, y, options))
+            .collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    let states: Vec<Vec<PixelState>> = (0..height).into_par_iter().map(compare_row).collect();
+    #[cfg(not(feature = "parallel"))]
+    let states: Vec<Vec<PixelState>> = (0..height).map(compare_row).collect();
+
+    let mut image = RgbaImage::new(width, height);
+    let mut differing_pixels = 0;
+    let mut anti_aliased_pixels = 0;
+    let mut ignored_pixels = 0;
+
+    for (y, row) in states.iter().enumerate() {
+        for (x, state) in row.iter().enumerate() {
+            let (x, y) = (x as u32, y as u32);
+            let faded = {
+                let grey = (luma(expected.get_pixel(x, y)) * 0.1 + 255.0 * 0.9) as u8;
+                Rgba([grey, grey, grey, 255])
+            };
+
+            let pixel = match state {
+                PixelState::Same => faded,
+                PixelState::Ignored => {
+                    ignored_pixels += 1;
+                    Rgba([200, 220, 255, 255])
+                }
+                PixelState::AntiAliased => {
+                    anti_aliased_pixels += 1;
+                    Rgba([255, 200, 0, 255])
+                }
+                PixelState::Different => {
+                    differing_pixels += 1;
+                    Rgba([255, 0, 0, 255])
+                }
+            };
+            image.put_pixel(x, y, pixel);
+        }
+    }
+
+    Ok(DiffResult {
+        differing_pixels,
+        anti_aliased_pixels,
+        total_pixels: width as u64 * height as u64 - ignored_pixels,
+        image,
+    })
+}
+
+fn classify(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    x: u32,
+    y: u32,
+    options: &DiffOptions,
+) -> PixelState {
+    if options
+        .ignore
+        .iter()
+        .any(|r| r.contains(x as i32, y as i32))
+    {
+        return PixelState::Ignored;
+    }
+
+    let a = expected.get_pixel(x, y);
+    let b = actual.get_pixel(x, y);
+    if distance(a, b) <= options.threshold {
+        return PixelState::Same;
+    }
+
+    // Anti-aliased edges shift by a pixel between renderers; accept the
+    // difference when each image has a close match nearby in the other.
+    if options.aa_tolerance > 0
+        && has_neighbour_match(actual, a, x, y, options)
+        && has_neighbour_match(expected, b, x, y, options)
+    {
+        return PixelState::AntiAliased;
+    }
+
+    PixelState::Different
+}
+
+fn has_neighbour_match(
+    image: &RgbaImage,
+    pixel: &Rgba<u8>,
+    x: u32,
+    y: u32,
+    options: &DiffOptions,
+) -> bool {
+    let radius = options.aa_tolerance as i64;
+    let (width, height) = (image.width() as i64, image.height() as i64);
+
+    (-radius..=radius).any(|dy| {
+        (-radius..=radius).any(|dx| {
+            let nx = x as i64 + dx;
+            let ny = y as i64 + dy;
+            nx >= 0
+                && ny >= 0
+                && nx < width
+                && ny < height
+                && distance(pixel, image.get_pixel(nx as u32, ny as u32)) <= options.threshold
+        })
+    })
+}
+
+fn luma(pixel: &Rgba<u8>) -> f64 {
+    0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64
+}
+
+// Normalised RGBA distance in 0.0..=1.0.
+fn distance(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    let sum: f64 =
+        a.0.iter()
+            .zip(b.0.iter())
+            .map(|(&a, &b)| (a as f64 - b as f64).powi(2))
+            .sum();
+
+    (sum / (4.0 * 255.0 * 255.0)).sqrt()
+}
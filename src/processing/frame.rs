@@ -0,0 +1,256 @@
+use super::{Processor, beautify::round_corners, color::parse_color, text::TextStyle};
+use crate::{Region, Result, Screenshot, ScreenshotError};
+use image::{Rgba, RgbaImage, imageops};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// A user-supplied frame: an image whose screen area is transparent, and
+// where that area sits in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameAsset {
+    pub image: PathBuf,
+    pub screen: Region,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceFrame {
+    Browser { url: Option<String> },
+    Phone,
+    Laptop,
+    Asset(FrameAsset),
+}
+
+impl std::str::FromStr for DeviceFrame {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "browser" | "window" => Ok(Self::Browser { url: None }),
+            "phone" | "mobile" => Ok(Self::Phone),
+            "laptop" => Ok(Self::Laptop),
+            _ => Err(format!(
+                "Invalid frame: {} (use browser, phone or laptop)",
+                s
+            )),
+        }
+    }
+}
+
+impl DeviceFrame {
+    pub fn apply(&self, screenshot: &Screenshot) -> Result<Screenshot> {
+        let data = match self {
+            Self::Browser { url } => browser(&screenshot.data, url.as_deref()),
+            Self::Phone => phone(&screenshot.data),
+            Self::Laptop => laptop(&screenshot.data),
+            Self::Asset(asset) => from_asset(&screenshot.data, asset)?,
+        };
+
+        let mut framed = screenshot.clone();
+        framed.metadata.width = data.width();
+        framed.metadata.height = data.height();
+        framed.data = data;
+        Ok(framed)
+    }
+}
+
+pub(super) fn color(hex: &str) -> Rgba<u8> {
+    parse_color(hex).unwrap_or(Rgba([0, 0, 0, 255]))
+}
+
+pub(super) fn panel(width: u32, height: u32, radius: u32, fill: Rgba<u8>) -> RgbaImage {
+    round_corners(&RgbaImage::from_pixel(width, height, fill), radius)
+}
+
+// Close, minimise and zoom buttons in the macOS colours, left to right from x.
+pub(super) fn traffic_lights(canvas: &mut RgbaImage, x: u32, center_y: u32, radius: u32) {
+    let lights = ["#ff5f57", "#febc2e", "#28c840"];
+    for (i, light) in lights.iter().enumerate() {
+        let light = panel(radius * 2, radius * 2, radius, color(light));
+        let left = x + i as u32 * radius * 3;
+        imageops::overlay(canvas, &light, left as i64, (center_y - radius) as i64);
+    }
+}
+
+fn browser(screen: &RgbaImage, url: Option<&str>) -> RgbaImage {
+    let (width, height) = screen.dimensions();
+    let bar = (width / 28).clamp(32, 72);
+    let mut canvas = panel(width, height + bar, 0, color("#e3e3e6"));
+    imageops::overlay(&mut canvas, screen, 0, bar as i64);
+
+    let dot = (bar / 5).max(4);
+    traffic_lights(&mut canvas, bar / 2, bar / 2, dot);
+
+    let left = bar / 2 + dot * 10;
+    let pill_height = bar * 3 / 5;
+    if width > left + bar {
+        let pill = panel(
+            width - left - bar / 2,
+            pill_height,
+            pill_height / 2,
+            color("#ffffff"),
+        );
+        let top = (bar - pill_height) / 2;
+        imageops::overlay(&mut canvas, &pill, left as i64, top as i64);
+
+        if let Some(url) = url.filter(|url| !url.is_empty()) {
+            let style = TextStyle {
+                family: Some("sans-serif".to_string()),
+                size: pill_height as f32 * 0.6,
+                color: "#4a4a4f".to_string(),
+                ..Default::default()
+            };
+            let x = (left + pill_height / 2) as i32;
+            let y = (top + pill_height / 5) as i32;
+            if let Err(e) = style.draw(&mut canvas, url, x, y) {
+                tracing::warn!("Could not draw the frame URL: {}", e);
+            }
+        }
+    }
+
+    round_corners(&canvas, bar / 4)
+}
+
+fn phone(screen: &RgbaImage) -> RgbaImage {
+    let (width, height) = screen.dimensions();
+    let bezel = (width / 18).clamp(12, 80);
+    let mut canvas = panel(
+        width + bezel * 2,
+        height + bezel * 2,
+        bezel * 3,
+        color("#111113"),
+    );
+    imageops::overlay(
+        &mut canvas,
+        &round_corners(screen, bezel * 2),
+        bezel as i64,
+        bezel as i64,
+    );
+
+    let island = panel(width / 4, bezel * 4 / 5, bezel * 2 / 5, color("#111113"));
+    let x = (canvas.width() - island.width()) / 2;
+    imageops::overlay(&mut canvas, &island, x as i64, (bezel + bezel / 2) as i64);
+
+    canvas
+}
+
+fn laptop(screen: &RgbaImage) -> RgbaImage {
+    let (width, height) = screen.dimensions();
+    let bezel = (width / 30).clamp(10, 60);
+    let lid = panel(
+        width + bezel * 2,
+        height + bezel * 3,
+        bezel,
+        color("#1c1c1e"),
+    );
+    let base_width = lid.width() + lid.width() / 8;
+    let base_height = bezel * 3 / 2;
+
+    let mut canvas = RgbaImage::new(base_width, lid.height() + base_height);
+    let lid_x = ((base_width - lid.width()) / 2) as i64;
+    imageops::overlay(&mut canvas, &lid, lid_x, 0);
+    imageops::overlay(&mut canvas, screen, lid_x + bezel as i64, bezel as i64);
+
+    let base = panel(base_width, base_height, base_height / 2, color("#c7c7cc"));
+    imageops::overlay(&mut canvas, &base, 0, lid.height() as i64);
+    let notch = panel(
+        base_width / 6,
+        base_height / 3,
+        base_height / 6,
+        color("#a1a1a6"),
+    );
+    let notch_x = (base_width - notch.width()) / 2;
+    imageops::overlay(&mut canvas, &notch, notch_x as i64, lid.height() as i64);
+
+    canvas
+}
+
+fn from_asset(screen: &RgbaImage, asset: &FrameAsset) -> Result<RgbaImage> {
+    let frame = image::open(&asset.image)?.to_rgba8();
+    let area = asset.screen;
+    if area.width == 0 || area.height == 0 {
+        return Err(ScreenshotError::Config(format!(
+            "Frame {} has an empty screen area",
+            asset.image.display()
+        )));
+    }
+
+    let fitted = imageops::resize(
+        screen,
+        area.width,
+        area.height,
+        imageops::FilterType::Lanczos3,
+    );
+    let mut canvas = RgbaImage::new(frame.width(), frame.height());
+    imageops::overlay(&mut canvas, &fitted, area.x as i64, area.y as i64);
+    imageops::overlay(&mut canvas, &frame, 0, 0);
+    Ok(canvas)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FrameParams {
+    style: Option<String>,
+    url: Option<String>,
+    image: Option<PathBuf>,
+    screen: Option<Region>,
+}
+
+pub struct FrameProcessor;
+
+impl Processor for FrameProcessor {
+    fn name(&self) -> &str {
+        "frame"
+    }
+
+    fn description(&self) -> &str {
+        "Place the image in a browser, phone or laptop frame"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "style": { "enum": ["browser", "phone", "laptop"] },
+                "url": { "type": "string" },
+                "image": { "type": "string" },
+                "screen": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "integer" },
+                        "y": { "type": "integer" },
+                        "width": { "type": "integer" },
+                        "height": { "type": "integer" }
+                    }
+                }
+            }
+        })
+    }
+
+    fn apply(&self, screenshot: Screenshot, params: &serde_json::Value) -> Result<Screenshot> {
+        let params: FrameParams = match params {
+            serde_json::Value::Null => FrameParams::default(),
+            params => serde_json::from_value(params.clone())?,
+        };
+
+        let frame = match (params.image, params.screen) {
+            (Some(image), Some(screen)) => DeviceFrame::Asset(FrameAsset { image, screen }),
+            (Some(_), None) => {
+                return Err(ScreenshotError::Config(
+                    "A frame image needs a screen area".to_string(),
+                ));
+            }
+            _ => match params
+                .style
+                .as_deref()
+                .unwrap_or("browser")
+                .parse()
+                .map_err(ScreenshotError::Config)?
+            {
+                DeviceFrame::Browser { .. } => DeviceFrame::Browser { url: params.url },
+                frame => frame,
+            },
+        };
+
+        frame.apply(&screenshot)
+    }
+}
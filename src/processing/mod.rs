@@ -1,4 +1,30 @@
-use crate::{Result, Screenshot};
+pub mod adjust;
+pub mod beautify;
+pub mod chrome;
+pub mod color;
+pub mod diff;
+pub mod frame;
+pub mod pipeline;
+pub mod plugin;
+mod quantize;
+pub mod redact;
+pub mod text;
+mod tiles;
+pub mod watermark;
+
+pub use adjust::Adjustments;
+pub use beautify::{BackgroundStyle, BeautifyPreset};
+pub use chrome::{ChromeStyle, WindowChrome};
+pub use diff::{DiffOptions, DiffResult};
+pub use frame::{DeviceFrame, FrameAsset};
+pub use pipeline::{EditHistory, FlipDirection, Pipeline, ProcessingStep};
+pub use plugin::{Processor, ProcessorRegistry};
+pub(crate) use quantize::quantize;
+pub use redact::RedactOptions;
+pub use text::TextStyle;
+pub use watermark::{Watermark, WatermarkPosition};
+
+use crate::{Region, Result, Screenshot, ScreenshotError};
 use image::{Rgba, RgbaImage};
 
 pub struct ImageProcessor;
@@ -78,12 +104,426 @@ impl ImageProcessor {
         Ok(new_screenshot)
     }
 
+    pub fn rotate(screenshot: &Screenshot, degrees: u32) -> Result<Screenshot> {
+        let rotated = match degrees % 360 {
+            0 => screenshot.data.clone(),
+            90 => image::imageops::rotate90(&screenshot.data),
+            180 => image::imageops::rotate180(&screenshot.data),
+            270 => image::imageops::rotate270(&screenshot.data),
+            _ => {
+                return Err(ScreenshotError::Config(format!(
+                    "Rotation must be a multiple of 90 degrees, got {}",
+                    degrees
+                )));
+            }
+        };
+
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.metadata.width = rotated.width();
+        new_screenshot.metadata.height = rotated.height();
+        new_screenshot.data = rotated;
+
+        Ok(new_screenshot)
+    }
+
+    pub fn flip_horizontal(screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        image::imageops::flip_horizontal_in_place(&mut new_screenshot.data);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn flip_vertical(screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        image::imageops::flip_vertical_in_place(&mut new_screenshot.data);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn auto_trim(screenshot: &Screenshot, tolerance: u8) -> Result<Screenshot> {
+        let data = &screenshot.data;
+        let (width, height) = data.dimensions();
+        if width == 0 || height == 0 {
+            return Ok(screenshot.clone());
+        }
+
+        // The top-left pixel decides what counts as border: fully transparent
+        // shadow margins or a uniform background colour.
+        let border = *data.get_pixel(0, 0);
+        let is_border = |pixel: &Rgba<u8>| {
+            if border[3] == 0 {
+                pixel[3] <= tolerance
+            } else {
+                pixel
+                    .0
+                    .iter()
+                    .zip(border.0.iter())
+                    .all(|(a, b)| a.abs_diff(*b) <= tolerance)
+            }
+        };
+
+        let row_is_border = |y: u32| (0..width).all(|x| is_border(data.get_pixel(x, y)));
+        let column_is_border =
+            |x: u32, top: u32, bottom: u32| (top..bottom).all(|y| is_border(data.get_pixel(x, y)));
+
+        let Some(top) = (0..height).find(|&y| !row_is_border(y)) else {
+            return Ok(screenshot.clone());
+        };
+        let bottom = (0..height)
+            .rev()
+            .find(|&y| !row_is_border(y))
+            .unwrap_or(top)
+            + 1;
+        let left = (0..width)
+            .find(|&x| !column_is_border(x, top, bottom))
+            .unwrap_or(0);
+        let right = (0..width)
+            .rev()
+            .find(|&x| !column_is_border(x, top, bottom))
+            .unwrap_or(width - 1)
+            + 1;
+
+        Self::crop(screenshot, left, top, right - left, bottom - top)
+    }
+
+    pub fn adjust(screenshot: &Screenshot, adjustments: &Adjustments) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        if !adjustments.is_identity() {
+            new_screenshot.data = adjustments.apply(&screenshot.data);
+        }
+
+        Ok(new_screenshot)
+    }
+
+    pub fn grayscale(screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.data = adjust::grayscale(&screenshot.data);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn invert(screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.data = adjust::invert(&screenshot.data);
+
+        Ok(new_screenshot)
+    }
+
     pub fn blur(screenshot: &Screenshot, sigma: f32) -> Result<Screenshot> {
-        let blurred = image::imageops::blur(&screenshot.data, sigma);
+        let blurred = tiles::blur(&screenshot.data, sigma);
 
         let mut new_screenshot = screenshot.clone();
         new_screenshot.data = blurred;
 
         Ok(new_screenshot)
     }
+
+    pub fn blur_region(screenshot: &Screenshot, region: Region, sigma: f32) -> Result<Screenshot> {
+        let (x, y, width, height) = Self::clamp_region(screenshot, region)?;
+        let patch = image::imageops::crop_imm(&screenshot.data, x, y, width, height).to_image();
+        let blurred = tiles::blur(&patch, sigma);
+
+        let mut new_screenshot = screenshot.clone();
+        image::imageops::replace(&mut new_screenshot.data, &blurred, x as i64, y as i64);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn pixelate(
+        screenshot: &Screenshot,
+        region: Option<Region>,
+        block_size: u32,
+    ) -> Result<Screenshot> {
+        let region = region.unwrap_or(Region::new(0, 0, screenshot.width(), screenshot.height()));
+        let (x, y, width, height) = Self::clamp_region(screenshot, region)?;
+        let block_size = block_size.max(1);
+
+        let mut new_screenshot = screenshot.clone();
+        tiles::pixelate(&mut new_screenshot.data, x, y, width, height, block_size);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn mask(
+        screenshot: &Screenshot,
+        regions: &[Region],
+        color: Rgba<u8>,
+    ) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+
+        for region in regions {
+            let Ok((x, y, width, height)) = Self::clamp_region(screenshot, *region) else {
+                continue;
+            };
+
+            for py in y..y + height {
+                for px in x..x + width {
+                    new_screenshot.data.put_pixel(px, py, color);
+                }
+            }
+        }
+
+        Ok(new_screenshot)
+    }
+
+    // Pixels outside the polygon become transparent. Points are in image
+    // coordinates and the path is closed implicitly; crossings use even-odd.
+    pub fn mask_polygon(screenshot: &Screenshot, points: &[(f64, f64)]) -> Result<Screenshot> {
+        if points.len() < 3 {
+            return Err(ScreenshotError::InvalidRegion(
+                "A polygon needs at least three points".to_string(),
+            ));
+        }
+
+        let mut new_screenshot = screenshot.clone();
+        let mut crossings = Vec::new();
+
+        for (y, row) in new_screenshot.data.enumerate_rows_mut() {
+            let sample = y as f64 + 0.5;
+
+            crossings.clear();
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                if (y0 <= sample) != (y1 <= sample) {
+                    crossings.push(x0 + (sample - y0) / (y1 - y0) * (x1 - x0));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for (x, _, pixel) in row {
+                let center = x as f64 + 0.5;
+                if crossings.partition_point(|&c| c < center) % 2 == 0 {
+                    pixel[3] = 0;
+                }
+            }
+        }
+
+        Ok(new_screenshot)
+    }
+
+    pub fn outline(
+        screenshot: &Screenshot,
+        region: Region,
+        color: Rgba<u8>,
+        thickness: u32,
+    ) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+
+        for i in 0..thickness.max(1) {
+            let width = region.width.saturating_sub(2 * i);
+            let height = region.height.saturating_sub(2 * i);
+            if width == 0 || height == 0 {
+                break;
+            }
+
+            let rect = imageproc::rect::Rect::at(region.x + i as i32, region.y + i as i32)
+                .of_size(width, height);
+            imageproc::drawing::draw_hollow_rect_mut(&mut new_screenshot.data, rect, color);
+        }
+
+        Ok(new_screenshot)
+    }
+
+    // Numbered bubble for step-by-step guides: a filled circle with the
+    // number centred in white.
+    pub fn add_step_marker(
+        screenshot: &Screenshot,
+        center: (i32, i32),
+        number: u32,
+        radius: u32,
+        color: Rgba<u8>,
+    ) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        let radius = radius.max(4);
+
+        imageproc::drawing::draw_filled_circle_mut(
+            &mut new_screenshot.data,
+            center,
+            radius as i32,
+            color,
+        );
+
+        let font = TextStyle::default().load_font()?;
+        let label = number.to_string();
+        let size = radius as f32 * if label.len() > 1 { 1.1 } else { 1.4 };
+        let (width, height) = text::text_size(&label, &font, size);
+        text::draw_text(
+            &mut new_screenshot.data,
+            &label,
+            center.0 - width as i32 / 2,
+            center.1 - height as i32 / 2,
+            &font,
+            size,
+            Rgba([255, 255, 255, 255]),
+        );
+
+        Ok(new_screenshot)
+    }
+
+    fn clamp_region(screenshot: &Screenshot, region: Region) -> Result<(u32, u32, u32, u32)> {
+        let bounds = Region::new(0, 0, screenshot.width(), screenshot.height());
+        let clamped = region.intersect(&bounds).ok_or_else(|| {
+            ScreenshotError::InvalidRegion("Region lies outside the image".to_string())
+        })?;
+
+        Ok((
+            clamped.x as u32,
+            clamped.y as u32,
+            clamped.width,
+            clamped.height,
+        ))
+    }
+
+    pub fn quantize(screenshot: &Screenshot, levels: u32) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.data = quantize::quantize(&screenshot.data, levels);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn round_corners(screenshot: &Screenshot, radius: u32) -> Result<Screenshot> {
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.data = beautify::round_corners(&screenshot.data, radius);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn pad_with_background(
+        screenshot: &Screenshot,
+        padding: u32,
+        background: &BackgroundStyle,
+    ) -> Result<Screenshot> {
+        let padded = beautify::pad_with_background(&screenshot.data, padding, 0, background)?;
+
+        let mut new_screenshot = screenshot.clone();
+        new_screenshot.metadata.width = padded.width();
+        new_screenshot.metadata.height = padded.height();
+        new_screenshot.data = padded;
+
+        Ok(new_screenshot)
+    }
+
+    pub fn watermark_text(
+        screenshot: &Screenshot,
+        text: &str,
+        position: WatermarkPosition,
+        opacity: f32,
+        style: &TextStyle,
+    ) -> Result<Screenshot> {
+        let font = style.load_font()?;
+        let mut color = style.color()?;
+        color[3] = (color[3] as f32 * opacity.clamp(0.0, 1.0)).round() as u8;
+
+        let size = text::text_size(text, &font, style.size);
+        let (x, y) = position.place(screenshot.data.dimensions(), size);
+
+        let mut new_screenshot = screenshot.clone();
+        text::draw_text(
+            &mut new_screenshot.data,
+            text,
+            x as i32,
+            y as i32,
+            &font,
+            style.size,
+            color,
+        );
+
+        Ok(new_screenshot)
+    }
+
+    pub fn watermark_image<P: AsRef<std::path::Path>>(
+        screenshot: &Screenshot,
+        path: P,
+        position: WatermarkPosition,
+        scale: f32,
+        opacity: f32,
+    ) -> Result<Screenshot> {
+        let mut mark = image::open(path)?.to_rgba8();
+
+        let target_width = (screenshot.width() as f32 * scale.clamp(0.01, 1.0)).round() as u32;
+        if target_width > 0 && target_width != mark.width() {
+            let target_height =
+                (mark.height() as f32 * target_width as f32 / mark.width() as f32).round() as u32;
+            mark = image::imageops::resize(
+                &mark,
+                target_width,
+                target_height.max(1),
+                image::imageops::FilterType::Lanczos3,
+            );
+        }
+
+        let opacity = opacity.clamp(0.0, 1.0);
+        for pixel in mark.pixels_mut() {
+            pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+        }
+
+        let (x, y) = position.place(screenshot.data.dimensions(), mark.dimensions());
+
+        let mut new_screenshot = screenshot.clone();
+        image::imageops::overlay(&mut new_screenshot.data, &mark, x, y);
+
+        Ok(new_screenshot)
+    }
+
+    pub fn decode_qr(screenshot: &Screenshot) -> Vec<String> {
+        let luma = image::DynamicImage::ImageRgba8(screenshot.data.clone()).to_luma8();
+        let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+            luma.width() as usize,
+            luma.height() as usize,
+            |x, y| luma.get_pixel(x as u32, y as u32).0[0],
+        );
+
+        prepared
+            .detect_grids()
+            .into_iter()
+            .filter_map(|grid| grid.decode().ok())
+            .map(|(_, content)| content)
+            .collect()
+    }
+
+    // QR codes are decoded in-process; zbarimg, when installed, adds 1D codes
+    // (EAN, UPC, Code 128, ...) and the remaining 2D symbologies.
+    pub fn decode_barcodes(screenshot: &Screenshot) -> Result<Vec<String>> {
+        let mut payloads = Self::decode_qr(screenshot);
+        for payload in zbar_decode(screenshot)? {
+            if !payloads.contains(&payload) {
+                payloads.push(payload);
+            }
+        }
+        Ok(payloads)
+    }
+
+    pub fn beautify(screenshot: &Screenshot, preset: &BeautifyPreset) -> Result<Screenshot> {
+        beautify::beautify(screenshot, preset)
+    }
+}
+
+fn zbar_decode(screenshot: &Screenshot) -> Result<Vec<String>> {
+    let path = std::env::temp_dir().join(format!(
+        "wst-scan-{}-{}.png",
+        std::process::id(),
+        screenshot.metadata.timestamp.timestamp_millis()
+    ));
+    crate::export::Exporter::save(screenshot, &path, crate::OutputFormat::Png)?;
+
+    let output = std::process::Command::new("zbarimg")
+        .args(["--quiet", "--raw"])
+        .arg(&path)
+        .output();
+    let _ = std::fs::remove_file(&path);
+
+    // zbarimg exits non-zero when it finds nothing, so only stdout matters.
+    match output {
+        Ok(output) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::debug!("zbarimg not installed; only QR codes are decoded");
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e.into()),
+    }
 }
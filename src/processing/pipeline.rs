@@ -0,0 +1,269 @@
+use super::{
+    Adjustments, BackgroundStyle, BeautifyPreset, ImageProcessor, ProcessorRegistry, WindowChrome,
+    color::parse_color,
+};
+use crate::{Region, Result, Screenshot, ScreenshotError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlipDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl std::str::FromStr for FlipDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "h" | "horizontal" => Ok(Self::Horizontal),
+            "v" | "vertical" => Ok(Self::Vertical),
+            _ => Err(format!("Invalid flip direction: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ProcessingStep {
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    Border {
+        width: u32,
+        #[serde(default = "default_border_color")]
+        color: String,
+    },
+    Shadow {
+        offset: u32,
+    },
+    Rotate {
+        degrees: u32,
+    },
+    Flip {
+        direction: FlipDirection,
+    },
+    AutoTrim {
+        #[serde(default)]
+        tolerance: u8,
+    },
+    Adjust {
+        #[serde(flatten)]
+        adjustments: Adjustments,
+    },
+    Grayscale,
+    Invert,
+    Blur {
+        sigma: f32,
+        #[serde(default)]
+        region: Option<Region>,
+    },
+    Pixelate {
+        block_size: u32,
+        #[serde(default)]
+        region: Option<Region>,
+    },
+    Annotate {
+        region: Region,
+        #[serde(default = "default_annotation_color")]
+        color: String,
+        #[serde(default = "default_annotation_thickness")]
+        thickness: u32,
+    },
+    RoundCorners {
+        radius: u32,
+    },
+    Pad {
+        padding: u32,
+        background: BackgroundStyle,
+    },
+    Beautify {
+        preset: String,
+    },
+    Chrome {
+        #[serde(flatten)]
+        chrome: WindowChrome,
+    },
+    Plugin {
+        name: String,
+        #[serde(default)]
+        params: serde_json::Value,
+    },
+}
+
+fn default_border_color() -> String {
+    "#000000".to_string()
+}
+
+fn default_annotation_color() -> String {
+    "#ff3b30".to_string()
+}
+
+fn default_annotation_thickness() -> u32 {
+    3
+}
+
+impl ProcessingStep {
+    pub fn apply(
+        &self,
+        screenshot: &Screenshot,
+        presets: &BTreeMap<String, BeautifyPreset>,
+    ) -> Result<Screenshot> {
+        match self {
+            Self::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => ImageProcessor::crop(screenshot, *x, *y, *width, *height),
+            Self::Resize { width, height } => ImageProcessor::resize(screenshot, *width, *height),
+            Self::Border { width, color } => {
+                ImageProcessor::add_border(screenshot, *width, step_color(color)?)
+            }
+            Self::Shadow { offset } => ImageProcessor::add_shadow(screenshot, *offset),
+            Self::Rotate { degrees } => ImageProcessor::rotate(screenshot, *degrees),
+            Self::Flip { direction } => match direction {
+                FlipDirection::Horizontal => ImageProcessor::flip_horizontal(screenshot),
+                FlipDirection::Vertical => ImageProcessor::flip_vertical(screenshot),
+            },
+            Self::AutoTrim { tolerance } => ImageProcessor::auto_trim(screenshot, *tolerance),
+            Self::Adjust { adjustments } => ImageProcessor::adjust(screenshot, adjustments),
+            Self::Grayscale => ImageProcessor::grayscale(screenshot),
+            Self::Invert => ImageProcessor::invert(screenshot),
+            Self::Blur { sigma, region } => match region {
+                Some(region) => ImageProcessor::blur_region(screenshot, *region, *sigma),
+                None => ImageProcessor::blur(screenshot, *sigma),
+            },
+            Self::Pixelate { block_size, region } => {
+                ImageProcessor::pixelate(screenshot, *region, *block_size)
+            }
+            Self::Annotate {
+                region,
+                color,
+                thickness,
+            } => ImageProcessor::outline(screenshot, *region, step_color(color)?, *thickness),
+            Self::RoundCorners { radius } => ImageProcessor::round_corners(screenshot, *radius),
+            Self::Pad {
+                padding,
+                background,
+            } => ImageProcessor::pad_with_background(screenshot, *padding, background),
+            Self::Beautify { preset } => {
+                ImageProcessor::beautify(screenshot, &BeautifyPreset::find(preset, presets)?)
+            }
+            Self::Chrome { chrome } => chrome.apply(screenshot),
+            Self::Plugin { name, params } => {
+                ProcessorRegistry::apply(name, screenshot.clone(), params)
+            }
+        }
+    }
+}
+
+fn step_color(color: &str) -> Result<image::Rgba<u8>> {
+    parse_color(color).ok_or_else(|| ScreenshotError::Config(format!("Invalid color: {}", color)))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pipeline {
+    #[serde(default)]
+    pub steps: Vec<ProcessingStep>,
+}
+
+impl Pipeline {
+    pub fn new(steps: Vec<ProcessingStep>) -> Self {
+        Self { steps }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn push(&mut self, step: ProcessingStep) {
+        self.steps.push(step);
+    }
+
+    pub fn apply(
+        &self,
+        screenshot: &Screenshot,
+        presets: &BTreeMap<String, BeautifyPreset>,
+    ) -> Result<Screenshot> {
+        let mut result = screenshot.clone();
+        for step in &self.steps {
+            result = step.apply(&result, presets)?;
+        }
+        Ok(result)
+    }
+
+    pub fn find(name: &str, pipelines: &BTreeMap<String, Pipeline>) -> Result<Self> {
+        pipelines
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ScreenshotError::Config(format!("Unknown pipeline: {}", name)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EditHistory {
+    original: Screenshot,
+    pipeline: Pipeline,
+    undone: Vec<ProcessingStep>,
+}
+
+impl EditHistory {
+    pub fn new(original: Screenshot) -> Self {
+        Self {
+            original,
+            pipeline: Pipeline::default(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    pub fn push(&mut self, step: ProcessingStep) {
+        self.pipeline.push(step);
+        self.undone.clear();
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.pipeline.steps.pop() {
+            Some(step) => {
+                self.undone.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(step) => {
+                self.pipeline.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.pipeline.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub fn render(&self, presets: &BTreeMap<String, BeautifyPreset>) -> Result<Screenshot> {
+        self.pipeline.apply(&self.original, presets)
+    }
+}
@@ -0,0 +1,79 @@
+use super::frame::FrameProcessor;
+use crate::{Result, Screenshot, ScreenshotError};
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+// A filter third-party crates can plug into pipelines and `wst process`
+// without touching ProcessingStep. `params` describes the accepted
+// parameters as a JSON schema; `apply` receives the values for one step.
+pub trait Processor: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    fn params(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object" })
+    }
+
+    fn apply(&self, screenshot: Screenshot, params: &serde_json::Value) -> Result<Screenshot>;
+}
+
+static REGISTRY: Lazy<RwLock<BTreeMap<String, Arc<dyn Processor>>>> = Lazy::new(|| {
+    let builtin: [Arc<dyn Processor>; 1] = [Arc::new(FrameProcessor)];
+    RwLock::new(
+        builtin
+            .into_iter()
+            .map(|processor| (processor.name().to_string(), processor))
+            .collect(),
+    )
+});
+
+pub struct ProcessorRegistry;
+
+impl ProcessorRegistry {
+    // Registering a name twice replaces the earlier processor.
+    pub fn register(processor: impl Processor + 'static) {
+        let processor: Arc<dyn Processor> = Arc::new(processor);
+        REGISTRY
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(processor.name().to_string(), processor);
+    }
+
+    pub fn get(name: &str) -> Result<Arc<dyn Processor>> {
+        REGISTRY
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ScreenshotError::Config(format!("Unknown processor: {}", name)))
+    }
+
+    pub fn all() -> Vec<Arc<dyn Processor>> {
+        REGISTRY
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn apply(
+        name: &str,
+        screenshot: Screenshot,
+        params: &serde_json::Value,
+    ) -> Result<Screenshot> {
+        Self::get(name)?.apply(screenshot, params)
+    }
+}
+
+// NAME or NAME:{"json":"params"}, as given to `wst process --processor`.
+pub fn parse_spec(spec: &str) -> Result<(String, serde_json::Value)> {
+    match spec.split_once(':') {
+        Some((name, params)) => Ok((name.to_string(), serde_json::from_str(params)?)),
+        None => Ok((spec.to_string(), serde_json::Value::Null)),
+    }
+}
@@ -0,0 +1,70 @@
+use image::{Rgba, RgbaImage};
+
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+pub(crate) fn quantize(image: &RgbaImage, levels: u32) -> RgbaImage {
+    let levels = levels.clamp(2, 256);
+    let (low, high) = luminance_range(image);
+    let range = (high - low).max(1.0);
+    let step = 255.0 / (levels - 1) as f64;
+
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let stretched =
+            ((luminance(image.get_pixel(x, y)) - low) / range * 255.0).clamp(0.0, 255.0);
+
+        let threshold = (BAYER_8X8[(y % 8) as usize][(x % 8) as usize] as f64 + 0.5) / 64.0 - 0.5;
+        let level = (stretched / step + threshold)
+            .round()
+            .clamp(0.0, (levels - 1) as f64);
+        let value = (level * step).round() as u8;
+
+        Rgba([value, value, value, 255])
+    })
+}
+
+// Composited onto white: e-ink panels and thermal paper have no transparency,
+// and a transparent corner should not print as black.
+fn luminance(pixel: &Rgba<u8>) -> f64 {
+    let alpha = pixel[3] as f64 / 255.0;
+    let value = 0.2126 * pixel[0] as f64 + 0.7152 * pixel[1] as f64 + 0.0722 * pixel[2] as f64;
+    value * alpha + 255.0 * (1.0 - alpha)
+}
+
+// Stretch contrast between the 1st and 99th percentile so washed-out UI
+// greys still land on distinct palette entries.
+fn luminance_range(image: &RgbaImage) -> (f64, f64) {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        histogram[luminance(pixel).round() as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().sum();
+    let cutoff = total / 100;
+
+    let low = percentile(histogram.iter().enumerate(), cutoff).unwrap_or(0.0);
+    let high = percentile(histogram.iter().enumerate().rev(), cutoff).unwrap_or(255.0);
+
+    if high > low {
+        (low, high)
+    } else {
+        (0.0, 255.0)
+    }
+}
+
+fn percentile<'a>(mut bins: impl Iterator<Item = (usize, &'a u64)>, cutoff: u64) -> Option<f64> {
+    let mut seen = 0;
+    bins.find(|(_, count)| {
+        seen += **count;
+        seen > cutoff
+    })
+    .map(|(value, _)| value as f64)
+}
@@ -0,0 +1,159 @@
+use super::ImageProcessor;
+use crate::{OutputFormat, Region, Result, Screenshot, ScreenshotError, export::Exporter};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const EMAIL: &str = r"[\w.+-]+@[\w-]+(\.[\w-]+)+";
+const IPV4: &str = r"\b(\d{1,3}\.){3}\d{1,3}\b";
+const IPV6: &str = r"\b([0-9a-fA-F]{1,4}:){3,7}[0-9a-fA-F]{1,4}\b";
+const TOKEN: &str = r"\b([0-9a-fA-F]{16,}|[A-Za-z0-9_\-]{32,})\b";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactOptions {
+    pub builtin: bool,
+    pub patterns: Vec<String>,
+    pub sigma: f32,
+    pub padding: u32,
+    pub ocr_command: String,
+    pub language: Option<String>,
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            patterns: Vec::new(),
+            sigma: 8.0,
+            padding: 2,
+            ocr_command: "tesseract".to_string(),
+            language: None,
+        }
+    }
+}
+
+impl RedactOptions {
+    fn rules(&self) -> Result<Vec<Regex>> {
+        let builtin: &[&str] = if self.builtin {
+            &[EMAIL, IPV4, IPV6, TOKEN]
+        } else {
+            &[]
+        };
+
+        builtin
+            .iter()
+            .copied()
+            .chain(self.patterns.iter().map(String::as_str))
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    ScreenshotError::Config(format!("Invalid redact pattern {}: {}", pattern, e))
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Word {
+    pub text: String,
+    pub region: Region,
+}
+
+// Runs the OCR engine over the image and returns every recognised word
+// with its box, using tesseract's TSV output.
+pub fn recognize(screenshot: &Screenshot, options: &RedactOptions) -> Result<Vec<Word>> {
+    let png = Exporter::encode(screenshot, OutputFormat::Png, &Default::default())?;
+
+    let mut cmd = Command::new(&options.ocr_command);
+    cmd.args(["stdin", "stdout"]);
+    if let Some(language) = &options.language {
+        cmd.args(["-l", language]);
+    }
+    let mut child = cmd
+        .arg("tsv")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| ScreenshotError::ToolUnavailable {
+            tool: options.ocr_command.clone(),
+            reason: "install it to use --auto-redact".to_string(),
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&png)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ScreenshotError::Unknown(format!(
+            "{} failed to read the image",
+            options.ocr_command
+        )));
+    }
+
+    Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Columns: level page block par line word left top width height conf text.
+// Level 5 rows are single words.
+fn parse_tsv(tsv: &str) -> Vec<Word> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 12 || fields[0] != "5" {
+                return None;
+            }
+
+            let text = fields[11].trim();
+            if text.is_empty() {
+                return None;
+            }
+
+            Some(Word {
+                text: text.to_string(),
+                region: Region::new(
+                    fields[6].parse().ok()?,
+                    fields[7].parse().ok()?,
+                    fields[8].parse().ok()?,
+                    fields[9].parse().ok()?,
+                ),
+            })
+        })
+        .collect()
+}
+
+pub fn sensitive_regions(words: &[Word], options: &RedactOptions) -> Result<Vec<Region>> {
+    let rules = options.rules()?;
+    let padding = options.padding as i32;
+
+    Ok(words
+        .iter()
+        .filter(|word| rules.iter().any(|rule| rule.is_match(&word.text)))
+        .map(|word| {
+            Region::new(
+                word.region.x - padding,
+                word.region.y - padding,
+                word.region.width + 2 * options.padding,
+                word.region.height + 2 * options.padding,
+            )
+        })
+        .collect())
+}
+
+pub fn auto_redact(
+    screenshot: &Screenshot,
+    options: &RedactOptions,
+) -> Result<(Screenshot, usize)> {
+    let words = recognize(screenshot, options)?;
+    let regions = sensitive_regions(&words, options)?;
+
+    let mut redacted = screenshot.clone();
+    for region in &regions {
+        redacted = ImageProcessor::blur_region(&redacted, *region, options.sigma)?;
+    }
+
+    Ok((redacted, regions.len()))
+}
@@ -0,0 +1,172 @@
+use super::color::parse_color;
+use crate::{Result, ScreenshotError};
+use ab_glyph::{FontVec, PxScale};
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextStyle {
+    pub font: Option<PathBuf>,
+    pub family: Option<String>,
+    pub size: f32,
+    pub color: String,
+    pub outline: Option<String>,
+    pub outline_width: u32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font: None,
+            family: None,
+            size: 24.0,
+            color: "#ffffff".to_string(),
+            outline: None,
+            outline_width: 2,
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn load_font(&self) -> Result<FontVec> {
+        let path = match (&self.font, &self.family) {
+            (Some(path), _) => path.clone(),
+            (None, Some(family)) => match_font(family)?,
+            (None, None) => match_font("sans-serif:bold")?,
+        };
+        load_font(&path)
+    }
+
+    pub fn color(&self) -> Result<Rgba<u8>> {
+        parse_color(&self.color)
+            .ok_or_else(|| ScreenshotError::Config(format!("Invalid color: {}", self.color)))
+    }
+
+    pub fn outline_color(&self) -> Result<Option<Rgba<u8>>> {
+        self.outline
+            .as_deref()
+            .map(|color| {
+                parse_color(color)
+                    .ok_or_else(|| ScreenshotError::Config(format!("Invalid color: {}", color)))
+            })
+            .transpose()
+    }
+
+    pub fn draw(&self, image: &mut RgbaImage, text: &str, x: i32, y: i32) -> Result<()> {
+        let font = self.load_font()?;
+        let mask = text_mask(image.dimensions(), text, x, y, &font, self.size);
+
+        if let Some(outline) = self.outline_color()? {
+            let width = self.outline_width.min(u8::MAX as u32) as u8;
+            let outline_mask = imageproc::morphology::dilate(
+                &mask,
+                imageproc::distance_transform::Norm::LInf,
+                width,
+            );
+            blend_mask(image, &outline_mask, outline);
+        }
+
+        blend_mask(image, &mask, self.color()?);
+        Ok(())
+    }
+}
+
+pub fn load_font(path: &Path) -> Result<FontVec> {
+    let bytes = std::fs::read(path)?;
+    FontVec::try_from_vec(bytes)
+        .map_err(|_| ScreenshotError::Config(format!("Invalid font file: {}", path.display())))
+}
+
+// Accepts any fontconfig pattern, e.g. "DejaVu Serif:italic".
+fn match_font(pattern: &str) -> Result<PathBuf> {
+    let output = std::process::Command::new("fc-match")
+        .arg("--format=%{file}")
+        .arg(pattern)
+        .output()
+        .map_err(|_| ScreenshotError::ToolUnavailable {
+            tool: "fc-match".to_string(),
+            reason: "set a font path instead".to_string(),
+        })?;
+
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    if !output.status.success() || !path.is_file() {
+        return Err(ScreenshotError::Config(format!(
+            "No font found for {}; set a font path",
+            pattern
+        )));
+    }
+
+    Ok(path)
+}
+
+pub(crate) fn text_size(text: &str, font: &FontVec, size: f32) -> (u32, u32) {
+    imageproc::drawing::text_size(PxScale::from(size), font, text)
+}
+
+// Text is rendered into a coverage mask first so the colour can be
+// alpha-composited instead of blended channel-by-channel.
+pub(crate) fn draw_text(
+    image: &mut RgbaImage,
+    text: &str,
+    x: i32,
+    y: i32,
+    font: &FontVec,
+    size: f32,
+    color: Rgba<u8>,
+) {
+    let mask = text_mask(image.dimensions(), text, x, y, font, size);
+    blend_mask(image, &mask, color);
+}
+
+fn text_mask(
+    (width, height): (u32, u32),
+    text: &str,
+    x: i32,
+    y: i32,
+    font: &FontVec,
+    size: f32,
+) -> GrayImage {
+    let mut mask = GrayImage::new(width, height);
+    imageproc::drawing::draw_text_mut(
+        &mut mask,
+        Luma([255]),
+        x,
+        y,
+        PxScale::from(size),
+        font,
+        text,
+    );
+    mask
+}
+
+fn blend_mask(image: &mut RgbaImage, mask: &GrayImage, color: Rgba<u8>) {
+    for (px, py, coverage) in mask.enumerate_pixels() {
+        if coverage[0] == 0 {
+            continue;
+        }
+
+        let alpha = color[3] as u32 * coverage[0] as u32 / 255;
+        blend_pixel(image.get_pixel_mut(px, py), color, alpha as u8);
+    }
+}
+
+pub(crate) fn blend_pixel(target: &mut Rgba<u8>, color: Rgba<u8>, alpha: u8) {
+    let source_alpha = alpha as f32 / 255.0;
+    let target_alpha = target[3] as f32 / 255.0;
+    let out_alpha = source_alpha + target_alpha * (1.0 - source_alpha);
+
+    if out_alpha <= 0.0 {
+        *target = Rgba([0, 0, 0, 0]);
+        return;
+    }
+
+    for i in 0..3 {
+        let value = (color[i] as f32 * source_alpha
+            + target[i] as f32 * target_alpha * (1.0 - source_alpha))
+            / out_alpha;
+        target[i] = value.round() as u8;
+    }
+    target[3] = (out_alpha * 255.0).round() as u8;
+}
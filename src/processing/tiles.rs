@@ -0,0 +1,86 @@
+use image::RgbaImage;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+const MIN_STRIP_HEIGHT: u32 = 64;
+
+pub(crate) fn blur(image: &RgbaImage, sigma: f32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let strip_height = strip_height(height);
+
+    // Each strip is blurred with enough surrounding rows that the kernel never
+    // sees the artificial strip edge.
+    let margin = (sigma * 3.0).ceil() as u32 + 1;
+    let starts: Vec<u32> = (0..height).step_by(strip_height as usize).collect();
+
+    let blur_strip = |&start: &u32| {
+        let end = (start + strip_height).min(height);
+        let top = start.saturating_sub(margin);
+        let bottom = (end + margin).min(height);
+
+        let patch = image::imageops::crop_imm(image, 0, top, width, bottom - top).to_image();
+        let blurred = image::imageops::blur(&patch, sigma);
+        let strip =
+            image::imageops::crop_imm(&blurred, 0, start - top, width, end - start).to_image();
+        (start, strip)
+    };
+
+    #[cfg(feature = "parallel")]
+    let strips: Vec<(u32, RgbaImage)> = starts.par_iter().map(blur_strip).collect();
+    #[cfg(not(feature = "parallel"))]
+    let strips: Vec<(u32, RgbaImage)> = starts.iter().map(blur_strip).collect();
+
+    let mut output = RgbaImage::new(width, height);
+    for (start, strip) in strips {
+        image::imageops::replace(&mut output, &strip, 0, start as i64);
+    }
+    output
+}
+
+pub(crate) fn pixelate(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, block: u32) {
+    let stride = image.width() as usize * 4;
+    let rows = &mut image.as_mut()[y as usize * stride..(y + height) as usize * stride];
+    let chunk = block as usize * stride;
+
+    let pixelate_rows = |band: &mut [u8]| {
+        let band_height = (band.len() / stride) as u32;
+
+        for block_x in (x..x + width).step_by(block as usize) {
+            let block_w = block.min(x + width - block_x);
+            let columns = block_x as usize * 4..(block_x + block_w) as usize * 4;
+
+            let mut sum = [0u64; 4];
+            for row in band.chunks(stride) {
+                for pixel in row[columns.clone()].chunks(4) {
+                    for (total, channel) in sum.iter_mut().zip(pixel) {
+                        *total += *channel as u64;
+                    }
+                }
+            }
+
+            let count = (block_w * band_height) as u64;
+            let average = sum.map(|total| (total / count) as u8);
+
+            for row in band.chunks_mut(stride) {
+                for pixel in row[columns.clone()].chunks_mut(4) {
+                    pixel.copy_from_slice(&average);
+                }
+            }
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    rows.par_chunks_mut(chunk).for_each(pixelate_rows);
+    #[cfg(not(feature = "parallel"))]
+    rows.chunks_mut(chunk).for_each(pixelate_rows);
+}
+
+fn strip_height(height: u32) -> u32 {
+    #[cfg(feature = "parallel")]
+    let strips = rayon::current_num_threads() as u32;
+    #[cfg(not(feature = "parallel"))]
+    let strips = 1;
+
+    height.div_ceil(strips.max(1)).max(MIN_STRIP_HEIGHT)
+}
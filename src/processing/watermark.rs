@@ -0,0 +1,100 @@
+use super::{ImageProcessor, text::TextStyle};
+use crate::{Result, Screenshot};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+impl std::str::FromStr for WatermarkPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            "center" => Ok(Self::Center),
+            _ => Err(format!("Invalid watermark position: {}", s)),
+        }
+    }
+}
+
+const MARGIN: u32 = 16;
+
+impl WatermarkPosition {
+    pub(crate) fn place(&self, canvas: (u32, u32), item: (u32, u32)) -> (i64, i64) {
+        let (canvas_w, canvas_h) = (canvas.0 as i64, canvas.1 as i64);
+        let (item_w, item_h) = (item.0 as i64, item.1 as i64);
+        let margin = MARGIN as i64;
+
+        match self {
+            Self::TopLeft => (margin, margin),
+            Self::TopRight => (canvas_w - item_w - margin, margin),
+            Self::BottomLeft => (margin, canvas_h - item_h - margin),
+            Self::BottomRight => (canvas_w - item_w - margin, canvas_h - item_h - margin),
+            Self::Center => ((canvas_w - item_w) / 2, (canvas_h - item_h) / 2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Watermark {
+    pub text: Option<String>,
+    pub image: Option<PathBuf>,
+    pub position: WatermarkPosition,
+    pub opacity: f32,
+    pub scale: f32,
+    pub style: TextStyle,
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self {
+            text: None,
+            image: None,
+            position: WatermarkPosition::default(),
+            opacity: 0.6,
+            scale: 0.15,
+            style: TextStyle::default(),
+        }
+    }
+}
+
+impl Watermark {
+    pub fn apply(&self, screenshot: &Screenshot) -> Result<Screenshot> {
+        let mut result = screenshot.clone();
+
+        if let Some(path) = &self.image {
+            result = ImageProcessor::watermark_image(
+                &result,
+                path,
+                self.position,
+                self.scale,
+                self.opacity,
+            )?;
+        }
+
+        if let Some(text) = &self.text {
+            result = ImageProcessor::watermark_text(
+                &result,
+                text,
+                self.position,
+                self.opacity,
+                &self.style,
+            )?;
+        }
+
+        Ok(result)
+    }
+}
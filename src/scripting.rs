@@ -0,0 +1,172 @@
+use crate::{Region, Result, Screenshot, ScreenshotError, processing::ImageProcessor};
+use image::Rgba;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+#[derive(Debug, Default)]
+pub struct ScriptOutcome {
+    pub rename: Option<String>,
+    pub upload: Option<String>,
+    pub aborted: bool,
+}
+
+struct State {
+    screenshot: Screenshot,
+    outcome: ScriptOutcome,
+}
+
+// Rhai passes arguments by value, so the handle shares its state with the
+// caller instead of carrying the image itself.
+#[derive(Clone)]
+struct Shot(Rc<RefCell<State>>);
+
+impl Shot {
+    fn apply(&mut self, step: impl FnOnce(&Screenshot) -> Result<Screenshot>) -> ScriptResult<()> {
+        let mut state = self.0.borrow_mut();
+        let next = step(&state.screenshot).map_err(|e| e.to_string())?;
+        state.screenshot = next;
+        Ok(())
+    }
+
+    fn metadata(&mut self, field: impl FnOnce(&Screenshot) -> Option<String>) -> String {
+        field(&self.0.borrow().screenshot).unwrap_or_default()
+    }
+}
+
+fn region(x: i64, y: i64, width: i64, height: i64) -> Region {
+    Region::new(
+        x as i32,
+        y as i32,
+        width.max(0) as u32,
+        height.max(0) as u32,
+    )
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Shot>("Screenshot")
+        .register_get("width", |s: &mut Shot| {
+            s.0.borrow().screenshot.width() as i64
+        })
+        .register_get("height", |s: &mut Shot| {
+            s.0.borrow().screenshot.height() as i64
+        })
+        .register_get("mode", |s: &mut Shot| {
+            s.metadata(|shot| Some(format!("{:?}", shot.metadata.mode).to_lowercase()))
+        })
+        .register_get("app_id", |s: &mut Shot| {
+            s.metadata(|shot| shot.metadata.app_id.clone())
+        })
+        .register_get("window_title", |s: &mut Shot| {
+            s.metadata(|shot| shot.metadata.window_title.clone())
+        })
+        .register_get("monitor", |s: &mut Shot| {
+            s.metadata(|shot| shot.metadata.monitor.clone())
+        })
+        .register_fn(
+            "pixel",
+            |s: &mut Shot, x: i64, y: i64| -> ScriptResult<Array> {
+                let state = s.0.borrow();
+                let data = &state.screenshot.data;
+                if x < 0 || y < 0 || x >= data.width() as i64 || y >= data.height() as i64 {
+                    return Err(format!("Pixel {},{} is outside the image", x, y).into());
+                }
+                let pixel = data.get_pixel(x as u32, y as u32);
+                Ok(pixel.0.iter().map(|&c| Dynamic::from(c as i64)).collect())
+            },
+        )
+        .register_fn(
+            "set_pixel",
+            |s: &mut Shot, x: i64, y: i64, rgba: Array| -> ScriptResult<()> {
+                let mut channels = [255u8; 4];
+                for (channel, value) in channels.iter_mut().zip(rgba) {
+                    *channel = value.as_int()?.clamp(0, 255) as u8;
+                }
+                let mut state = s.0.borrow_mut();
+                let data = &mut state.screenshot.data;
+                if x >= 0 && y >= 0 && x < data.width() as i64 && y < data.height() as i64 {
+                    data.put_pixel(x as u32, y as u32, Rgba(channels));
+                }
+                Ok(())
+            },
+        )
+        .register_fn(
+            "blur",
+            |s: &mut Shot, x: i64, y: i64, w: i64, h: i64, sigma: f64| {
+                s.apply(|shot| ImageProcessor::blur_region(shot, region(x, y, w, h), sigma as f32))
+            },
+        )
+        .register_fn(
+            "pixelate",
+            |s: &mut Shot, x: i64, y: i64, w: i64, h: i64, block: i64| {
+                s.apply(|shot| {
+                    ImageProcessor::pixelate(shot, Some(region(x, y, w, h)), block.max(1) as u32)
+                })
+            },
+        )
+        .register_fn("mask", |s: &mut Shot, x: i64, y: i64, w: i64, h: i64| {
+            s.apply(|shot| ImageProcessor::mask(shot, &[region(x, y, w, h)], Rgba([0, 0, 0, 255])))
+        })
+        .register_fn("crop", |s: &mut Shot, x: i64, y: i64, w: i64, h: i64| {
+            s.apply(|shot| shot.crop(region(x, y, w, h)))
+        })
+        .register_fn("rename", |s: &mut Shot, name: &str| {
+            s.0.borrow_mut().outcome.rename = Some(name.to_string());
+        })
+        .register_fn("upload", |s: &mut Shot, target: &str| {
+            s.0.borrow_mut().outcome.upload = Some(target.to_string());
+        })
+        .register_fn("upload", |s: &mut Shot| {
+            s.0.borrow_mut().outcome.upload = Some(String::new());
+        })
+        .register_fn("abort", |s: &mut Shot| {
+            s.0.borrow_mut().outcome.aborted = true;
+        });
+
+    engine
+}
+
+// Runs `fn on_capture(shot)` from the script. The engine lives only for the
+// call, so nothing non-Send is held across the caller's awaits.
+pub fn on_capture(script: &Path, screenshot: Screenshot) -> Result<(Screenshot, ScriptOutcome)> {
+    let engine = engine();
+    let ast = engine
+        .compile_file(script.to_path_buf())
+        .map_err(|e| script_error(script, e))?;
+
+    if !ast.iter_functions().any(|f| f.name == "on_capture") {
+        return Err(ScreenshotError::Config(format!(
+            "{} does not define on_capture(shot)",
+            script.display()
+        )));
+    }
+
+    let shot = Shot(Rc::new(RefCell::new(State {
+        screenshot,
+        outcome: ScriptOutcome::default(),
+    })));
+    let _ = engine
+        .call_fn::<Dynamic>(&mut Scope::new(), &ast, "on_capture", (shot.clone(),))
+        .map_err(|e| script_error(script, e))?;
+
+    let state = Rc::try_unwrap(shot.0)
+        .map(RefCell::into_inner)
+        .unwrap_or_else(|shared| {
+            let mut state = shared.borrow_mut();
+            State {
+                screenshot: state.screenshot.clone(),
+                outcome: std::mem::take(&mut state.outcome),
+            }
+        });
+    Ok((state.screenshot, state.outcome))
+}
+
+fn script_error(script: &Path, e: Box<EvalAltResult>) -> ScreenshotError {
+    ScreenshotError::Config(format!("{}: {}", script.display(), e))
+}
@@ -0,0 +1,72 @@
+use crate::{Result, error::ScreenshotError, history::History};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sequence {
+    pub name: String,
+    pub directory: PathBuf,
+    pub counter: u32,
+}
+
+impl Sequence {
+    fn state_file_path() -> Result<PathBuf> {
+        Ok(History::state_directory()?.join("sequence.json"))
+    }
+
+    pub fn start(name: &str, directory: PathBuf) -> Result<Self> {
+        if name.is_empty() || name.contains('/') {
+            return Err(ScreenshotError::Config(format!(
+                "Invalid sequence name: {:?}",
+                name
+            )));
+        }
+
+        let sequence = Self {
+            name: name.to_string(),
+            directory,
+            counter: 0,
+        };
+        sequence.save()?;
+        Ok(sequence)
+    }
+
+    pub fn current() -> Result<Option<Self>> {
+        let path = Self::state_file_path()?;
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    pub fn stop() -> Result<Option<Self>> {
+        let current = Self::current()?;
+        if current.is_some() {
+            std::fs::remove_file(Self::state_file_path()?)?;
+        }
+        Ok(current)
+    }
+
+    pub fn next_path(&mut self, extension: &str) -> Result<PathBuf> {
+        self.counter += 1;
+        self.save()?;
+
+        Ok(self
+            .directory
+            .join(format!("{}-{:02}.{}", self.name, self.counter, extension)))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::state_file_path()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use crate::Region;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aspect {
+    #[default]
+    Free,
+    Square,
+    Wide,
+    Standard,
+}
+
+impl Aspect {
+    pub const ALL: [Aspect; 4] = [Self::Free, Self::Square, Self::Wide, Self::Standard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Free => "Free",
+            Self::Square => "1:1",
+            Self::Wide => "16:9",
+            Self::Standard => "4:3",
+        }
+    }
+
+    pub fn ratio(self) -> Option<f64> {
+        match self {
+            Self::Free => None,
+            Self::Square => Some(1.0),
+            Self::Wide => Some(16.0 / 9.0),
+            Self::Standard => Some(4.0 / 3.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Edges {
+    left: bool,
+    top: bool,
+    right: bool,
+    bottom: bool,
+}
+
+impl Edges {
+    fn any(self) -> bool {
+        self.left || self.top || self.right || self.bottom
+    }
+}
+
+// Coordinates are in image pixels. The rectangle is kept as free edges while
+// dragging so an edge can be pulled past its opposite; `region` normalises.
+#[derive(Debug, Clone, Default)]
+pub struct CropSelection {
+    pub aspect: Aspect,
+    rect: Option<(f64, f64, f64, f64)>,
+    drag: Option<Edges>,
+}
+
+impl CropSelection {
+    pub fn begin(&mut self, x: f64, y: f64, tolerance: f64) {
+        if let Some((left, top, right, bottom)) = self.rect {
+            let within_x = x >= left.min(right) - tolerance && x <= left.max(right) + tolerance;
+            let within_y = y >= top.min(bottom) - tolerance && y <= top.max(bottom) + tolerance;
+            let edges = Edges {
+                left: within_y && (x - left).abs() <= tolerance,
+                top: within_x && (y - top).abs() <= tolerance,
+                right: within_y && (x - right).abs() <= tolerance,
+                bottom: within_x && (y - bottom).abs() <= tolerance,
+            };
+
+            if edges.any() {
+                self.drag = Some(edges);
+                return;
+            }
+        }
+
+        self.rect = Some((x, y, x, y));
+        self.drag = Some(Edges {
+            right: true,
+            bottom: true,
+            ..Edges::default()
+        });
+    }
+
+    pub fn update(&mut self, x: f64, y: f64) {
+        let (Some(edges), Some((left, top, right, bottom))) = (self.drag, self.rect.as_mut())
+        else {
+            return;
+        };
+
+        if edges.left {
+            *left = x;
+        }
+        if edges.right {
+            *right = x;
+        }
+        if edges.top {
+            *top = y;
+        }
+        if edges.bottom {
+            *bottom = y;
+        }
+
+        let Some(ratio) = self.aspect.ratio() else {
+            return;
+        };
+
+        let (width, height) = (*right - *left, *bottom - *top);
+        let vertical_only = (edges.top || edges.bottom) && !(edges.left || edges.right);
+
+        if vertical_only {
+            *right = *left + height.abs() * ratio * if width < 0.0 { -1.0 } else { 1.0 };
+        } else {
+            let height = width.abs() / ratio * if height < 0.0 { -1.0 } else { 1.0 };
+            if edges.top {
+                *top = *bottom - height;
+            } else {
+                *bottom = *top + height;
+            }
+        }
+    }
+
+    pub fn end(&mut self) {
+        self.drag = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.rect = None;
+        self.drag = None;
+    }
+
+    pub fn region(&self, width: u32, height: u32) -> Option<Region> {
+        let (left, top, right, bottom) = self.rect?;
+
+        let x0 = left.min(right).clamp(0.0, width as f64).round();
+        let y0 = top.min(bottom).clamp(0.0, height as f64).round();
+        let x1 = left.max(right).clamp(0.0, width as f64).round();
+        let y1 = top.max(bottom).clamp(0.0, height as f64).round();
+
+        (x1 - x0 >= 1.0 && y1 - y0 >= 1.0)
+            .then(|| Region::new(x0 as i32, y0 as i32, (x1 - x0) as u32, (y1 - y0) as u32))
+    }
+}
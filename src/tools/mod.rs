@@ -1 +1,39 @@
+mod crop;
 
+pub use crop::{Aspect, CropSelection};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tool {
+    #[default]
+    Select,
+    Text,
+    Bubble,
+    Label,
+    Counter,
+    Pen,
+    Crop,
+}
+
+impl Tool {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Select => "🖱️ Select",
+            Self::Text => "🔤 Text",
+            Self::Bubble => "💬 Bubble",
+            Self::Label => "🏷️ Label",
+            Self::Counter => "① Counter",
+            Self::Pen => "✏️ Pen",
+            Self::Crop => "✂️ Crop",
+        }
+    }
+
+    pub const ALL: [Tool; 7] = [
+        Self::Select,
+        Self::Text,
+        Self::Bubble,
+        Self::Label,
+        Self::Counter,
+        Self::Pen,
+        Self::Crop,
+    ];
+}
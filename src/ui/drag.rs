@@ -0,0 +1,67 @@
+use gtk4::gdk_pixbuf::{InterpType, Pixbuf};
+use gtk4::prelude::*;
+use gtk4::{DragSource, gdk, gio, glib};
+
+const ICON_SIZE: f64 = 128.0;
+
+// Browsers and chat clients mostly accept file drops, so the image is written
+// to a temporary file and offered as a uri-list alongside the raw PNG bytes.
+pub fn attach_image_source(
+    widget: &impl IsA<gtk4::Widget>,
+    pixbuf: impl Fn() -> Option<Pixbuf> + 'static,
+) {
+    let source = DragSource::new();
+    source.set_actions(gdk::DragAction::COPY);
+
+    source.connect_prepare(move |source, _, _| {
+        let pixbuf = pixbuf()?;
+        match content_for(&pixbuf) {
+            Ok(content) => {
+                set_icon(source, &pixbuf);
+                Some(content)
+            }
+            Err(e) => {
+                eprintln!("Failed to start drag: {}", e);
+                None
+            }
+        }
+    });
+
+    widget.add_controller(source);
+}
+
+fn content_for(pixbuf: &Pixbuf) -> Result<gdk::ContentProvider, glib::Error> {
+    let png = pixbuf.save_to_bufferv("png", &[])?;
+
+    let path = std::env::temp_dir().join(format!(
+        "wst-drag-{}-{}.png",
+        std::process::id(),
+        glib::monotonic_time()
+    ));
+    std::fs::write(&path, &png).map_err(|e| {
+        glib::Error::new(
+            gio::IOErrorEnum::Failed,
+            &format!("{}: {}", path.display(), e),
+        )
+    })?;
+    let uri_list = format!("{}\r\n", gio::File::for_path(&path).uri());
+
+    Ok(gdk::ContentProvider::new_union(&[
+        gdk::ContentProvider::for_bytes("text/uri-list", &glib::Bytes::from_owned(uri_list)),
+        gdk::ContentProvider::for_bytes("image/png", &glib::Bytes::from_owned(png)),
+    ]))
+}
+
+fn set_icon(source: &DragSource, pixbuf: &Pixbuf) {
+    let scale = (ICON_SIZE / pixbuf.width().max(pixbuf.height()) as f64).min(1.0);
+    let width = ((pixbuf.width() as f64 * scale) as i32).max(1);
+    let height = ((pixbuf.height() as f64 * scale) as i32).max(1);
+
+    if let Some(icon) = pixbuf.scale_simple(width, height, InterpType::Bilinear) {
+        source.set_icon(
+            Some(&gdk::Texture::for_pixbuf(&icon)),
+            width / 2,
+            height / 2,
+        );
+    }
+}
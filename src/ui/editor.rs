@@ -1,17 +1,140 @@
+use crate::annotation::{Annotation, AnnotationLayer, CalloutShape, default_callout_style};
 use crate::config::Config;
-use gtk4::gdk_pixbuf::Pixbuf;
+use crate::processing::{
+    Adjustments, BeautifyPreset, EditHistory, ImageProcessor, ProcessingStep, TextStyle,
+};
+use crate::tools::{Aspect, CropSelection, Tool};
+use crate::{CaptureMode, OutputFormat, Screenshot};
+use gtk4::gdk_pixbuf::{Colorspace, Pixbuf};
 use gtk4::prelude::*;
-use gtk4::{Application, ApplicationWindow, Box as GtkBox, Button, DrawingArea, Orientation};
-use std::cell::RefCell;
+use gtk4::{
+    Application, ApplicationWindow, Box as GtkBox, Button, DrawingArea, Entry, Orientation,
+    Overlay, ToggleButton,
+};
+use gtk4::{gdk, gio, glib};
+use image::{Rgba, RgbaImage};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+type PendingText = Rc<RefCell<Option<(Entry, (i32, i32))>>>;
+type Stroke = Rc<RefCell<Vec<(f64, f64, f32)>>>;
+
+const PEN_THICKNESS: u32 = 4;
+
+struct Document {
+    history: EditHistory,
+    pixbuf: Pixbuf,
+    presets: BTreeMap<String, BeautifyPreset>,
+    annotations: AnnotationLayer,
+    annotations_path: Option<PathBuf>,
+    adjustments: Adjustments,
+    zoom: f64,
+}
+
+impl Document {
+    fn new(pixbuf: Pixbuf, presets: BTreeMap<String, BeautifyPreset>) -> Self {
+        Self {
+            history: EditHistory::new(screenshot_from_pixbuf(&pixbuf)),
+            pixbuf,
+            presets,
+            annotations: AnnotationLayer::default(),
+            annotations_path: None,
+            adjustments: Adjustments::default(),
+            zoom: 1.0,
+        }
+    }
+
+    fn set_annotations(&mut self, layer: AnnotationLayer, path: PathBuf) -> crate::Result<()> {
+        self.annotations = layer;
+        self.annotations_path = Some(path);
+        self.refresh()
+    }
+
+    fn add_annotation(&mut self, annotation: Annotation) -> crate::Result<()> {
+        self.annotations.push(annotation);
+        if let Err(e) = self.refresh() {
+            self.annotations.annotations.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn save_annotations(&self) -> crate::Result<PathBuf> {
+        let path = self.annotations_path.clone().ok_or_else(|| {
+            crate::ScreenshotError::Config(
+                "This image has no file to store annotations next to".to_string(),
+            )
+        })?;
+        self.annotations.save(&path)?;
+        Ok(path)
+    }
+
+    fn replace(&mut self, pixbuf: Pixbuf) {
+        // The external editor received the rendered image, so annotations are
+        // now baked into its pixels.
+        self.annotations.annotations.clear();
+        self.history = EditHistory::new(screenshot_from_pixbuf(&pixbuf));
+        self.pixbuf = pixbuf;
+    }
+
+    fn apply(&mut self, step: ProcessingStep) -> crate::Result<()> {
+        self.history.push(step);
+        if let Err(e) = self.refresh() {
+            self.history.undo();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self) -> crate::Result<bool> {
+        if !self.history.undo() {
+            return Ok(false);
+        }
+        self.refresh()?;
+        Ok(true)
+    }
+
+    fn redo(&mut self) -> crate::Result<bool> {
+        if !self.history.redo() {
+            return Ok(false);
+        }
+        self.refresh()?;
+        Ok(true)
+    }
+
+    fn preview_adjustments(&mut self, adjustments: Adjustments) -> crate::Result<()> {
+        self.adjustments = adjustments;
+        self.refresh()
+    }
+
+    fn commit_adjustments(&mut self) -> crate::Result<()> {
+        if self.adjustments.is_identity() {
+            return Ok(());
+        }
+
+        let adjustments = std::mem::take(&mut self.adjustments);
+        self.apply(ProcessingStep::Adjust { adjustments })
+    }
+
+    fn refresh(&mut self) -> crate::Result<()> {
+        let screenshot = self.history.render(&self.presets)?;
+        let screenshot = ImageProcessor::adjust(&screenshot, &self.adjustments)?;
+        let screenshot = self.annotations.render(&screenshot)?;
+        self.pixbuf = pixbuf_from_image(screenshot.data);
+        Ok(())
+    }
+}
+
 pub struct EditorWindow {
     window: ApplicationWindow,
-    pixbuf: Rc<RefCell<Pixbuf>>,
+    document: Rc<RefCell<Document>>,
+    drawing_area: DrawingArea,
 }
 
 impl EditorWindow {
-    pub fn new(app: &Application, pixbuf: Pixbuf, _config: Config) -> crate::Result<Self> {
+    pub fn new(app: &Application, pixbuf: Pixbuf, config: Config) -> crate::Result<Self> {
         let window = ApplicationWindow::builder()
             .application(app)
             .title("Screenshot Editor")
@@ -22,28 +145,144 @@ impl EditorWindow {
         let main_box = GtkBox::new(Orientation::Vertical, 0);
         window.set_child(Some(&main_box));
 
-        let pixbuf = Rc::new(RefCell::new(pixbuf));
-
-        let toolbar = Self::create_toolbar();
-        main_box.append(&toolbar);
+        let document = Rc::new(RefCell::new(Document::new(pixbuf, config.presets.clone())));
 
         let drawing_area = DrawingArea::new();
         drawing_area.set_vexpand(true);
         drawing_area.set_hexpand(true);
 
+        let toolbar = Self::create_toolbar(&window, &document, &drawing_area, &config);
+        main_box.append(&toolbar);
+
+        let tool = Rc::new(Cell::new(Tool::default()));
+        let text_style = Rc::new(RefCell::new(TextStyle::default()));
+        let crop = Rc::new(RefCell::new(CropSelection::default()));
+        let stroke: Stroke = Rc::new(RefCell::new(Vec::new()));
+        main_box.append(&Self::create_tool_bar(
+            &tool,
+            &text_style,
+            &crop,
+            &drawing_area,
+        ));
+
         {
-            let pixbuf = pixbuf.clone();
+            let document = document.clone();
+            let tool = tool.clone();
+            let crop = crop.clone();
+            let stroke = stroke.clone();
+            let text_style = text_style.clone();
             drawing_area.set_draw_func(move |_, cr, width, height| {
-                Self::draw_image(cr, &pixbuf.borrow(), width, height);
+                let document = document.borrow();
+                Self::draw_image(cr, &document, width, height);
+                match tool.get() {
+                    Tool::Crop => Self::draw_crop(cr, &document, &crop.borrow(), width, height),
+                    Tool::Pen => Self::draw_stroke(
+                        cr,
+                        &document,
+                        &stroke.borrow(),
+                        &text_style.borrow().color,
+                        width,
+                        height,
+                    ),
+                    _ => {}
+                }
             });
         }
 
-        main_box.append(&drawing_area);
+        let canvas = Overlay::new();
+        canvas.set_child(Some(&drawing_area));
+        main_box.append(&canvas);
+        Self::setup_canvas(&document, &drawing_area, &canvas, &tool, &text_style);
+        Self::setup_crop(&window, &document, &drawing_area, &tool, &crop);
+        Self::setup_pen(&document, &drawing_area, &tool, &text_style, &stroke);
+        Self::setup_zoom(&document, &drawing_area);
+
+        let adjustments = Self::create_adjustments_bar(&document, &drawing_area);
+        main_box.append(&adjustments);
+
+        Self::setup_shortcuts(&window, &document, &drawing_area, &config);
+
+        Ok(Self {
+            window,
+            document,
+            drawing_area,
+        })
+    }
+
+    pub fn open(
+        app: &Application,
+        file: &Path,
+        annotations: Option<PathBuf>,
+        config: Config,
+    ) -> crate::Result<Self> {
+        let pixbuf = Pixbuf::from_file(file)
+            .map_err(|e| crate::ScreenshotError::Gui(format!("Failed to load image: {}", e)))?;
+        super::recent::remember(file);
+        let editor = Self::new(app, pixbuf, config)?;
+
+        let path = annotations.unwrap_or_else(|| AnnotationLayer::sidecar_path(file));
+        let layer = if path.exists() {
+            AnnotationLayer::load(&path)?
+        } else {
+            AnnotationLayer {
+                image: Some(file.to_path_buf()),
+                ..AnnotationLayer::default()
+            }
+        };
+        editor.document.borrow_mut().set_annotations(layer, path)?;
+
+        Ok(editor)
+    }
 
-        Ok(Self { window, pixbuf })
+    pub fn add_annotation(&self, annotation: Annotation) -> crate::Result<()> {
+        self.document.borrow_mut().add_annotation(annotation)?;
+        self.drawing_area.queue_draw();
+        Ok(())
     }
 
-    fn create_toolbar() -> GtkBox {
+    pub fn apply_step(&self, step: ProcessingStep) -> crate::Result<()> {
+        self.document.borrow_mut().apply(step)?;
+        self.drawing_area.queue_draw();
+        Ok(())
+    }
+
+    fn setup_shortcuts(
+        window: &ApplicationWindow,
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+        config: &Config,
+    ) {
+        let key_controller = gtk4::EventControllerKey::new();
+        let document = document.clone();
+        let drawing_area = drawing_area.clone();
+        let shortcuts = config.shortcuts.clone();
+
+        key_controller.connect_key_pressed(move |_, key, _, state| {
+            let result = if matches_shortcut(&shortcuts.undo, key, state) {
+                document.borrow_mut().undo()
+            } else if matches_shortcut(&shortcuts.redo, key, state) {
+                document.borrow_mut().redo()
+            } else {
+                return glib::Propagation::Proceed;
+            };
+
+            match result {
+                Ok(true) => drawing_area.queue_draw(),
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to update image: {}", e),
+            }
+            glib::Propagation::Stop
+        });
+
+        window.add_controller(key_controller);
+    }
+
+    fn create_toolbar(
+        window: &ApplicationWindow,
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+        config: &Config,
+    ) -> GtkBox {
         let toolbar = GtkBox::new(Orientation::Horizontal, 5);
         toolbar.set_margin_start(10);
         toolbar.set_margin_end(10);
@@ -51,29 +290,768 @@ impl EditorWindow {
         toolbar.set_margin_bottom(10);
 
         let save_btn = Button::with_label("💾 Save");
-        let crop_btn = Button::with_label("✂️ Crop");
         let copy_btn = Button::with_label("📋 Copy");
 
         toolbar.append(&save_btn);
-        toolbar.append(&crop_btn);
         toolbar.append(&copy_btn);
 
+        {
+            let window = window.clone();
+            let document = document.clone();
+            let config = config.clone();
+            save_btn.connect_clicked(move |_| {
+                let screenshot = screenshot_from_pixbuf(&document.borrow().pixbuf);
+                super::export::ExportDialog::new(&window, screenshot, &config).show();
+            });
+        }
+
+        let undo_btn = Button::with_label("↶ Undo");
+        let redo_btn = Button::with_label("↷ Redo");
+
+        for (button, undo) in [(&undo_btn, true), (&redo_btn, false)] {
+            let document = document.clone();
+            let drawing_area = drawing_area.clone();
+            button.connect_clicked(move |_| {
+                let result = if undo {
+                    document.borrow_mut().undo()
+                } else {
+                    document.borrow_mut().redo()
+                };
+
+                match result {
+                    Ok(true) => drawing_area.queue_draw(),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("Failed to update image: {}", e),
+                }
+            });
+        }
+
+        toolbar.append(&undo_btn);
+        toolbar.append(&redo_btn);
+
+        let annotations_btn = Button::with_label("🗒️ Save annotations");
+        {
+            let document = document.clone();
+            annotations_btn.connect_clicked(move |_| match document.borrow().save_annotations() {
+                Ok(path) => println!("{}", path.display()),
+                Err(e) => eprintln!("Failed to save annotations: {}", e),
+            });
+        }
+        toolbar.append(&annotations_btn);
+
+        let chrome_btn = Button::with_label("🪟 Window chrome");
+        {
+            let document = document.clone();
+            let drawing_area = drawing_area.clone();
+            let chrome = config.chrome.clone();
+            chrome_btn.connect_clicked(move |_| {
+                let step = ProcessingStep::Chrome {
+                    chrome: chrome.clone(),
+                };
+                match document.borrow_mut().apply(step) {
+                    Ok(()) => drawing_area.queue_draw(),
+                    Err(e) => eprintln!("Failed to add window chrome: {}", e),
+                }
+            });
+        }
+        toolbar.append(&chrome_btn);
+
+        let external_editor = config
+            .external_editor
+            .clone()
+            .unwrap_or_else(|| "gimp".to_string());
+        let open_with_btn = Button::with_label(&format!("🖌️ Open in {}", external_editor));
+        {
+            let document = document.clone();
+            let drawing_area = drawing_area.clone();
+            open_with_btn.connect_clicked(move |button| {
+                let document = document.clone();
+                let drawing_area = drawing_area.clone();
+                let button = button.clone();
+                let external_editor = external_editor.clone();
+
+                button.set_sensitive(false);
+                glib::spawn_future_local(async move {
+                    let current = document.borrow().pixbuf.clone();
+                    match Self::edit_externally(&current, &external_editor).await {
+                        Ok(edited) => {
+                            document.borrow_mut().replace(edited);
+                            drawing_area.queue_draw();
+                        }
+                        Err(e) => eprintln!("Failed to edit in {}: {}", external_editor, e),
+                    }
+                    button.set_sensitive(true);
+                });
+            });
+        }
+        toolbar.append(&open_with_btn);
+
+        let print_btn = Button::with_label("🖨️ Print");
+        {
+            let document = document.clone();
+            print_btn.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk4::Window>();
+                let pixbuf = document.borrow().pixbuf.clone();
+                if let Err(e) = super::print::print_pixbuf(parent.as_ref(), &pixbuf, "Screenshot") {
+                    eprintln!("{}", e);
+                }
+            });
+        }
+        toolbar.append(&print_btn);
+
         toolbar
     }
 
-    fn draw_image(cr: &cairo::Context, pixbuf: &Pixbuf, width: i32, height: i32) {
-        cr.set_source_rgb(1.0, 1.0, 1.0);
-        cr.paint().unwrap();
+    fn create_tool_bar(
+        tool: &Rc<Cell<Tool>>,
+        text_style: &Rc<RefCell<TextStyle>>,
+        crop: &Rc<RefCell<CropSelection>>,
+        drawing_area: &DrawingArea,
+    ) -> GtkBox {
+        let bar = GtkBox::new(Orientation::Horizontal, 5);
+        bar.set_margin_start(10);
+        bar.set_margin_end(10);
+        bar.set_margin_bottom(10);
+
+        let mut group: Option<ToggleButton> = None;
+        for variant in Tool::ALL {
+            let button = ToggleButton::with_label(variant.label());
+            button.set_group(group.as_ref());
+            button.set_active(variant == tool.get());
 
-        let img_width = pixbuf.width() as f64;
-        let img_height = pixbuf.height() as f64;
+            let tool = tool.clone();
+            let area = drawing_area.clone();
+            button.connect_toggled(move |button| {
+                if button.is_active() {
+                    tool.set(variant);
+                    area.queue_draw();
+                }
+            });
 
-        let scale_x = width as f64 / img_width;
-        let scale_y = height as f64 / img_height;
-        let scale = scale_x.min(scale_y) * 0.9;
+            bar.append(&button);
+            group.get_or_insert(button);
+        }
 
-        let offset_x = (width as f64 - img_width * scale) / 2.0;
-        let offset_y = (height as f64 - img_height * scale) / 2.0;
+        bar.append(&gtk4::Separator::new(Orientation::Vertical));
+
+        let font = gtk4::FontButton::with_font("Sans Bold 24");
+        {
+            let text_style = text_style.clone();
+            font.connect_font_set(move |font| {
+                let Some(description) = font.font_desc() else {
+                    return;
+                };
+                let mut style = text_style.borrow_mut();
+                style.family = description.family().map(|family| {
+                    let mut pattern = family.to_string();
+                    if description.weight() >= gtk4::pango::Weight::Bold {
+                        pattern.push_str(":bold");
+                    }
+                    if description.style() != gtk4::pango::Style::Normal {
+                        pattern.push_str(":italic");
+                    }
+                    pattern
+                });
+                if description.size() > 0 {
+                    style.size = description.size() as f32 / gtk4::pango::SCALE as f32;
+                }
+            });
+        }
+        bar.append(&font);
+
+        let color = gtk4::ColorButton::with_rgba(&gdk::RGBA::WHITE);
+        {
+            let text_style = text_style.clone();
+            color.connect_color_set(move |color| {
+                text_style.borrow_mut().color = hex_color(&color.rgba());
+            });
+        }
+        bar.append(&color);
+
+        let outline = gtk4::CheckButton::with_label("Outline");
+        let outline_color = gtk4::ColorButton::with_rgba(&gdk::RGBA::BLACK);
+        {
+            let text_style = text_style.clone();
+            let outline_color = outline_color.clone();
+            outline.connect_toggled(move |outline| {
+                text_style.borrow_mut().outline = outline
+                    .is_active()
+                    .then(|| hex_color(&outline_color.rgba()));
+            });
+        }
+        {
+            let text_style = text_style.clone();
+            let outline = outline.clone();
+            outline_color.connect_color_set(move |color| {
+                if outline.is_active() {
+                    text_style.borrow_mut().outline = Some(hex_color(&color.rgba()));
+                }
+            });
+        }
+        bar.append(&outline);
+        bar.append(&outline_color);
+
+        bar.append(&gtk4::Separator::new(Orientation::Vertical));
+
+        let labels: Vec<&str> = Aspect::ALL.iter().map(|aspect| aspect.label()).collect();
+        let aspect = gtk4::DropDown::from_strings(&labels);
+        {
+            let crop = crop.clone();
+            let area = drawing_area.clone();
+            aspect.connect_selected_notify(move |dropdown| {
+                if let Some(&selected) = Aspect::ALL.get(dropdown.selected() as usize) {
+                    let mut crop = crop.borrow_mut();
+                    crop.aspect = selected;
+                    crop.clear();
+                    area.queue_draw();
+                }
+            });
+        }
+        bar.append(&aspect);
+
+        bar
+    }
+
+    fn setup_canvas(
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+        canvas: &Overlay,
+        tool: &Rc<Cell<Tool>>,
+        text_style: &Rc<RefCell<TextStyle>>,
+    ) {
+        {
+            let document = document.clone();
+            let tool = tool.clone();
+            super::drag::attach_image_source(drawing_area, move || {
+                (tool.get() == Tool::Select).then(|| document.borrow().pixbuf.clone())
+            });
+        }
+
+        let pending: PendingText = Rc::new(RefCell::new(None));
+        let click = gtk4::GestureClick::new();
+
+        let document = document.clone();
+        let area = drawing_area.clone();
+        let canvas = canvas.clone();
+        let tool = tool.clone();
+        let text_style = text_style.clone();
+        click.connect_pressed(move |_, _, x, y| {
+            let position = Self::to_image(&document.borrow(), &area, x, y);
+
+            match tool.get() {
+                Tool::Text => {
+                    let text_style = text_style.clone();
+                    Self::place_text(
+                        &document,
+                        &area,
+                        &canvas,
+                        &pending,
+                        (x, y),
+                        position,
+                        move |(x, y), text| Annotation::Text {
+                            x,
+                            y,
+                            text,
+                            style: text_style.borrow().clone(),
+                        },
+                    )
+                }
+                Tool::Bubble | Tool::Label => {
+                    let shape = match tool.get() {
+                        Tool::Bubble => CalloutShape::Bubble,
+                        _ => CalloutShape::Label,
+                    };
+                    // The first click is the point being called out and the
+                    // box opens beside it; further clicks move the box.
+                    let (box_x, box_y) = if pending.borrow().is_some() {
+                        (x, y)
+                    } else {
+                        (x + 24.0, (y - 64.0).max(0.0))
+                    };
+                    let text_style = text_style.clone();
+                    Self::place_text(
+                        &document,
+                        &area,
+                        &canvas,
+                        &pending,
+                        (box_x, box_y),
+                        Self::to_image(&document.borrow(), &area, box_x, box_y),
+                        move |(x, y), text| Annotation::Callout {
+                            x,
+                            y,
+                            anchor: position,
+                            text,
+                            shape,
+                            fill: "#ffffff".to_string(),
+                            color: "#ff3b30".to_string(),
+                            thickness: 3,
+                            style: TextStyle {
+                                font: text_style.borrow().font.clone(),
+                                family: text_style.borrow().family.clone(),
+                                size: text_style.borrow().size,
+                                ..default_callout_style()
+                            },
+                        },
+                    )
+                }
+                Tool::Counter => {
+                    let mut document = document.borrow_mut();
+                    let annotation = Annotation::StepMarker {
+                        x: position.0,
+                        y: position.1,
+                        number: document.annotations.next_step_number(),
+                        color: "#ff3b30".to_string(),
+                        radius: 16,
+                    };
+                    match document.add_annotation(annotation) {
+                        Ok(()) => area.queue_draw(),
+                        Err(e) => eprintln!("Failed to add step marker: {}", e),
+                    }
+                }
+                Tool::Select | Tool::Pen | Tool::Crop => {}
+            }
+        });
+
+        drawing_area.add_controller(click);
+    }
+
+    fn setup_crop(
+        window: &ApplicationWindow,
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+        tool: &Rc<Cell<Tool>>,
+        crop: &Rc<RefCell<CropSelection>>,
+    ) {
+        let drag = gtk4::GestureDrag::new();
+        let start = Rc::new(Cell::new((0.0, 0.0)));
+
+        {
+            let document = document.clone();
+            let area = drawing_area.clone();
+            let tool = tool.clone();
+            let crop = crop.clone();
+            let start = start.clone();
+            drag.connect_drag_begin(move |_, x, y| {
+                if tool.get() != Tool::Crop {
+                    return;
+                }
+                start.set((x, y));
+
+                let (scale, offset_x, offset_y) = Self::view_transform(
+                    &document.borrow(),
+                    area.width() as f64,
+                    area.height() as f64,
+                );
+                crop.borrow_mut().begin(
+                    (x - offset_x) / scale,
+                    (y - offset_y) / scale,
+                    8.0 / scale,
+                );
+                area.queue_draw();
+            });
+        }
+        {
+            let document = document.clone();
+            let area = drawing_area.clone();
+            let tool = tool.clone();
+            let crop = crop.clone();
+            drag.connect_drag_update(move |_, dx, dy| {
+                if tool.get() != Tool::Crop {
+                    return;
+                }
+                let (x, y) = start.get();
+
+                let (scale, offset_x, offset_y) = Self::view_transform(
+                    &document.borrow(),
+                    area.width() as f64,
+                    area.height() as f64,
+                );
+                crop.borrow_mut()
+                    .update((x + dx - offset_x) / scale, (y + dy - offset_y) / scale);
+                area.queue_draw();
+            });
+        }
+        {
+            let crop = crop.clone();
+            drag.connect_drag_end(move |_, _, _| {
+                crop.borrow_mut().end();
+            });
+        }
+        drawing_area.add_controller(drag);
+
+        let keys = gtk4::EventControllerKey::new();
+        let document = document.clone();
+        let area = drawing_area.clone();
+        let tool = tool.clone();
+        let crop = crop.clone();
+        keys.connect_key_pressed(move |_, key, _, _| {
+            if tool.get() != Tool::Crop {
+                return glib::Propagation::Proceed;
+            }
+
+            match key {
+                gdk::Key::Return | gdk::Key::KP_Enter => {
+                    let region = {
+                        let pixbuf = &document.borrow().pixbuf;
+                        crop.borrow()
+                            .region(pixbuf.width() as u32, pixbuf.height() as u32)
+                    };
+                    let Some(region) = region else {
+                        return glib::Propagation::Proceed;
+                    };
+
+                    let step = ProcessingStep::Crop {
+                        x: region.x as u32,
+                        y: region.y as u32,
+                        width: region.width,
+                        height: region.height,
+                    };
+                    if let Err(e) = document.borrow_mut().apply(step) {
+                        eprintln!("Failed to crop image: {}", e);
+                    }
+                }
+                gdk::Key::Escape => {}
+                _ => return glib::Propagation::Proceed,
+            }
+
+            crop.borrow_mut().clear();
+            area.queue_draw();
+            glib::Propagation::Stop
+        });
+
+        window.add_controller(keys);
+    }
+
+    fn setup_pen(
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+        tool: &Rc<Cell<Tool>>,
+        text_style: &Rc<RefCell<TextStyle>>,
+        stroke: &Stroke,
+    ) {
+        let drag = gtk4::GestureDrag::new();
+
+        let add_point = {
+            let document = document.clone();
+            let area = drawing_area.clone();
+            let stroke = stroke.clone();
+            move |gesture: &gtk4::GestureDrag, x: f64, y: f64| {
+                // Mice and touchscreens report no pressure; treat them as a
+                // medium press so strokes keep the nominal thickness.
+                let pressure = gesture
+                    .current_event()
+                    .and_then(|event| event.axis(gdk::AxisUse::Pressure))
+                    .map_or(0.5, |pressure| pressure.clamp(0.05, 1.0));
+
+                let (scale, offset_x, offset_y) = Self::view_transform(
+                    &document.borrow(),
+                    area.width() as f64,
+                    area.height() as f64,
+                );
+                stroke.borrow_mut().push((
+                    (x - offset_x) / scale,
+                    (y - offset_y) / scale,
+                    pressure as f32,
+                ));
+                area.queue_draw();
+            }
+        };
+
+        {
+            let tool = tool.clone();
+            let stroke = stroke.clone();
+            let add_point = add_point.clone();
+            drag.connect_drag_begin(move |gesture, x, y| {
+                if tool.get() != Tool::Pen {
+                    return;
+                }
+                stroke.borrow_mut().clear();
+                add_point(gesture, x, y);
+            });
+        }
+        {
+            let tool = tool.clone();
+            drag.connect_drag_update(move |gesture, dx, dy| {
+                if tool.get() != Tool::Pen {
+                    return;
+                }
+                if let Some((x, y)) = gesture.start_point() {
+                    add_point(gesture, x + dx, y + dy);
+                }
+            });
+        }
+        {
+            let document = document.clone();
+            let area = drawing_area.clone();
+            let tool = tool.clone();
+            let text_style = text_style.clone();
+            let stroke = stroke.clone();
+            drag.connect_drag_end(move |_, _, _| {
+                let points = std::mem::take(&mut *stroke.borrow_mut());
+                if tool.get() != Tool::Pen || points.is_empty() {
+                    return;
+                }
+
+                let annotation = Annotation::Freehand {
+                    points: points
+                        .into_iter()
+                        .map(|(x, y, pressure)| (x.round() as i32, y.round() as i32, pressure))
+                        .collect(),
+                    color: text_style.borrow().color.clone(),
+                    thickness: PEN_THICKNESS,
+                };
+                if let Err(e) = document.borrow_mut().add_annotation(annotation) {
+                    eprintln!("Failed to add drawing: {}", e);
+                }
+                area.queue_draw();
+            });
+        }
+
+        drawing_area.add_controller(drag);
+    }
+
+    fn setup_zoom(document: &Rc<RefCell<Document>>, drawing_area: &DrawingArea) {
+        let zoom = gtk4::GestureZoom::new();
+        let start = Rc::new(Cell::new(1.0));
+
+        {
+            let document = document.clone();
+            let start = start.clone();
+            zoom.connect_begin(move |_, _| start.set(document.borrow().zoom));
+        }
+
+        let document = document.clone();
+        let area = drawing_area.clone();
+        zoom.connect_scale_changed(move |_, scale| {
+            document.borrow_mut().zoom = (start.get() * scale).clamp(0.25, 8.0);
+            area.queue_draw();
+        });
+
+        drawing_area.add_controller(zoom);
+    }
+
+    // Clicking again while text is pending moves it; Enter bakes it in as an
+    // annotation and Escape drops it.
+    fn place_text(
+        document: &Rc<RefCell<Document>>,
+        area: &DrawingArea,
+        canvas: &Overlay,
+        pending: &PendingText,
+        (x, y): (f64, f64),
+        position: (i32, i32),
+        build: impl Fn((i32, i32), String) -> Annotation + 'static,
+    ) {
+        if let Some((entry, current)) = pending.borrow_mut().as_mut() {
+            entry.set_margin_start(x as i32);
+            entry.set_margin_top(y as i32);
+            *current = position;
+            return;
+        }
+
+        let entry = Entry::new();
+        entry.set_placeholder_text(Some("Type, then press Enter"));
+        entry.set_halign(gtk4::Align::Start);
+        entry.set_valign(gtk4::Align::Start);
+        entry.set_margin_start(x as i32);
+        entry.set_margin_top(y as i32);
+        canvas.add_overlay(&entry);
+        entry.grab_focus();
+
+        {
+            let document = document.clone();
+            let area = area.clone();
+            let canvas = canvas.clone();
+            let pending = pending.clone();
+            entry.connect_activate(move |entry| {
+                let Some((_, (x, y))) = pending.borrow_mut().take() else {
+                    return;
+                };
+                canvas.remove_overlay(entry);
+
+                let text = entry.text().to_string();
+                if text.is_empty() {
+                    return;
+                }
+
+                match document.borrow_mut().add_annotation(build((x, y), text)) {
+                    Ok(()) => area.queue_draw(),
+                    Err(e) => eprintln!("Failed to add text: {}", e),
+                }
+            });
+        }
+
+        let keys = gtk4::EventControllerKey::new();
+        {
+            let canvas = canvas.clone();
+            let pending = pending.clone();
+            keys.connect_key_pressed(move |_, key, _, _| {
+                if key != gdk::Key::Escape {
+                    return glib::Propagation::Proceed;
+                }
+                if let Some((entry, _)) = pending.borrow_mut().take() {
+                    canvas.remove_overlay(&entry);
+                }
+                glib::Propagation::Stop
+            });
+        }
+        entry.add_controller(keys);
+
+        pending.replace(Some((entry, position)));
+    }
+
+    fn view_transform(document: &Document, width: f64, height: f64) -> (f64, f64, f64) {
+        let img_width = document.pixbuf.width() as f64;
+        let img_height = document.pixbuf.height() as f64;
+
+        let scale = (width / img_width).min(height / img_height) * 0.9 * document.zoom;
+        let offset_x = (width - img_width * scale) / 2.0;
+        let offset_y = (height - img_height * scale) / 2.0;
+
+        (scale, offset_x, offset_y)
+    }
+
+    fn to_image(document: &Document, area: &DrawingArea, x: f64, y: f64) -> (i32, i32) {
+        let (scale, offset_x, offset_y) =
+            Self::view_transform(document, area.width() as f64, area.height() as f64);
+
+        (
+            ((x - offset_x) / scale).round() as i32,
+            ((y - offset_y) / scale).round() as i32,
+        )
+    }
+
+    fn create_adjustments_bar(
+        document: &Rc<RefCell<Document>>,
+        drawing_area: &DrawingArea,
+    ) -> GtkBox {
+        let bar = GtkBox::new(Orientation::Horizontal, 5);
+        bar.set_margin_start(10);
+        bar.set_margin_end(10);
+        bar.set_margin_bottom(10);
+
+        let defaults = Adjustments::default();
+        let sliders: Rc<Vec<gtk4::Scale>> = Rc::new(
+            [
+                ("Brightness", -1.0, 1.0, defaults.brightness),
+                ("Contrast", 0.0, 3.0, defaults.contrast),
+                ("Saturation", 0.0, 3.0, defaults.saturation),
+                ("Gamma", 0.1, 3.0, defaults.gamma),
+            ]
+            .into_iter()
+            .map(|(label, min, max, value)| {
+                bar.append(&gtk4::Label::new(Some(label)));
+                let scale = gtk4::Scale::with_range(Orientation::Horizontal, min, max, 0.05);
+                scale.set_value(value as f64);
+                scale.set_hexpand(true);
+                bar.append(&scale);
+                scale
+            })
+            .collect(),
+        );
+
+        let current = {
+            let sliders = sliders.clone();
+            move || Adjustments {
+                brightness: sliders[0].value() as f32,
+                contrast: sliders[1].value() as f32,
+                saturation: sliders[2].value() as f32,
+                gamma: sliders[3].value() as f32,
+            }
+        };
+
+        let resetting = Rc::new(Cell::new(false));
+
+        let preview = {
+            let document = document.clone();
+            let drawing_area = drawing_area.clone();
+            move |adjustments| match document.borrow_mut().preview_adjustments(adjustments) {
+                Ok(()) => drawing_area.queue_draw(),
+                Err(e) => eprintln!("Failed to adjust image: {}", e),
+            }
+        };
+
+        for slider in sliders.iter() {
+            let resetting = resetting.clone();
+            let preview = preview.clone();
+            let current = current.clone();
+            slider.connect_value_changed(move |_| {
+                if !resetting.get() {
+                    preview(current());
+                }
+            });
+        }
+
+        let reset = {
+            let sliders = sliders.clone();
+            move || {
+                resetting.set(true);
+                for (slider, value) in sliders.iter().zip([
+                    defaults.brightness,
+                    defaults.contrast,
+                    defaults.saturation,
+                    defaults.gamma,
+                ]) {
+                    slider.set_value(value as f64);
+                }
+                resetting.set(false);
+                preview(defaults);
+            }
+        };
+
+        let apply_btn = Button::with_label("Apply");
+        {
+            let document = document.clone();
+            let reset = reset.clone();
+            apply_btn.connect_clicked(move |_| {
+                if let Err(e) = document.borrow_mut().commit_adjustments() {
+                    eprintln!("Failed to adjust image: {}", e);
+                }
+                reset();
+            });
+        }
+
+        let reset_btn = Button::with_label("Reset");
+        reset_btn.connect_clicked(move |_| reset());
+
+        bar.append(&apply_btn);
+        bar.append(&reset_btn);
+
+        bar
+    }
+
+    async fn edit_externally(pixbuf: &Pixbuf, command: &str) -> Result<Pixbuf, glib::Error> {
+        let path = std::env::temp_dir().join(format!(
+            "wst-edit-{}-{}.png",
+            std::process::id(),
+            glib::monotonic_time()
+        ));
+        pixbuf.savev(&path, "png", &[])?;
+
+        let mut argv: Vec<&std::ffi::OsStr> = command
+            .split_whitespace()
+            .map(std::ffi::OsStr::new)
+            .collect();
+        argv.push(path.as_os_str());
+
+        let result = match gio::Subprocess::newv(&argv, gio::SubprocessFlags::NONE) {
+            Ok(process) => match process.wait_check_future().await {
+                Ok(()) => Pixbuf::from_file(&path),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    fn draw_image(cr: &cairo::Context, document: &Document, width: i32, height: i32) {
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        cr.paint().unwrap();
+
+        let pixbuf = &document.pixbuf;
+        let (scale, offset_x, offset_y) =
+            Self::view_transform(document, width as f64, height as f64);
 
         cr.save().unwrap();
         cr.translate(offset_x, offset_y);
@@ -83,7 +1061,168 @@ impl EditorWindow {
         cr.restore().unwrap();
     }
 
+    fn draw_crop(
+        cr: &cairo::Context,
+        document: &Document,
+        crop: &CropSelection,
+        width: i32,
+        height: i32,
+    ) {
+        let pixbuf = &document.pixbuf;
+        let Some(region) = crop.region(pixbuf.width() as u32, pixbuf.height() as u32) else {
+            return;
+        };
+
+        let (scale, offset_x, offset_y) =
+            Self::view_transform(document, width as f64, height as f64);
+        let x = offset_x + region.x as f64 * scale;
+        let y = offset_y + region.y as f64 * scale;
+        let w = region.width as f64 * scale;
+        let h = region.height as f64 * scale;
+
+        cr.save().unwrap();
+
+        cr.set_fill_rule(cairo::FillRule::EvenOdd);
+        cr.rectangle(
+            offset_x,
+            offset_y,
+            pixbuf.width() as f64 * scale,
+            pixbuf.height() as f64 * scale,
+        );
+        cr.rectangle(x, y, w, h);
+        cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+        cr.fill().unwrap();
+
+        cr.set_source_rgba(1.0, 1.0, 1.0, 0.5);
+        cr.set_line_width(1.0);
+        for third in [1.0, 2.0] {
+            cr.move_to(x + w * third / 3.0, y);
+            cr.line_to(x + w * third / 3.0, y + h);
+            cr.move_to(x, y + h * third / 3.0);
+            cr.line_to(x + w, y + h * third / 3.0);
+        }
+        cr.stroke().unwrap();
+
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        cr.set_line_width(2.0);
+        cr.rectangle(x, y, w, h);
+        cr.stroke().unwrap();
+
+        let handle = 8.0;
+        for (hx, hy) in [
+            (x, y + h / 2.0),
+            (x + w, y + h / 2.0),
+            (x + w / 2.0, y),
+            (x + w / 2.0, y + h),
+            (x, y),
+            (x + w, y),
+            (x, y + h),
+            (x + w, y + h),
+        ] {
+            cr.rectangle(hx - handle / 2.0, hy - handle / 2.0, handle, handle);
+        }
+        cr.fill().unwrap();
+
+        cr.restore().unwrap();
+    }
+
+    fn draw_stroke(
+        cr: &cairo::Context,
+        document: &Document,
+        stroke: &[(f64, f64, f32)],
+        color: &str,
+        width: i32,
+        height: i32,
+    ) {
+        let (scale, offset_x, offset_y) =
+            Self::view_transform(document, width as f64, height as f64);
+        let color = gdk::RGBA::parse(color).unwrap_or(gdk::RGBA::RED);
+
+        cr.save().unwrap();
+        cr.set_source_rgba(
+            color.red() as f64,
+            color.green() as f64,
+            color.blue() as f64,
+            color.alpha() as f64,
+        );
+        cr.set_line_cap(cairo::LineCap::Round);
+
+        for pair in stroke.windows(2) {
+            let ((x0, y0, p0), (x1, y1, p1)) = (pair[0], pair[1]);
+            cr.set_line_width(PEN_THICKNESS as f64 * (p0 + p1) as f64 * scale);
+            cr.move_to(offset_x + x0 * scale, offset_y + y0 * scale);
+            cr.line_to(offset_x + x1 * scale, offset_y + y1 * scale);
+            cr.stroke().unwrap();
+        }
+
+        cr.restore().unwrap();
+    }
+
     pub fn show(&self) {
         self.window.present();
     }
 }
+
+fn hex_color(rgba: &gdk::RGBA) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel(rgba.red()),
+        channel(rgba.green()),
+        channel(rgba.blue()),
+        channel(rgba.alpha())
+    )
+}
+
+fn matches_shortcut(shortcut: &str, key: gdk::Key, state: gdk::ModifierType) -> bool {
+    let mut required = gdk::ModifierType::empty();
+    let mut expected_key = None;
+
+    for part in shortcut.split('+') {
+        match part.trim().to_lowercase().as_str() {
+            "ctrl" | "control" => required |= gdk::ModifierType::CONTROL_MASK,
+            "shift" => required |= gdk::ModifierType::SHIFT_MASK,
+            "alt" => required |= gdk::ModifierType::ALT_MASK,
+            "super" => required |= gdk::ModifierType::SUPER_MASK,
+            _ => expected_key = gdk::Key::from_name(part.trim()),
+        }
+    }
+
+    let modifiers = gdk::ModifierType::CONTROL_MASK
+        | gdk::ModifierType::SHIFT_MASK
+        | gdk::ModifierType::ALT_MASK
+        | gdk::ModifierType::SUPER_MASK;
+
+    expected_key.is_some_and(|expected| expected.to_lower() == key.to_lower())
+        && state & modifiers == required
+}
+
+pub(crate) fn screenshot_from_pixbuf(pixbuf: &Pixbuf) -> Screenshot {
+    let width = pixbuf.width() as u32;
+    let height = pixbuf.height() as u32;
+    let channels = pixbuf.n_channels() as usize;
+    let stride = pixbuf.rowstride() as usize;
+    let bytes = pixbuf.read_pixel_bytes();
+
+    let data = RgbaImage::from_fn(width, height, |x, y| {
+        let i = y as usize * stride + x as usize * channels;
+        let alpha = if channels == 4 { bytes[i + 3] } else { 255 };
+        Rgba([bytes[i], bytes[i + 1], bytes[i + 2], alpha])
+    });
+
+    Screenshot::new(data, CaptureMode::Screen, OutputFormat::Png)
+}
+
+pub(crate) fn pixbuf_from_image(image: RgbaImage) -> Pixbuf {
+    let (width, height) = image.dimensions();
+
+    Pixbuf::from_bytes(
+        &glib::Bytes::from_owned(image.into_raw()),
+        Colorspace::Rgb,
+        true,
+        8,
+        width as i32,
+        height as i32,
+        width as i32 * 4,
+    )
+}
@@ -0,0 +1,276 @@
+use crate::config::Config;
+use crate::export::{ExportOptions, Exporter};
+use crate::processing::ImageProcessor;
+use crate::{OutputFormat, Screenshot};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, CheckButton, DropDown, Entry,
+    FileChooserAction, FileChooserDialog, Label, Orientation, ResponseType, Scale, SpinButton,
+    Window,
+};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+const FORMATS: [&str; 4] = ["png", "jpg", "webp", "pdf"];
+
+struct Controls {
+    screenshot: Screenshot,
+    format: DropDown,
+    quality: Scale,
+    scale: SpinButton,
+    copy: CheckButton,
+    path: Entry,
+    estimate: Label,
+    clipboard: crate::config::ClipboardConfig,
+}
+
+impl Controls {
+    fn format(&self) -> OutputFormat {
+        FORMATS
+            .get(self.format.selected() as usize)
+            .and_then(|f| f.parse().ok())
+            .unwrap_or(OutputFormat::Png)
+    }
+
+    fn options(&self) -> ExportOptions {
+        ExportOptions {
+            quality: Some(self.quality.value().round() as u8),
+            ..ExportOptions::default()
+        }
+    }
+
+    fn scaled(&self) -> crate::Result<Screenshot> {
+        let percent = self.scale.value_as_int().clamp(1, 100) as u32;
+        if percent == 100 {
+            return Ok(self.screenshot.clone());
+        }
+
+        let width = (self.screenshot.data.width() * percent / 100).max(1);
+        let height = (self.screenshot.data.height() * percent / 100).max(1);
+        ImageProcessor::resize(&self.screenshot, width, height)
+    }
+
+    fn refresh(&self) {
+        let format = self.format();
+        let copy = self.copy.is_active();
+        self.quality
+            .set_sensitive(format == OutputFormat::Jpeg && !copy);
+        self.format.set_sensitive(!copy);
+        self.path.set_sensitive(!copy);
+
+        let path = PathBuf::from(self.path.text().as_str());
+        if !copy && OutputFormat::from_path(&path) != Some(format) {
+            self.path.set_text(
+                &path
+                    .with_extension(format.extension())
+                    .display()
+                    .to_string(),
+            );
+        }
+
+        let estimate = self.scaled().and_then(|screenshot| {
+            let format = if copy { OutputFormat::Png } else { format };
+            let bytes = Exporter::encode(&screenshot, format, &self.options())?;
+            Ok((screenshot.data.dimensions(), bytes.len()))
+        });
+
+        match estimate {
+            Ok(((width, height), bytes)) => self.estimate.set_text(&format!(
+                "{}×{} · about {}",
+                width,
+                height,
+                human_size(bytes as u64)
+            )),
+            Err(e) => self.estimate.set_text(&format!("Cannot encode: {}", e)),
+        }
+    }
+
+    fn export(&self) -> crate::Result<()> {
+        let screenshot = self.scaled()?;
+
+        if self.copy.is_active() {
+            return Exporter::copy_to_clipboard_with(&screenshot, &self.clipboard);
+        }
+
+        let path = PathBuf::from(self.path.text().as_str());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Exporter::save_with_options(&screenshot, &path, self.format(), &self.options())?;
+        println!("{}", path.display());
+        Ok(())
+    }
+}
+
+pub struct ExportDialog {
+    window: Window,
+}
+
+impl ExportDialog {
+    pub fn new(parent: &ApplicationWindow, screenshot: Screenshot, config: &Config) -> Self {
+        let window = Window::builder()
+            .transient_for(parent)
+            .modal(true)
+            .title("Export")
+            .default_width(420)
+            .build();
+
+        let main_box = GtkBox::new(Orientation::Vertical, 12);
+        main_box.set_margin_start(16);
+        main_box.set_margin_end(16);
+        main_box.set_margin_top(16);
+        main_box.set_margin_bottom(16);
+        window.set_child(Some(&main_box));
+
+        let selected = FORMATS
+            .iter()
+            .position(|f| f.parse::<OutputFormat>().ok() == Some(config.default_format))
+            .unwrap_or(0);
+        let format = DropDown::from_strings(&FORMATS);
+        format.set_selected(selected as u32);
+        main_box.append(&row("Format", &format));
+
+        let quality = Scale::with_range(Orientation::Horizontal, 1.0, 100.0, 1.0);
+        quality.set_value(config.jpeg_quality as f64);
+        quality.set_digits(0);
+        quality.set_draw_value(true);
+        quality.set_hexpand(true);
+        main_box.append(&row("Quality", &quality));
+
+        let scale = SpinButton::with_range(10.0, 100.0, 5.0);
+        scale.set_value(100.0);
+        main_box.append(&row("Scale (%)", &scale));
+
+        let path = Entry::new();
+        path.set_hexpand(true);
+        let filename = format!("{}.{}", config.generate_filename(), FORMATS[selected]);
+        path.set_text(&config.save_directory.join(filename).display().to_string());
+        let browse_btn = Button::with_label("Browse…");
+        let path_box = GtkBox::new(Orientation::Horizontal, 5);
+        path_box.append(&path);
+        path_box.append(&browse_btn);
+        main_box.append(&row("Save to", &path_box));
+
+        let copy = CheckButton::with_label("Copy to the clipboard instead of saving");
+        main_box.append(&copy);
+
+        let estimate = Label::new(None);
+        estimate.set_xalign(0.0);
+        estimate.add_css_class("dim-label");
+        main_box.append(&estimate);
+
+        let buttons = GtkBox::new(Orientation::Horizontal, 5);
+        buttons.set_halign(Align::End);
+        let cancel_btn = Button::with_label("Cancel");
+        let export_btn = Button::with_label("Export");
+        buttons.append(&cancel_btn);
+        buttons.append(&export_btn);
+        main_box.append(&buttons);
+
+        let controls = Rc::new(Controls {
+            screenshot,
+            format,
+            quality,
+            scale,
+            copy,
+            path,
+            estimate,
+            clipboard: config.clipboard.clone(),
+        });
+        controls.refresh();
+
+        {
+            let refresh = controls.clone();
+            controls
+                .format
+                .connect_selected_notify(move |_| refresh.refresh());
+        }
+        {
+            let refresh = controls.clone();
+            controls
+                .quality
+                .connect_value_changed(move |_| refresh.refresh());
+        }
+        {
+            let refresh = controls.clone();
+            controls
+                .scale
+                .connect_value_changed(move |_| refresh.refresh());
+        }
+        {
+            let refresh = controls.clone();
+            controls.copy.connect_toggled(move |_| refresh.refresh());
+        }
+
+        {
+            let window = window.clone();
+            let controls = controls.clone();
+            browse_btn.connect_clicked(move |_| {
+                let dialog = FileChooserDialog::new(
+                    Some("Export as"),
+                    Some(&window),
+                    FileChooserAction::Save,
+                    &[
+                        ("Cancel", ResponseType::Cancel),
+                        ("Select", ResponseType::Accept),
+                    ],
+                );
+                dialog.set_modal(true);
+
+                let current = PathBuf::from(controls.path.text().as_str());
+                if let Some(name) = current.file_name() {
+                    dialog.set_current_name(&name.to_string_lossy());
+                }
+
+                let controls = controls.clone();
+                dialog.connect_response(move |dialog, response| {
+                    if response == ResponseType::Accept
+                        && let Some(path) = dialog.file().and_then(|file| file.path())
+                    {
+                        controls.path.set_text(&path.display().to_string());
+                        controls.refresh();
+                    }
+                    dialog.close();
+                });
+                dialog.present();
+            });
+        }
+
+        {
+            let window = window.clone();
+            cancel_btn.connect_clicked(move |_| window.close());
+        }
+
+        {
+            let window = window.clone();
+            export_btn.connect_clicked(move |_| match controls.export() {
+                Ok(()) => window.close(),
+                Err(e) => controls.estimate.set_text(&format!("Export failed: {}", e)),
+            });
+        }
+
+        Self { window }
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
+
+fn row(label: &str, widget: &impl IsA<gtk4::Widget>) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+    let label = Label::new(Some(label));
+    label.set_xalign(0.0);
+    label.set_hexpand(true);
+    row.append(&label);
+    row.append(widget);
+    row
+}
+
+fn human_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
@@ -1,33 +1,58 @@
 #[cfg(feature = "gui")]
+pub mod drag;
+#[cfg(feature = "gui")]
 pub mod editor;
 #[cfg(feature = "gui")]
+pub mod export;
+#[cfg(feature = "gui")]
 pub mod overlay;
 #[cfg(feature = "gui")]
+pub mod preferences;
+#[cfg(feature = "gui")]
+pub mod preview;
+#[cfg(feature = "gui")]
+pub mod print;
+#[cfg(feature = "gui")]
+pub mod recent;
+#[cfg(feature = "gui")]
+pub mod setup;
+#[cfg(feature = "gui")]
+pub mod theme;
+#[cfg(feature = "gui")]
 pub mod widgets;
 
 #[cfg(feature = "gui")]
 pub use editor::EditorWindow;
 #[cfg(feature = "gui")]
 pub use overlay::SelectionOverlay;
+#[cfg(feature = "gui")]
+pub use preferences::PreferencesWindow;
+#[cfg(feature = "gui")]
+pub use preview::PreviewWindow;
+#[cfg(feature = "gui")]
+pub use recent::StartScreen;
+#[cfg(feature = "gui")]
+pub use setup::SetupWindow;
 
 #[cfg(feature = "gui")]
-use crate::{Result, config::Config};
+use crate::{Result, Screenshot, config::Config};
 #[cfg(feature = "gui")]
 use std::path::PathBuf;
 
 #[cfg(feature = "gui")]
-pub async fn launch_gui(config: Config) -> Result<()> {
+pub async fn launch_gui(config: Config, mode: crate::CaptureMode) -> Result<()> {
     use gtk4::prelude::*;
 
     gtk4::init()
         .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+    let config = theme::resolve(config).await;
 
     let app = gtk4::Application::builder()
         .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.editor")
         .build();
 
     app.connect_activate(move |app| {
-        let overlay = SelectionOverlay::new(app, config.clone());
+        let overlay = SelectionOverlay::new(app, config.clone(), mode);
         overlay.show();
     });
 
@@ -36,20 +61,28 @@ pub async fn launch_gui(config: Config) -> Result<()> {
 }
 
 #[cfg(feature = "gui")]
-pub async fn launch_editor(file: PathBuf, config: Config) -> Result<()> {
+pub async fn launch_editor(
+    file: Option<PathBuf>,
+    annotations: Option<PathBuf>,
+    config: Config,
+) -> Result<()> {
     use gtk4::prelude::*;
 
     gtk4::init()
         .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+    let config = theme::resolve(config).await;
 
     let app = gtk4::Application::builder()
         .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.editor")
         .build();
 
     app.connect_activate(move |app| {
-        let pixbuf = gtk4::gdk_pixbuf::Pixbuf::from_file(&file).expect("Failed to load pixbuf");
+        let Some(file) = &file else {
+            StartScreen::new(app, config.clone()).show();
+            return;
+        };
 
-        match EditorWindow::new(app, pixbuf, config.clone()) {
+        match EditorWindow::open(app, file, annotations.clone(), config.clone()) {
             Ok(editor) => editor.show(),
             Err(e) => eprintln!("Failed to create editor: {}", e),
         }
@@ -58,3 +91,93 @@ pub async fn launch_editor(file: PathBuf, config: Config) -> Result<()> {
     app.run();
     Ok(())
 }
+
+#[cfg(feature = "gui")]
+pub async fn launch_setup(config: Config) -> Result<()> {
+    use gtk4::prelude::*;
+
+    gtk4::init()
+        .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+    let config = theme::resolve(config).await;
+
+    let app = gtk4::Application::builder()
+        .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.setup")
+        .build();
+
+    app.connect_activate(move |app| {
+        SetupWindow::new(app, config.clone()).show();
+    });
+
+    app.run_with_args::<&str>(&[]);
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+pub async fn launch_preferences(config: Config) -> Result<()> {
+    use gtk4::prelude::*;
+
+    gtk4::init()
+        .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+    let config = theme::resolve(config).await;
+
+    let app = gtk4::Application::builder()
+        .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.preferences")
+        .build();
+
+    app.connect_activate(move |app| {
+        PreferencesWindow::new(app, config.clone()).show();
+    });
+
+    app.run_with_args::<&str>(&[]);
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+pub async fn launch_print(file: PathBuf) -> Result<()> {
+    use gtk4::prelude::*;
+
+    gtk4::init()
+        .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+
+    let pixbuf = gtk4::gdk_pixbuf::Pixbuf::from_file(&file)
+        .map_err(|e| crate::error::ScreenshotError::Gui(format!("Failed to load image: {}", e)))?;
+    let job_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Screenshot".to_string());
+
+    let app = gtk4::Application::builder()
+        .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.print")
+        .build();
+
+    app.connect_activate(move |app| {
+        let _hold = app.hold();
+        if let Err(e) = print::print_pixbuf(None, &pixbuf, &job_name) {
+            eprintln!("{}", e);
+        }
+    });
+
+    app.run_with_args::<&str>(&[]);
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+pub async fn launch_preview(screenshot: Screenshot, output: PathBuf, config: Config) -> Result<()> {
+    use gtk4::prelude::*;
+
+    gtk4::init()
+        .map_err(|_| crate::error::ScreenshotError::Gui("Failed to init GTK".to_string()))?;
+    let config = theme::resolve(config).await;
+
+    let app = gtk4::Application::builder()
+        .application_id("com.hans-chrstn.just-a-simple-wayland-screenshot-tool.preview")
+        .build();
+
+    app.connect_activate(move |app| {
+        let preview = PreviewWindow::new(app, screenshot.clone(), output.clone(), config.clone());
+        preview.show();
+    });
+
+    app.run_with_args::<&str>(&[]);
+    Ok(())
+}
@@ -1,12 +1,41 @@
-use crate::{Region, Result, ScreenshotError, config::Config};
+use crate::{
+    CaptureMode, CaptureOptions, LogicalRegion, Region, Result, Screenshot,
+    capture::{CaptureRequest, Compositor},
+    config::{Config, HudConfig, MouseAction},
+    export::{ExportOptions, Exporter},
+    history::History,
+    processing::ImageProcessor,
+};
 use gtk4::prelude::*;
 use gtk4::{Application, ApplicationWindow, CssProvider, gdk, glib};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
-use std::time::Instant;
 
+// Some(points) while in freeform mode, in drawing-area coordinates.
+type Lasso = Rc<RefCell<Option<Vec<(f64, f64)>>>>;
+
+// Some(windows) while snapping to windows is toggled on.
+type SnapWindows = Rc<RefCell<Option<Vec<(Region, String)>>>>;
+
+use super::preview::PreviewWindow;
+use super::theme::Theme;
 use super::widgets::AnimatedWidget;
 
+#[derive(Debug, Clone, Copy)]
+struct MonitorGeometry {
+    x: i32,
+    y: i32,
+    scale: f64,
+}
+
+// What the HUD describes: the selection, the monitor it is measured on and
+// the window it snapped to.
+struct HudContext<'a> {
+    region: Region,
+    monitor: MonitorGeometry,
+    snap_target: Option<&'a str>,
+}
+
 pub struct SelectionOverlay {
     window: ApplicationWindow,
     animated_widget: Rc<RefCell<AnimatedWidget>>,
@@ -14,7 +43,7 @@ pub struct SelectionOverlay {
 }
 
 impl SelectionOverlay {
-    pub fn new(app: &Application, config: Config) -> Self {
+    pub fn new(app: &Application, config: Config, mode: CaptureMode) -> Self {
         let window = Self::create_window(app);
         let display = gdk::Display::default().expect("Could not get default display");
 
@@ -27,8 +56,21 @@ impl SelectionOverlay {
             &config,
         )));
 
-        Self::setup_ui(&window, &animated_widget, &config);
-        Self::setup_keyboard_handler(&window, &config);
+        let monitor = Self::get_monitor_geometry(&display);
+        let selection = Rc::new(RefCell::new(None));
+        let snap_target = Rc::new(RefCell::new(None));
+        let lasso: Lasso = Rc::new(RefCell::new((mode == CaptureMode::Freeform).then(Vec::new)));
+
+        Self::setup_ui(
+            &window,
+            &animated_widget,
+            &selection,
+            &snap_target,
+            &lasso,
+            monitor,
+            &config,
+        );
+        Self::setup_keyboard_handler(&window, &selection, &lasso, monitor, &config);
 
         Self {
             window,
@@ -65,9 +107,56 @@ impl SelectionOverlay {
             .unwrap_or(1920.0)
     }
 
+    fn get_monitor_geometry(display: &gdk::Display) -> MonitorGeometry {
+        let Some(monitor) = display
+            .monitors()
+            .item(0)
+            .and_then(|obj| obj.downcast::<gdk::Monitor>().ok())
+        else {
+            return MonitorGeometry {
+                x: 0,
+                y: 0,
+                scale: 1.0,
+            };
+        };
+
+        // Selections are mapped into the same transform-aware layout the
+        // capture crops against, so a rotated output lines up with its frame.
+        let output = monitor.connector().and_then(|connector| {
+            Compositor::detect()
+                .displays()
+                .ok()?
+                .into_iter()
+                .find(|d| d.name == connector.as_str())
+        });
+
+        match output {
+            Some(output) => {
+                let region = output.logical_region().0;
+                MonitorGeometry {
+                    x: region.x,
+                    y: region.y,
+                    scale: output.scale,
+                }
+            }
+            None => {
+                let geometry = monitor.geometry();
+                MonitorGeometry {
+                    x: geometry.x(),
+                    y: geometry.y(),
+                    scale: monitor.scale_factor() as f64,
+                }
+            }
+        }
+    }
+
     fn setup_ui(
         window: &ApplicationWindow,
         animated_widget: &Rc<RefCell<AnimatedWidget>>,
+        selection: &Rc<RefCell<Option<Region>>>,
+        snap_target: &Rc<RefCell<Option<String>>>,
+        lasso: &Lasso,
+        monitor: MonitorGeometry,
         config: &Config,
     ) {
         let drawing_area = gtk4::DrawingArea::new();
@@ -75,21 +164,469 @@ impl SelectionOverlay {
         drawing_area.set_vexpand(true);
 
         let widget = animated_widget.clone();
+        let current_selection = selection.clone();
+        let current_snap_target = snap_target.clone();
+        let current_lasso = lasso.clone();
+        let hud = config.gui.hud.clone();
+        let theme = Theme::from_config(&config.gui.theme);
         drawing_area.set_draw_func(move |_, cr, width, height| {
             cr.set_operator(cairo::Operator::Clear);
             cr.paint().unwrap();
             cr.set_operator(cairo::Operator::Over);
 
+            if let Some(region) = *current_selection.borrow() {
+                match current_lasso.borrow().as_deref() {
+                    Some(points) => {
+                        Self::draw_lasso(cr, points, width as f64, height as f64, &theme)
+                    }
+                    None => Self::draw_selection(cr, &region, width as f64, height as f64, &theme),
+                }
+                Self::draw_hud(
+                    cr,
+                    width as f64,
+                    height as f64,
+                    HudContext {
+                        region,
+                        monitor,
+                        snap_target: current_snap_target.borrow().as_deref(),
+                    },
+                    &hud,
+                    &theme,
+                );
+            }
+
             widget.borrow().draw(cr, width as f64, height as f64);
         });
 
+        Self::setup_selection(&drawing_area, selection, snap_target, lasso);
+        Self::setup_mouse_bindings(
+            window,
+            &drawing_area,
+            selection,
+            snap_target,
+            lasso,
+            monitor,
+            config,
+        );
+
         window.set_child(Some(&drawing_area));
 
-        Self::start_animation(
-            animated_widget,
-            &drawing_area,
-            config.gui.animation.duration_ms,
+        if config.gui.animation.enabled {
+            Self::start_animation(
+                animated_widget,
+                &drawing_area,
+                config.gui.animation.duration_ms,
+            );
+        } else {
+            let mut widget = animated_widget.borrow_mut();
+            widget.update(1.0);
+            widget.set_showing_controls(true);
+        }
+    }
+
+    fn setup_selection(
+        area: &gtk4::DrawingArea,
+        selection: &Rc<RefCell<Option<Region>>>,
+        snap_target: &Rc<RefCell<Option<String>>>,
+        lasso: &Lasso,
+    ) {
+        let drag = gtk4::GestureDrag::new();
+
+        {
+            let selection = selection.clone();
+            let snap_target = snap_target.clone();
+            let lasso = lasso.clone();
+            let area = area.clone();
+            drag.connect_drag_begin(move |_, x, y| {
+                // Separate presses extend the same outline, so clicking out
+                // vertices draws a polygon and dragging draws freehand.
+                *selection.borrow_mut() = match lasso.borrow_mut().as_mut() {
+                    Some(points) => {
+                        points.push((x, y));
+                        Self::bounding_region(points)
+                    }
+                    None => Some(Region::new(x as i32, y as i32, 0, 0)),
+                };
+                *snap_target.borrow_mut() = None;
+                area.queue_draw();
+            });
+        }
+
+        {
+            let selection = selection.clone();
+            let lasso = lasso.clone();
+            let area = area.clone();
+            drag.connect_drag_update(move |gesture, offset_x, offset_y| {
+                if let Some(points) = lasso.borrow_mut().as_mut() {
+                    if let Some((start_x, start_y)) = gesture.start_point() {
+                        points.push((start_x + offset_x, start_y + offset_y));
+                        *selection.borrow_mut() = Self::bounding_region(points);
+                        area.queue_draw();
+                    }
+                    return;
+                }
+
+                if let Some((start_x, start_y)) = gesture.start_point() {
+                    *selection.borrow_mut() = Some(Region::from_points(
+                        start_x.round() as i32,
+                        start_y.round() as i32,
+                        (start_x + offset_x).round() as i32,
+                        (start_y + offset_y).round() as i32,
+                    ));
+                    area.queue_draw();
+                }
+            });
+        }
+
+        area.add_controller(drag);
+    }
+
+    fn setup_mouse_bindings(
+        window: &ApplicationWindow,
+        area: &gtk4::DrawingArea,
+        selection: &Rc<RefCell<Option<Region>>>,
+        snap_target: &Rc<RefCell<Option<String>>>,
+        lasso: &Lasso,
+        monitor: MonitorGeometry,
+        config: &Config,
+    ) {
+        let bindings = config.gui.mouse_bindings.clone();
+        let snap_windows: SnapWindows = Rc::new(RefCell::new(None));
+
+        let click = gtk4::GestureClick::new();
+        click.set_button(0);
+
+        {
+            let window = window.clone();
+            let area = area.clone();
+            let selection = selection.clone();
+            let snap_target = snap_target.clone();
+            let snap_windows = snap_windows.clone();
+            let config = config.clone();
+            click.connect_pressed(move |gesture, _, x, y| {
+                let action = match gesture.current_button() {
+                    2 => bindings.middle,
+                    3 => bindings.right,
+                    _ => return,
+                };
+
+                match action {
+                    MouseAction::None => {}
+                    MouseAction::Cancel => window.close(),
+                    MouseAction::CaptureOutput => {
+                        let region =
+                            Self::output_under_cursor(&area, monitor, x, y).map(LogicalRegion);
+                        Self::capture_and_close(&window, &config, region, None);
+                    }
+                    MouseAction::ToggleWindowSnap => {
+                        let enabled = snap_windows.borrow().is_some();
+                        *snap_windows.borrow_mut() = if enabled {
+                            None
+                        } else {
+                            Some(Self::snap_targets(&window, monitor))
+                        };
+                        *selection.borrow_mut() = None;
+                        *snap_target.borrow_mut() = None;
+                        area.queue_draw();
+                    }
+                }
+            });
+        }
+        area.add_controller(click);
+
+        // Touchscreens have no Space key to confirm with, so lifting the finger
+        // after dragging out a region captures it.
+        let touch = gtk4::GestureDrag::new();
+        touch.set_touch_only(true);
+        {
+            let window = window.clone();
+            let selection = selection.clone();
+            let lasso = lasso.clone();
+            let config = config.clone();
+            touch.connect_drag_end(move |_, _, _| {
+                let Some(region) = selection.borrow().filter(|r| r.width > 2 && r.height > 2)
+                else {
+                    return;
+                };
+
+                let outline = Self::lasso_outline(&lasso, &region);
+                let selection = Some(Self::to_logical(region, monitor));
+                Self::capture_and_close(&window, &config, selection, outline);
+            });
+        }
+        area.add_controller(touch);
+
+        let motion = gtk4::EventControllerMotion::new();
+        {
+            let area = area.clone();
+            let selection = selection.clone();
+            let snap_target = snap_target.clone();
+            motion.connect_motion(move |controller, x, y| {
+                let Some(targets) = snap_windows.borrow().clone() else {
+                    return;
+                };
+                if controller
+                    .current_event_state()
+                    .contains(gdk::ModifierType::BUTTON1_MASK)
+                {
+                    return;
+                }
+
+                let target = targets
+                    .into_iter()
+                    .filter(|(r, _)| r.contains(x.floor() as i32, y.floor() as i32))
+                    .min_by_key(|(r, _)| r.width as u64 * r.height as u64);
+                *selection.borrow_mut() = target.as_ref().map(|(r, _)| *r);
+                *snap_target.borrow_mut() = target.map(|(_, name)| name);
+                area.queue_draw();
+            });
+        }
+        area.add_controller(motion);
+    }
+
+    fn snap_targets(window: &ApplicationWindow, monitor: MonitorGeometry) -> Vec<(Region, String)> {
+        let own_app_id = window
+            .application()
+            .and_then(|app| app.application_id())
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+
+        Compositor::detect()
+            .windows()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|w| w.app_id != own_app_id && w.width > 0 && w.height > 0)
+            .map(|w| {
+                let region = Region::new(w.x - monitor.x, w.y - monitor.y, w.width, w.height);
+                let name = if w.title.is_empty() {
+                    w.app_id
+                } else {
+                    w.title
+                };
+                (region, name)
+            })
+            .collect()
+    }
+
+    fn output_under_cursor(
+        area: &gtk4::DrawingArea,
+        monitor: MonitorGeometry,
+        x: f64,
+        y: f64,
+    ) -> Option<Region> {
+        let global_x = x + monitor.x as f64;
+        let global_y = y + monitor.y as f64;
+
+        let output = Compositor::detect()
+            .displays()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|d| d.logical_region().0)
+            .find(|r| r.contains(global_x.floor() as i32, global_y.floor() as i32));
+
+        output.or_else(|| {
+            let (width, height) = (area.width(), area.height());
+            (width > 0 && height > 0)
+                .then(|| Region::new(monitor.x, monitor.y, width as u32, height as u32))
+        })
+    }
+
+    fn to_logical(region: Region, monitor: MonitorGeometry) -> LogicalRegion {
+        LogicalRegion(Region::new(
+            region.x + monitor.x,
+            region.y + monitor.y,
+            region.width,
+            region.height,
+        ))
+    }
+
+    fn bounding_region(points: &[(f64, f64)]) -> Option<Region> {
+        let (first, rest) = points.split_first()?;
+        let (left, top, right, bottom) = rest.iter().fold(
+            (first.0, first.1, first.0, first.1),
+            |(left, top, right, bottom), &(x, y)| {
+                (left.min(x), top.min(y), right.max(x), bottom.max(y))
+            },
         );
+
+        Some(Region::from_points(
+            left.floor() as i32,
+            top.floor() as i32,
+            right.ceil() as i32,
+            bottom.ceil() as i32,
+        ))
+    }
+
+    fn lasso_outline(lasso: &Lasso, region: &Region) -> Option<Vec<(f64, f64)>> {
+        lasso.borrow().as_ref().map(|points| {
+            points
+                .iter()
+                .map(|(x, y)| (x - region.x as f64, y - region.y as f64))
+                .collect()
+        })
+    }
+
+    fn draw_lasso(
+        cr: &cairo::Context,
+        points: &[(f64, f64)],
+        width: f64,
+        height: f64,
+        theme: &Theme,
+    ) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+
+        let outline = |cr: &cairo::Context| {
+            cr.move_to(first.0, first.1);
+            for (x, y) in rest {
+                cr.line_to(*x, *y);
+            }
+            cr.close_path();
+        };
+
+        cr.set_fill_rule(cairo::FillRule::EvenOdd);
+        cr.rectangle(0.0, 0.0, width, height);
+        outline(cr);
+        theme.dim.set_source(cr);
+        cr.fill().unwrap();
+        cr.set_fill_rule(cairo::FillRule::Winding);
+
+        outline(cr);
+        theme.selection.set_source(cr);
+        cr.set_line_width(theme.selection_width);
+        cr.set_line_join(cairo::LineJoin::Round);
+        cr.stroke().unwrap();
+    }
+
+    fn draw_selection(
+        cr: &cairo::Context,
+        region: &Region,
+        width: f64,
+        height: f64,
+        theme: &Theme,
+    ) {
+        let x = region.x as f64;
+        let y = region.y as f64;
+        let w = region.width as f64;
+        let h = region.height as f64;
+
+        cr.set_fill_rule(cairo::FillRule::EvenOdd);
+        cr.rectangle(0.0, 0.0, width, height);
+        cr.rectangle(x, y, w, h);
+        theme.dim.set_source(cr);
+        cr.fill().unwrap();
+        cr.set_fill_rule(cairo::FillRule::Winding);
+
+        cr.rectangle(x, y, w, h);
+        theme.selection.set_source(cr);
+        cr.set_line_width(theme.selection_width);
+        cr.stroke().unwrap();
+
+        if theme.handle_size > 0.0 {
+            let half = theme.handle_size / 2.0;
+            for (hx, hy) in [(x, y), (x + w, y), (x, y + h), (x + w, y + h)] {
+                cr.rectangle(hx - half, hy - half, theme.handle_size, theme.handle_size);
+            }
+            cr.fill().unwrap();
+        }
+    }
+
+    fn draw_hud(
+        cr: &cairo::Context,
+        width: f64,
+        height: f64,
+        context: HudContext,
+        hud: &HudConfig,
+        theme: &Theme,
+    ) {
+        if !hud.enabled {
+            return;
+        }
+
+        let HudContext {
+            region,
+            monitor,
+            snap_target,
+        } = context;
+        let physical = LogicalRegion(region).to_physical(monitor.scale).0;
+        let mut lines = Vec::new();
+
+        if hud.show_size {
+            lines.push(format!("{} × {}", physical.width, physical.height));
+        }
+        if hud.show_position {
+            lines.push(format!(
+                "@ {}, {}",
+                region.x + monitor.x,
+                region.y + monitor.y
+            ));
+        }
+        if hud.show_aspect_ratio && physical.width > 0 && physical.height > 0 {
+            lines.push(aspect_ratio(physical.width, physical.height));
+        }
+        if hud.show_snap_target
+            && let Some(target) = snap_target
+        {
+            lines.push(format!("⧉ {}", target));
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+
+        cr.select_font_face(
+            &theme.font,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Bold,
+        );
+        cr.set_font_size(hud.font_size);
+
+        let padding = 6.0;
+        let gap = 6.0;
+        let line_height = hud.font_size * 1.3;
+        let text_width = lines
+            .iter()
+            .filter_map(|line| cr.text_extents(line).ok())
+            .map(|e| e.x_advance())
+            .fold(0.0, f64::max);
+
+        let label_width = text_width + padding * 2.0;
+        let label_height = line_height * lines.len() as f64 + padding * 2.0;
+
+        let x = region.x as f64;
+        let y = region.y as f64;
+        let w = region.width as f64;
+        let h = region.height as f64;
+
+        // Prefer above the selection, then below, and only fall back to the
+        // inside corner when neither edge has room.
+        let label_y = if y - label_height - gap >= 0.0 {
+            y - label_height - gap
+        } else if y + h + gap + label_height <= height {
+            y + h + gap
+        } else {
+            (y + gap).min(height - label_height).max(0.0)
+        };
+
+        let label_x = if x + label_width > width {
+            (x + w - label_width).max(0.0)
+        } else {
+            x.max(0.0)
+        };
+
+        cr.rectangle(label_x, label_y, label_width, label_height);
+        theme.toolbar_bottom.set_source(cr);
+        cr.fill().unwrap();
+
+        theme.text.set_source(cr);
+        for (i, line) in lines.iter().enumerate() {
+            cr.move_to(
+                label_x + padding,
+                label_y + padding + line_height * (i as f64 + 1.0) - line_height * 0.25,
+            );
+            cr.show_text(line).unwrap();
+        }
     }
 
     fn start_animation(
@@ -98,19 +635,28 @@ impl SelectionOverlay {
         duration: u64,
     ) {
         let widget = widget.clone();
-        let area = area.clone();
-        let start_time = Instant::now();
-
-        glib::timeout_add_local(std::time::Duration::from_millis(16), move || {
-            let elapsed = start_time.elapsed().as_millis() as f64;
-            let progress = (elapsed / duration as f64).min(1.0);
+        let start_time = Cell::new(None);
+
+        // Frame-clock times are in microseconds and follow the monitor's
+        // refresh, so progress stays time-based at any rate and the callback
+        // goes away once the intro is done.
+        area.add_tick_callback(move |area, clock| {
+            let now = clock.frame_time();
+            let start = start_time.get().unwrap_or(now);
+            start_time.set(Some(start));
+            let elapsed = (now - start) as f64 / 1000.0;
+            let progress = if duration == 0 {
+                1.0
+            } else {
+                (elapsed / duration as f64).min(1.0)
+            };
 
-            widget.borrow_mut().update(progress);
+            let mut widget = widget.borrow_mut();
+            widget.update(progress);
             area.queue_draw();
 
             if progress >= 1.0 {
-                widget.borrow_mut().set_showing_controls(true);
-                area.queue_draw();
+                widget.set_showing_controls(true);
                 glib::ControlFlow::Break
             } else {
                 glib::ControlFlow::Continue
@@ -118,23 +664,26 @@ impl SelectionOverlay {
         });
     }
 
-    fn setup_keyboard_handler(window: &ApplicationWindow, config: &Config) {
+    fn setup_keyboard_handler(
+        window: &ApplicationWindow,
+        selection: &Rc<RefCell<Option<Region>>>,
+        lasso: &Lasso,
+        monitor: MonitorGeometry,
+        config: &Config,
+    ) {
         let key_controller = gtk4::EventControllerKey::new();
         let window_clone = window.clone();
+        let selection = selection.clone();
+        let lasso = lasso.clone();
         let config = config.clone();
 
         key_controller.connect_key_pressed(move |_, key, _, _| match key {
             gdk::Key::space => {
-                let window = window_clone.clone();
-                let config = config.clone();
-
-                glib::MainContext::default().spawn_local(async move {
-                    if let Err(e) = ScreenshotCapture::capture_interactive(&config).await {
-                        eprintln!("Screenshot failed: {}", e);
-                    }
-                    window.close();
-                });
+                let region = selection.borrow().filter(|r| r.width > 0 && r.height > 0);
+                let outline = region.and_then(|r| Self::lasso_outline(&lasso, &r));
+                let selection = region.map(|r| Self::to_logical(r, monitor));
 
+                Self::capture_and_close(&window_clone, &config, selection, outline);
                 glib::Propagation::Stop
             }
             gdk::Key::Escape => {
@@ -147,6 +696,95 @@ impl SelectionOverlay {
         window.add_controller(key_controller);
     }
 
+    fn capture_and_close(
+        window: &ApplicationWindow,
+        config: &Config,
+        selection: Option<LogicalRegion>,
+        outline: Option<Vec<(f64, f64)>>,
+    ) {
+        let window = window.clone();
+        let config = config.clone();
+
+        window.set_visible(false);
+
+        glib::MainContext::default().spawn_local(async move {
+            // Without a selection the backend falls back to its own
+            // interactive picker.
+            let request = match selection {
+                Some(region) => {
+                    glib::timeout_future(std::time::Duration::from_millis(200)).await;
+                    CaptureRequest::region(region)
+                }
+                None => CaptureRequest::new(CaptureMode::Region, CaptureOptions::default()),
+            };
+            let result = request.run(&config.capture).await;
+
+            let result = match (result, selection, outline) {
+                (Ok(screenshot), Some(region), Some(outline)) => {
+                    Self::apply_outline(screenshot, region, &outline)
+                }
+                (result, _, _) => result,
+            };
+
+            match result {
+                Ok(screenshot) => Self::finish_capture(&window, &config, screenshot),
+                Err(e) => eprintln!("Screenshot failed: {}", e),
+            }
+            window.close();
+        });
+    }
+
+    // The outline is in logical pixels relative to the region; the capture may
+    // be at a higher output scale.
+    fn apply_outline(
+        screenshot: Screenshot,
+        region: LogicalRegion,
+        outline: &[(f64, f64)],
+    ) -> Result<Screenshot> {
+        let scale = if region.0.width > 0 {
+            screenshot.width() as f64 / region.0.width as f64
+        } else {
+            1.0
+        };
+        let points: Vec<(f64, f64)> = outline
+            .iter()
+            .map(|(x, y)| (x * scale, y * scale))
+            .collect();
+
+        let mut screenshot = ImageProcessor::mask_polygon(&screenshot, &points)?;
+        screenshot.metadata.mode = CaptureMode::Freeform;
+        Ok(screenshot)
+    }
+
+    fn finish_capture(window: &ApplicationWindow, config: &Config, screenshot: Screenshot) {
+        let filename = format!("{}.png", config.generate_filename());
+        let output_path = config.save_directory.join(filename);
+
+        let screenshot = match config.apply_watermark(screenshot) {
+            Ok(screenshot) => screenshot,
+            Err(e) => {
+                eprintln!("Screenshot failed: {}", e);
+                return;
+            }
+        };
+
+        if config.gui.preview.enabled_for(screenshot.metadata.mode)
+            && let Some(app) = window.application()
+        {
+            PreviewWindow::new(&app, screenshot, output_path, config.clone()).show();
+            return;
+        }
+
+        let export_options = ExportOptions {
+            metadata: config.embedded_metadata(&screenshot, None),
+            quality: Some(config.jpeg_quality),
+            ..Default::default()
+        };
+        if let Err(e) = ScreenshotCapture::save(&screenshot, &output_path, &export_options) {
+            eprintln!("Screenshot failed: {}", e);
+        }
+    }
+
     pub fn show(&self) {
         self.window.present();
         self.window.set_opacity(1.0);
@@ -156,35 +794,34 @@ impl SelectionOverlay {
 struct ScreenshotCapture;
 
 impl ScreenshotCapture {
-    async fn capture_interactive(config: &Config) -> Result<()> {
-        use ashpd::desktop::screenshot::ScreenshotRequest;
-
-        let response = ScreenshotRequest::default()
-            .interactive(true)
-            .send()
-            .await
-            .map_err(|e| ScreenshotError::Portal(e.to_string()))?
-            .response()
-            .map_err(|e| ScreenshotError::Portal(e.to_string()))?;
-
-        let uri = response.uri();
-        let path = uri
-            .to_file_path()
-            .map_err(|_| ScreenshotError::Portal("Invalid file path".to_string()))?;
+    fn save(
+        screenshot: &Screenshot,
+        output_path: &std::path::Path,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        Exporter::save_with_options(screenshot, output_path, crate::OutputFormat::Png, options)?;
+        println!("{}", output_path.display());
 
-        let img = image::open(&path).map_err(ScreenshotError::Image)?;
-        let screenshot = img.to_rgba8();
+        if let Err(e) = History::record(output_path, screenshot) {
+            eprintln!("Failed to record history: {}", e);
+        }
+        super::recent::remember(output_path);
 
-        let filename = format!("{}.png", config.generate_filename());
-        let output_path = config.save_directory.join(filename);
+        Ok(())
+    }
+}
 
-        screenshot
-            .save(&output_path)
-            .map_err(ScreenshotError::Image)?;
-        println!("{}", output_path.display());
+fn aspect_ratio(width: u32, height: u32) -> String {
+    fn gcd(a: u32, b: u32) -> u32 {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
 
-        let _ = std::fs::remove_file(path);
+    let divisor = gcd(width, height);
+    let (w, h) = (width / divisor, height / divisor);
 
-        Ok(())
+    if w <= 32 && h <= 32 {
+        format!("{}:{}", w, h)
+    } else {
+        format!("{:.2}:1", width as f64 / height as f64)
     }
 }
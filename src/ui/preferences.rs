@@ -0,0 +1,280 @@
+use super::setup::FORMATS;
+use crate::{
+    OutputFormat,
+    config::{Config, ShortcutConfig},
+};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, DropDown, Entry,
+    EventControllerKey, FileChooserAction, FileChooserDialog, Label, Orientation, ResponseType,
+    Scale, SpinButton, Switch, gdk, glib,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Picks the shortcut a preferences row edits.
+type ShortcutField = fn(&mut ShortcutConfig) -> &mut String;
+
+pub struct PreferencesWindow {
+    window: ApplicationWindow,
+}
+
+impl PreferencesWindow {
+    pub fn new(app: &Application, config: Config) -> Self {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("Preferences")
+            .default_width(520)
+            .build();
+
+        let main_box = GtkBox::new(Orientation::Vertical, 12);
+        main_box.set_margin_start(16);
+        main_box.set_margin_end(16);
+        main_box.set_margin_top(16);
+        main_box.set_margin_bottom(16);
+        window.set_child(Some(&main_box));
+
+        main_box.append(&Self::heading("Saving"));
+
+        let directory = Entry::new();
+        directory.set_text(&config.save_directory.display().to_string());
+        directory.set_hexpand(true);
+        let browse_btn = Button::with_label("Browse…");
+        let directory_box = GtkBox::new(Orientation::Horizontal, 5);
+        directory_box.append(&directory);
+        directory_box.append(&browse_btn);
+        main_box.append(&Self::row("Save screenshots to", &directory_box));
+
+        {
+            let window = window.clone();
+            let directory = directory.clone();
+            browse_btn.connect_clicked(move |_| {
+                let dialog = FileChooserDialog::new(
+                    Some("Choose a folder"),
+                    Some(&window),
+                    FileChooserAction::SelectFolder,
+                    &[
+                        ("Cancel", ResponseType::Cancel),
+                        ("Select", ResponseType::Accept),
+                    ],
+                );
+                dialog.set_modal(true);
+
+                let directory = directory.clone();
+                dialog.connect_response(move |dialog, response| {
+                    if response == ResponseType::Accept
+                        && let Some(path) = dialog.file().and_then(|file| file.path())
+                    {
+                        directory.set_text(&path.display().to_string());
+                    }
+                    dialog.close();
+                });
+                dialog.present();
+            });
+        }
+
+        let filename = Entry::new();
+        filename.set_text(&config.filename_template);
+        filename.set_hexpand(true);
+        main_box.append(&Self::row("File name template", &filename));
+
+        let format = DropDown::from_strings(&FORMATS);
+        format.set_selected(
+            FORMATS
+                .iter()
+                .position(|f| f.parse::<OutputFormat>().ok() == Some(config.default_format))
+                .unwrap_or(0) as u32,
+        );
+        main_box.append(&Self::row("Default format", &format));
+
+        let quality = Scale::with_range(Orientation::Horizontal, 1.0, 100.0, 1.0);
+        quality.set_value(config.jpeg_quality as f64);
+        quality.set_digits(0);
+        quality.set_draw_value(true);
+        quality.set_hexpand(true);
+        main_box.append(&Self::row("JPEG quality", &quality));
+
+        main_box.append(&Self::heading("Capture"));
+
+        let delay = SpinButton::with_range(0.0, 60.0, 1.0);
+        delay.set_value(config.delay_seconds as f64);
+        main_box.append(&Self::row("Delay (seconds)", &delay));
+
+        let cursor = Self::switch(config.include_cursor);
+        main_box.append(&Self::row("Include the cursor", &cursor));
+
+        let clipboard = Self::switch(config.auto_copy_to_clipboard);
+        main_box.append(&Self::row(
+            "Also copy captures to the clipboard",
+            &clipboard,
+        ));
+
+        let notify = Self::switch(config.notify_on_capture);
+        main_box.append(&Self::row("Notify after each capture", &notify));
+
+        let open = Self::switch(config.open_after_save);
+        main_box.append(&Self::row("Open captures after saving", &open));
+
+        main_box.append(&Self::heading("Editor shortcuts"));
+
+        let shortcuts = Rc::new(RefCell::new(config.shortcuts.clone()));
+        let bindings: [(&str, ShortcutField); 5] = [
+            ("Save", |s| &mut s.save),
+            ("Cancel", |s| &mut s.cancel),
+            ("Undo", |s| &mut s.undo),
+            ("Redo", |s| &mut s.redo),
+            ("Copy", |s| &mut s.copy),
+        ];
+        for (label, field) in bindings {
+            let button = Self::shortcut_button(shortcuts.clone(), field);
+            main_box.append(&Self::row(label, &button));
+        }
+
+        let buttons = GtkBox::new(Orientation::Horizontal, 5);
+        buttons.set_halign(Align::End);
+        let cancel_btn = Button::with_label("Cancel");
+        let save_btn = Button::with_label("Save");
+        buttons.append(&cancel_btn);
+        buttons.append(&save_btn);
+        main_box.append(&buttons);
+
+        {
+            let window = window.clone();
+            cancel_btn.connect_clicked(move |_| window.close());
+        }
+
+        {
+            let window = window.clone();
+            save_btn.connect_clicked(move |_| {
+                let mut config = config.clone();
+                config.save_directory = directory.text().as_str().into();
+                config.filename_template = filename.text().to_string();
+                config.default_format = FORMATS
+                    .get(format.selected() as usize)
+                    .and_then(|f| f.parse().ok())
+                    .unwrap_or(config.default_format);
+                config.jpeg_quality = quality.value().round() as u8;
+                config.delay_seconds = delay.value_as_int().max(0) as u64;
+                config.include_cursor = cursor.is_active();
+                config.auto_copy_to_clipboard = clipboard.is_active();
+                config.notify_on_capture = notify.is_active();
+                config.open_after_save = open.is_active();
+                config.shortcuts = shortcuts.borrow().clone();
+
+                if let Err(e) = config.save() {
+                    eprintln!("Failed to save configuration: {}", e);
+                }
+                window.close();
+            });
+        }
+
+        Self { window }
+    }
+
+    // Clicking arms the button; the next non-modifier key press (with its
+    // modifiers) becomes the shortcut, Escape keeps the old one.
+    fn shortcut_button(shortcuts: Rc<RefCell<ShortcutConfig>>, field: ShortcutField) -> Button {
+        let button = Button::with_label(field(&mut shortcuts.borrow_mut()).as_str());
+        let recording = Rc::new(RefCell::new(false));
+
+        {
+            let recording = recording.clone();
+            button.connect_clicked(move |button| {
+                *recording.borrow_mut() = true;
+                button.set_label("Press a shortcut…");
+            });
+        }
+
+        let controller = EventControllerKey::new();
+        {
+            let button = button.clone();
+            controller.connect_key_pressed(move |_, key, _, state| {
+                if !*recording.borrow() {
+                    return glib::Propagation::Proceed;
+                }
+
+                let mut shortcuts = shortcuts.borrow_mut();
+                let current = field(&mut shortcuts);
+
+                if key == gdk::Key::Escape {
+                    *recording.borrow_mut() = false;
+                    button.set_label(current);
+                    return glib::Propagation::Stop;
+                }
+
+                let Some(shortcut) = format_shortcut(key, state) else {
+                    return glib::Propagation::Stop;
+                };
+
+                *recording.borrow_mut() = false;
+                button.set_label(&shortcut);
+                *current = shortcut;
+                glib::Propagation::Stop
+            });
+        }
+        button.add_controller(controller);
+
+        button
+    }
+
+    fn heading(text: &str) -> Label {
+        let label = Label::new(None);
+        label.set_markup(&format!("<b>{}</b>", glib::markup_escape_text(text)));
+        label.set_xalign(0.0);
+        label
+    }
+
+    fn switch(active: bool) -> Switch {
+        let switch = Switch::new();
+        switch.set_active(active);
+        switch.set_halign(Align::End);
+        switch
+    }
+
+    fn row(label: &str, widget: &impl IsA<gtk4::Widget>) -> GtkBox {
+        let row = GtkBox::new(Orientation::Horizontal, 12);
+        let label = Label::new(Some(label));
+        label.set_xalign(0.0);
+        label.set_hexpand(true);
+        row.append(&label);
+        row.append(widget);
+        row
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
+
+fn format_shortcut(key: gdk::Key, state: gdk::ModifierType) -> Option<String> {
+    let name = key.to_lower().name()?;
+    if [
+        "Control",
+        "Shift",
+        "Alt",
+        "Super",
+        "Meta",
+        "Hyper",
+        "ISO_Level3",
+    ]
+    .iter()
+    .any(|modifier| name.starts_with(modifier))
+    {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    for (mask, label) in [
+        (gdk::ModifierType::CONTROL_MASK, "Ctrl"),
+        (gdk::ModifierType::SHIFT_MASK, "Shift"),
+        (gdk::ModifierType::ALT_MASK, "Alt"),
+        (gdk::ModifierType::SUPER_MASK, "Super"),
+    ] {
+        if state.contains(mask) {
+            parts.push(label.to_string());
+        }
+    }
+    parts.push(name.to_string());
+
+    Some(parts.join("+"))
+}
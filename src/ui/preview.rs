@@ -0,0 +1,177 @@
+use super::editor::{EditorWindow, pixbuf_from_image};
+use crate::config::{Config, PreviewAction};
+use crate::{
+    OutputFormat, Result, Screenshot,
+    export::{ExportOptions, Exporter},
+    history::History,
+};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, Entry, Orientation, Picture, glib,
+};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub struct PreviewWindow {
+    window: ApplicationWindow,
+}
+
+impl PreviewWindow {
+    pub fn new(
+        app: &Application,
+        screenshot: Screenshot,
+        output_path: PathBuf,
+        config: Config,
+    ) -> Self {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("Screenshot Preview")
+            .default_width(640)
+            .default_height(480)
+            .build();
+
+        let main_box = GtkBox::new(Orientation::Vertical, 8);
+        main_box.set_margin_start(10);
+        main_box.set_margin_end(10);
+        main_box.set_margin_top(10);
+        main_box.set_margin_bottom(10);
+        window.set_child(Some(&main_box));
+
+        let pixbuf = pixbuf_from_image(screenshot.data.clone());
+        let picture = Picture::for_pixbuf(&pixbuf);
+        picture.set_can_shrink(true);
+        picture.set_keep_aspect_ratio(true);
+        picture.set_vexpand(true);
+        main_box.append(&picture);
+
+        super::drag::attach_image_source(&picture, move || Some(pixbuf.clone()));
+
+        let directory = output_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| config.save_directory.clone());
+        let filename = Entry::new();
+        filename.set_text(
+            &output_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+        main_box.append(&filename);
+
+        let buttons = GtkBox::new(Orientation::Horizontal, 5);
+        buttons.set_halign(Align::End);
+
+        let discard_btn = Button::with_label("🗑️ Discard");
+        let edit_btn = Button::with_label("✏️ Edit");
+        let copy_btn = Button::with_label("📋 Copy");
+        let save_btn = Button::with_label("💾 Save");
+
+        buttons.append(&discard_btn);
+        buttons.append(&edit_btn);
+        buttons.append(&copy_btn);
+        buttons.append(&save_btn);
+        main_box.append(&buttons);
+
+        let export_options = ExportOptions {
+            metadata: config.embedded_metadata(&screenshot, None),
+            quality: Some(config.jpeg_quality),
+            ..Default::default()
+        };
+        let screenshot = Rc::new(screenshot);
+        let handled = Rc::new(Cell::new(false));
+
+        let save = {
+            let window = window.clone();
+            let screenshot = screenshot.clone();
+            let filename = filename.clone();
+            let handled = handled.clone();
+            move || {
+                handled.set(true);
+                let path = directory.join(filename.text().as_str());
+                if let Err(e) = Self::save(&screenshot, &path, &export_options) {
+                    eprintln!("Failed to save screenshot: {}", e);
+                }
+                window.close();
+            }
+        };
+
+        {
+            let save = save.clone();
+            save_btn.connect_clicked(move |_| save());
+        }
+
+        {
+            let screenshot = screenshot.clone();
+            let clipboard = config.clipboard.clone();
+            copy_btn.connect_clicked(move |_| {
+                if let Err(e) = Exporter::copy_to_clipboard_with(&screenshot, &clipboard) {
+                    eprintln!("Failed to copy screenshot: {}", e);
+                }
+            });
+        }
+
+        {
+            let window = window.clone();
+            let app = app.clone();
+            let screenshot = screenshot.clone();
+            let handled = handled.clone();
+            let config = config.clone();
+            edit_btn.connect_clicked(move |_| {
+                handled.set(true);
+                let pixbuf = pixbuf_from_image(screenshot.data.clone());
+                match EditorWindow::new(&app, pixbuf, config.clone()) {
+                    Ok(editor) => editor.show(),
+                    Err(e) => eprintln!("Failed to create editor: {}", e),
+                }
+                window.close();
+            });
+        }
+
+        {
+            let window = window.clone();
+            let handled = handled.clone();
+            discard_btn.connect_clicked(move |_| {
+                handled.set(true);
+                window.close();
+            });
+        }
+
+        let preview = &config.gui.preview;
+        if preview.timeout_seconds > 0 {
+            let window = window.clone();
+            let action = preview.timeout_action;
+            glib::timeout_add_seconds_local_once(preview.timeout_seconds as u32, move || {
+                if handled.get() {
+                    return;
+                }
+
+                match action {
+                    PreviewAction::Save => save(),
+                    PreviewAction::Discard => window.close(),
+                }
+            });
+        }
+
+        Self { window }
+    }
+
+    fn save(screenshot: &Screenshot, path: &Path, options: &ExportOptions) -> Result<()> {
+        let format = OutputFormat::from_path(path).unwrap_or(OutputFormat::Png);
+
+        Exporter::save_with_options(screenshot, path, format, options)?;
+        println!("{}", path.display());
+
+        if let Err(e) = History::record(path, screenshot) {
+            eprintln!("Failed to record history: {}", e);
+        }
+        super::recent::remember(path);
+
+        Ok(())
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
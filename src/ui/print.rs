@@ -0,0 +1,32 @@
+use crate::{Result, ScreenshotError};
+use gtk4::gdk::prelude::GdkCairoContextExt;
+use gtk4::gdk_pixbuf::Pixbuf;
+use gtk4::prelude::*;
+use gtk4::{PrintOperation, PrintOperationAction, Window};
+
+pub fn print_pixbuf(parent: Option<&Window>, pixbuf: &Pixbuf, job_name: &str) -> Result<()> {
+    let operation = PrintOperation::new();
+    operation.set_n_pages(1);
+    operation.set_job_name(job_name);
+
+    let pixbuf = pixbuf.clone();
+    operation.connect_draw_page(move |_, context, _| {
+        let cr = context.cairo_context();
+        let (page_width, page_height) = (context.width(), context.height());
+        let (width, height) = (pixbuf.width() as f64, pixbuf.height() as f64);
+
+        let scale = (page_width / width).min(page_height / height);
+        cr.translate(
+            (page_width - width * scale) / 2.0,
+            (page_height - height * scale) / 2.0,
+        );
+        cr.scale(scale, scale);
+        cr.set_source_pixbuf(&pixbuf, 0.0, 0.0);
+        let _ = cr.paint();
+    });
+
+    operation
+        .run(PrintOperationAction::PrintDialog, parent)
+        .map(|_| ())
+        .map_err(|e| ScreenshotError::Gui(format!("Printing failed: {}", e)))
+}
@@ -0,0 +1,188 @@
+use super::editor::EditorWindow;
+use crate::{Screenshot, config::Config, export::Exporter, history::History};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, FlowBox, Label, Orientation,
+    Picture, PolicyType, RecentManager, ScrolledWindow, SelectionMode, gdk_pixbuf::Pixbuf, gio,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const THUMBNAIL_WIDTH: i32 = 220;
+const THUMBNAIL_HEIGHT: i32 = 140;
+const MAX_RECENT: usize = 24;
+
+pub fn remember<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref();
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    RecentManager::default().add_item(&gio::File::for_path(path).uri());
+}
+
+pub fn recent_captures(limit: usize) -> Vec<PathBuf> {
+    let from_history = History::entries()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|entry| entry.path);
+
+    let from_recent_manager = RecentManager::default()
+        .items()
+        .into_iter()
+        .filter(|info| info.mime_type().starts_with("image/"))
+        .filter_map(|info| gio::File::for_uri(&info.uri()).path());
+
+    let mut seen = HashSet::new();
+    from_history
+        .chain(from_recent_manager)
+        .filter(|path| path.is_file() && seen.insert(path.clone()))
+        .take(limit)
+        .collect()
+}
+
+pub struct StartScreen {
+    window: ApplicationWindow,
+}
+
+impl StartScreen {
+    pub fn new(app: &Application, config: Config) -> Self {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("Recent Screenshots")
+            .default_width(760)
+            .default_height(520)
+            .build();
+
+        let main_box = GtkBox::new(Orientation::Vertical, 8);
+        main_box.set_margin_start(10);
+        main_box.set_margin_end(10);
+        main_box.set_margin_top(10);
+        main_box.set_margin_bottom(10);
+        window.set_child(Some(&main_box));
+
+        let captures = recent_captures(MAX_RECENT);
+        if captures.is_empty() {
+            let empty = Label::new(Some("No recent screenshots"));
+            empty.set_vexpand(true);
+            main_box.append(&empty);
+            return Self { window };
+        }
+
+        let grid = FlowBox::new();
+        grid.set_selection_mode(SelectionMode::None);
+        grid.set_valign(Align::Start);
+        grid.set_homogeneous(true);
+
+        for path in captures {
+            grid.insert(&Self::create_tile(app, &window, &grid, &path, &config), -1);
+        }
+
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_policy(PolicyType::Never, PolicyType::Automatic);
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&grid));
+        main_box.append(&scrolled);
+
+        Self { window }
+    }
+
+    fn create_tile(
+        app: &Application,
+        window: &ApplicationWindow,
+        grid: &FlowBox,
+        path: &Path,
+        config: &Config,
+    ) -> GtkBox {
+        let preview = GtkBox::new(Orientation::Vertical, 4);
+
+        if let Ok(thumbnail) =
+            Pixbuf::from_file_at_scale(path, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT, true)
+        {
+            let picture = Picture::for_pixbuf(&thumbnail);
+            picture.set_size_request(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+            preview.append(&picture);
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let label = Label::new(Some(&name));
+        label.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+        label.set_max_width_chars(28);
+        preview.append(&label);
+
+        let open_btn = Button::new();
+        open_btn.set_child(Some(&preview));
+        open_btn.set_tooltip_text(Some(&path.display().to_string()));
+
+        let copy_btn = Button::with_label("📋 Copy");
+        let delete_btn = Button::with_label("🗑️ Delete");
+        let actions = GtkBox::new(Orientation::Horizontal, 4);
+        actions.set_halign(Align::Center);
+        actions.append(&copy_btn);
+        actions.append(&delete_btn);
+
+        let tile = GtkBox::new(Orientation::Vertical, 4);
+        tile.append(&open_btn);
+        tile.append(&actions);
+
+        {
+            let app = app.clone();
+            let window = window.clone();
+            let path = path.to_path_buf();
+            let config = config.clone();
+            open_btn.connect_clicked(move |_| {
+                match EditorWindow::open(&app, &path, None, config.clone()) {
+                    Ok(editor) => {
+                        editor.show();
+                        window.close();
+                    }
+                    Err(e) => eprintln!("Failed to open {}: {}", path.display(), e),
+                }
+            });
+        }
+
+        {
+            let path = path.to_path_buf();
+            let clipboard = config.clipboard.clone();
+            copy_btn.connect_clicked(move |_| {
+                let result = Screenshot::from_path(&path).and_then(|screenshot| {
+                    Exporter::copy_to_clipboard_with(&screenshot, &clipboard)
+                });
+                if let Err(e) = result {
+                    eprintln!("Failed to copy {}: {}", path.display(), e);
+                }
+            });
+        }
+
+        {
+            let grid = grid.clone();
+            let tile = tile.clone();
+            let path = path.to_path_buf();
+            delete_btn.connect_clicked(move |_| {
+                // Trash rather than unlink so an accidental click can be undone
+                // from the file manager.
+                let file = gio::File::for_path(&path);
+                if let Err(e) = file.trash(None::<&gio::Cancellable>) {
+                    eprintln!("Failed to delete {}: {}", path.display(), e);
+                    return;
+                }
+
+                if let Err(e) = History::remove(&path) {
+                    eprintln!("Failed to update history: {}", e);
+                }
+                let _ = RecentManager::default().remove_item(&file.uri());
+
+                if let Some(child) = tile.parent() {
+                    grid.remove(&child);
+                }
+            });
+        }
+
+        tile
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
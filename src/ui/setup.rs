@@ -0,0 +1,117 @@
+use crate::{OutputFormat, config::Config};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, DropDown, Entry, Label,
+    Orientation, Switch,
+};
+
+pub(super) const FORMATS: [&str; 4] = ["png", "jpg", "webp", "clipboard"];
+
+pub struct SetupWindow {
+    window: ApplicationWindow,
+}
+
+impl SetupWindow {
+    pub fn new(app: &Application, config: Config) -> Self {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("Welcome to wst")
+            .default_width(460)
+            .resizable(false)
+            .build();
+
+        let main_box = GtkBox::new(Orientation::Vertical, 12);
+        main_box.set_margin_start(16);
+        main_box.set_margin_end(16);
+        main_box.set_margin_top(16);
+        main_box.set_margin_bottom(16);
+        window.set_child(Some(&main_box));
+
+        let intro = Label::new(Some(
+            "A few questions to get you started. You can change these later in the config file.",
+        ));
+        intro.set_wrap(true);
+        intro.set_xalign(0.0);
+        main_box.append(&intro);
+
+        let directory = Entry::new();
+        directory.set_text(&config.save_directory.display().to_string());
+        directory.set_hexpand(true);
+        main_box.append(&Self::row("Save screenshots to", &directory));
+
+        let format = DropDown::from_strings(&FORMATS);
+        format.set_selected(
+            FORMATS
+                .iter()
+                .position(|f| f.parse::<OutputFormat>().ok() == Some(config.default_format))
+                .unwrap_or(0) as u32,
+        );
+        main_box.append(&Self::row("Default format", &format));
+
+        let clipboard = Switch::new();
+        clipboard.set_active(config.auto_copy_to_clipboard);
+        clipboard.set_halign(Align::End);
+        main_box.append(&Self::row(
+            "Also copy captures to the clipboard",
+            &clipboard,
+        ));
+
+        let notify = Switch::new();
+        notify.set_active(config.notify_on_capture);
+        notify.set_halign(Align::End);
+        main_box.append(&Self::row("Notify after each capture", &notify));
+
+        let buttons = GtkBox::new(Orientation::Horizontal, 5);
+        buttons.set_halign(Align::End);
+        let defaults_btn = Button::with_label("Use Defaults");
+        let save_btn = Button::with_label("Save");
+        buttons.append(&defaults_btn);
+        buttons.append(&save_btn);
+        main_box.append(&buttons);
+
+        {
+            let window = window.clone();
+            defaults_btn.connect_clicked(move |_| {
+                if let Err(e) = Config::default().save() {
+                    eprintln!("Failed to save configuration: {}", e);
+                }
+                window.close();
+            });
+        }
+
+        {
+            let window = window.clone();
+            save_btn.connect_clicked(move |_| {
+                let mut config = config.clone();
+                config.save_directory = directory.text().as_str().into();
+                config.default_format = FORMATS
+                    .get(format.selected() as usize)
+                    .and_then(|f| f.parse().ok())
+                    .unwrap_or(config.default_format);
+                config.auto_copy_to_clipboard = clipboard.is_active();
+                config.notify_on_capture = notify.is_active();
+
+                if let Err(e) = config.save() {
+                    eprintln!("Failed to save configuration: {}", e);
+                }
+                window.close();
+            });
+        }
+
+        Self { window }
+    }
+
+    fn row(label: &str, widget: &impl IsA<gtk4::Widget>) -> GtkBox {
+        let row = GtkBox::new(Orientation::Horizontal, 12);
+        let label = Label::new(Some(label));
+        label.set_xalign(0.0);
+        label.set_hexpand(true);
+        row.append(&label);
+        row.append(widget);
+        row
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
@@ -0,0 +1,135 @@
+use crate::config::{Config, ThemeConfig, ThemePreset};
+use ashpd::desktop::settings::{ColorScheme, Settings};
+use gtk4::gdk;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rgba(pub f64, pub f64, pub f64, pub f64);
+
+impl Rgba {
+    pub fn set_source(&self, cr: &cairo::Context) {
+        cr.set_source_rgba(self.0, self.1, self.2, self.3);
+    }
+
+    // Accepts anything GTK's CSS parser does: #rrggbb, rgba(...), names.
+    fn parse(value: &str) -> Option<Self> {
+        match gdk::RGBA::parse(value) {
+            Ok(rgba) => Some(Self(
+                rgba.red() as f64,
+                rgba.green() as f64,
+                rgba.blue() as f64,
+                rgba.alpha() as f64,
+            )),
+            Err(_) => {
+                eprintln!("Ignoring invalid theme color: {}", value);
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub dim: Rgba,
+    pub selection: Rgba,
+    pub selection_width: f64,
+    pub handle_size: f64,
+    pub toolbar_top: Rgba,
+    pub toolbar_bottom: Rgba,
+    pub toolbar_shadow: Rgba,
+    pub toolbar_border: Rgba,
+    pub separator: Rgba,
+    pub text: Rgba,
+    pub font: String,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            dim: Rgba(0.0, 0.0, 0.0, 0.35),
+            selection: Rgba(0.30, 0.60, 1.0, 1.0),
+            selection_width: 2.0,
+            handle_size: 0.0,
+            toolbar_top: Rgba(0.11, 0.11, 0.13, 0.96),
+            toolbar_bottom: Rgba(0.08, 0.08, 0.10, 0.96),
+            toolbar_shadow: Rgba(0.0, 0.0, 0.0, 0.25),
+            toolbar_border: Rgba(0.25, 0.25, 0.28, 0.5),
+            separator: Rgba(0.28, 0.28, 0.32, 0.4),
+            text: Rgba(0.92, 0.92, 0.92, 1.0),
+            font: "Sans".to_string(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            dim: Rgba(1.0, 1.0, 1.0, 0.30),
+            selection: Rgba(0.10, 0.45, 0.90, 1.0),
+            selection_width: 2.0,
+            handle_size: 0.0,
+            toolbar_top: Rgba(0.98, 0.98, 0.99, 0.96),
+            toolbar_bottom: Rgba(0.93, 0.93, 0.95, 0.96),
+            toolbar_shadow: Rgba(0.0, 0.0, 0.0, 0.15),
+            toolbar_border: Rgba(0.70, 0.70, 0.74, 0.6),
+            separator: Rgba(0.65, 0.65, 0.70, 0.5),
+            text: Rgba(0.12, 0.12, 0.14, 1.0),
+            font: "Sans".to_string(),
+        }
+    }
+
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = match config.preset {
+            ThemePreset::Auto | ThemePreset::Dark => Self::dark(),
+            ThemePreset::Light => Self::light(),
+        };
+
+        if let Some(opacity) = config.dim_opacity {
+            theme.dim.3 = opacity.clamp(0.0, 1.0);
+        }
+        if let Some(color) = config.selection_color.as_deref().and_then(Rgba::parse) {
+            theme.selection = color;
+        }
+        if let Some(color) = config.toolbar_background.as_deref().and_then(Rgba::parse) {
+            theme.toolbar_top = color;
+            theme.toolbar_bottom = color;
+        }
+        if let Some(color) = config.toolbar_border.as_deref().and_then(Rgba::parse) {
+            theme.toolbar_border = color;
+        }
+        if let Some(color) = config.toolbar_text.as_deref().and_then(Rgba::parse) {
+            theme.text = color;
+        }
+
+        theme.selection_width = config.selection_width;
+        theme.handle_size = config.handle_size;
+        theme.font = config.font.clone();
+        theme
+    }
+}
+
+// Turns ThemePreset::Auto into the desktop's org.freedesktop.appearance
+// color-scheme and makes GTK's own widgets follow the same choice.
+pub async fn resolve(mut config: Config) -> Config {
+    let theme = &mut config.gui.theme;
+
+    let preset = match theme.preset {
+        ThemePreset::Auto => match portal_color_scheme().await {
+            Some(ColorScheme::PreferLight) => Some(ThemePreset::Light),
+            Some(ColorScheme::PreferDark) => Some(ThemePreset::Dark),
+            _ => None,
+        },
+        preset => Some(preset),
+    };
+
+    // Without a preference GTK keeps its own default look.
+    if let Some(preset) = preset {
+        theme.preset = preset;
+        if let Some(settings) = gtk4::Settings::default() {
+            settings.set_gtk_application_prefer_dark_theme(preset == ThemePreset::Dark);
+        }
+    }
+
+    config
+}
+
+async fn portal_color_scheme() -> Option<ColorScheme> {
+    Settings::new().await.ok()?.color_scheme().await.ok()
+}
@@ -1,3 +1,5 @@
+use super::theme::Theme;
+use crate::animation::{self, Easing, Shape};
 use crate::config::Config;
 use std::f64::consts::PI;
 
@@ -12,12 +14,18 @@ pub struct AnimatedWidget {
     #[allow(dead_code)]
     duration_ms: u64,
     showing_controls: bool,
+    easing: Easing,
+    start_shape: Shape,
+    end_shape: Shape,
+    theme: Theme,
     #[allow(dead_code)]
     config: Config,
 }
 
 impl AnimatedWidget {
     pub fn new(x: f64, y: f64, config: &Config) -> Self {
+        let animation = &config.gui.animation;
+
         Self {
             center_x: x,
             center_y: y,
@@ -26,8 +34,12 @@ impl AnimatedWidget {
             end_width: 280.0,
             end_height: 52.0,
             corner_radius: 26.0,
-            duration_ms: config.gui.animation.duration_ms,
+            duration_ms: animation.duration_ms,
             showing_controls: false,
+            easing: animation::parse_or(&animation.easing, Easing::EaseInOut),
+            start_shape: animation::parse_or(&animation.start_shape, Shape::Circle),
+            end_shape: animation::parse_or(&animation.end_shape, Shape::RoundedRect),
+            theme: Theme::from_config(&config.gui.theme),
             config: config.clone(),
         }
     }
@@ -40,14 +52,6 @@ impl AnimatedWidget {
         self.showing_controls = show;
     }
 
-    fn ease_in_out_cubic(&self, t: f64) -> f64 {
-        if t < 0.5 {
-            4.0 * t * t * t
-        } else {
-            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
-        }
-    }
-
     fn draw_rounded_rect(
         &self,
         cr: &cairo::Context,
@@ -75,7 +79,7 @@ impl AnimatedWidget {
     }
 
     pub fn draw(&self, cr: &cairo::Context, width: f64, _height: f64) {
-        let t = self.ease_in_out_cubic(self.progress);
+        let t = self.easing.apply(self.progress);
 
         let current_width =
             self.start_radius * 2.0 + t * (self.end_width - self.start_radius * 2.0);
@@ -86,30 +90,32 @@ impl AnimatedWidget {
         let x = center_x - current_width / 2.0;
         let y = self.center_y - current_height / 2.0;
 
-        let current_radius = if t < 0.3 {
-            current_width.min(current_height) / 2.0
-        } else {
-            let morph_progress = (t - 0.3) / 0.7;
-            let circle_radius = current_width.min(current_height) / 2.0;
-            circle_radius + morph_progress * (self.corner_radius - circle_radius)
-        };
+        let start_radius =
+            self.start_shape
+                .corner_radius(current_width, current_height, self.corner_radius);
+        let end_radius =
+            self.end_shape
+                .corner_radius(current_width, current_height, self.corner_radius);
+        let morph_progress = ((t - 0.3) / 0.7).clamp(0.0, 1.0);
+        let current_radius = start_radius + morph_progress * (end_radius - start_radius);
 
         cr.save().unwrap();
         cr.translate(0.0, 1.5);
         self.draw_rounded_rect(cr, x, y, current_width, current_height, current_radius);
-        cr.set_source_rgba(0.0, 0.0, 0.0, 0.25);
+        self.theme.toolbar_shadow.set_source(cr);
         cr.fill().unwrap();
         cr.restore().unwrap();
 
         self.draw_rounded_rect(cr, x, y, current_width, current_height, current_radius);
 
         let pattern = cairo::LinearGradient::new(0.0, y, 0.0, y + current_height);
-        pattern.add_color_stop_rgba(0.0, 0.11, 0.11, 0.13, 0.96);
-        pattern.add_color_stop_rgba(1.0, 0.08, 0.08, 0.10, 0.96);
+        let (top, bottom) = (self.theme.toolbar_top, self.theme.toolbar_bottom);
+        pattern.add_color_stop_rgba(0.0, top.0, top.1, top.2, top.3);
+        pattern.add_color_stop_rgba(1.0, bottom.0, bottom.1, bottom.2, bottom.3);
         cr.set_source(&pattern).unwrap();
         cr.fill_preserve().unwrap();
 
-        cr.set_source_rgba(0.25, 0.25, 0.28, 0.5);
+        self.theme.toolbar_border.set_source(cr);
         cr.set_line_width(0.8);
         cr.stroke().unwrap();
 
@@ -119,7 +125,11 @@ impl AnimatedWidget {
     }
 
     fn draw_controls(&self, cr: &cairo::Context, x: f64, y: f64, width: f64, height: f64) {
-        cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+        cr.select_font_face(
+            &self.theme.font,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Normal,
+        );
         cr.set_font_size(11.5);
 
         let controls = [("⏎ Space", "Save"), ("✕ Esc", "Cancel")];
@@ -132,7 +142,7 @@ impl AnimatedWidget {
             let center_x = section_x + section_width / 2.0;
 
             let extents = cr.text_extents(key).unwrap();
-            cr.set_source_rgba(0.92, 0.92, 0.92, 1.0);
+            self.theme.text.set_source(cr);
             cr.move_to(
                 center_x - extents.width() / 2.0,
                 center_y + extents.height() / 2.0 - 1.0,
@@ -140,7 +150,7 @@ impl AnimatedWidget {
             cr.show_text(key).unwrap();
 
             if i < controls.len() - 1 {
-                cr.set_source_rgba(0.28, 0.28, 0.32, 0.4);
+                self.theme.separator.set_source(cr);
                 cr.set_line_width(0.8);
                 let separator_x = section_x + section_width;
                 cr.move_to(separator_x, y + 12.0);
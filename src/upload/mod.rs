@@ -0,0 +1,267 @@
+pub mod webdav;
+
+pub use webdav::WebdavProvider;
+
+use crate::{
+    Result, Screenshot, ScreenshotError, config::expand_template, credentials::Credentials,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub trait Uploader {
+    fn upload(&self, file: &Path, key: &str) -> Result<String>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum UploadProvider {
+    Http {
+        url: String,
+        #[serde(default = "default_field")]
+        field: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        #[serde(default)]
+        response_url: Option<String>,
+    },
+    Put {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        #[serde(default)]
+        public_url: Option<String>,
+    },
+    Webdav(WebdavProvider),
+}
+
+fn default_field() -> String {
+    "file".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadTarget {
+    #[serde(flatten)]
+    pub provider: UploadProvider,
+    #[serde(default)]
+    pub key_template: Option<String>,
+}
+
+impl UploadTarget {
+    pub fn find<'a>(
+        name: Option<&str>,
+        default: Option<&str>,
+        targets: &'a BTreeMap<String, UploadTarget>,
+    ) -> Result<(&'a str, &'a UploadTarget)> {
+        let name = name.filter(|n| !n.is_empty()).or(default);
+
+        match name {
+            Some(name) => targets
+                .get_key_value(name)
+                .map(|(name, target)| (name.as_str(), target))
+                .ok_or_else(|| ScreenshotError::Config(format!("Unknown upload target: {}", name))),
+            None if targets.len() == 1 => Ok(targets
+                .iter()
+                .next()
+                .map(|(name, target)| (name.as_str(), target))
+                .unwrap()),
+            None => Err(ScreenshotError::Config(
+                "No upload target given; set default_upload or pass a target name".to_string(),
+            )),
+        }
+    }
+
+    // The key template shares the filename template's strftime and {token}
+    // syntax, but is expanded against the capture time rather than now.
+    pub fn resolve_key(&self, file: &Path, screenshot: &Screenshot) -> Result<String> {
+        let template = self.key_template.as_deref().unwrap_or("{filename}");
+
+        let filename = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stem = file
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let ext = file
+            .extension()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let hash = if template.contains("{hash}") {
+            format!("{:08x}", crc32fast::hash(&std::fs::read(file)?))
+        } else {
+            String::new()
+        };
+        let mode = format!("{:?}", screenshot.metadata.mode).to_lowercase();
+        let width = screenshot.width().to_string();
+        let height = screenshot.height().to_string();
+
+        let key = expand_template(
+            template,
+            screenshot.metadata.timestamp,
+            &[
+                ("filename", &filename),
+                ("stem", &stem),
+                ("ext", &ext),
+                ("hash", &hash),
+                ("mode", &mode),
+                ("width", &width),
+                ("height", &height),
+            ],
+        );
+
+        Ok(key.trim_start_matches('/').to_string())
+    }
+
+    pub fn upload(&self, name: &str, file: &Path, screenshot: &Screenshot) -> Result<String> {
+        let key = self.resolve_key(file, screenshot)?;
+
+        match Credentials::lookup(name) {
+            Some(secret) => self.provider.with_secret(&secret).upload(file, &key),
+            None => self.provider.upload(file, &key),
+        }
+    }
+}
+
+impl UploadProvider {
+    // Header values may reference the stored secret as {secret}; WebDAV
+    // falls back to it when no password is configured.
+    pub fn with_secret(&self, secret: &str) -> Self {
+        let fill = |headers: &BTreeMap<String, String>| {
+            headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.replace("{secret}", secret)))
+                .collect()
+        };
+
+        match self {
+            Self::Http {
+                url,
+                field,
+                headers,
+                response_url,
+            } => Self::Http {
+                url: url.clone(),
+                field: field.clone(),
+                headers: fill(headers),
+                response_url: response_url.clone(),
+            },
+            Self::Put {
+                url,
+                headers,
+                public_url,
+            } => Self::Put {
+                url: url.clone(),
+                headers: fill(headers),
+                public_url: public_url.clone(),
+            },
+            Self::Webdav(provider) => {
+                let mut provider = provider.clone();
+                provider.password.get_or_insert_with(|| secret.to_string());
+                Self::Webdav(provider)
+            }
+        }
+    }
+}
+
+impl Uploader for UploadProvider {
+    fn upload(&self, file: &Path, key: &str) -> Result<String> {
+        match self {
+            Self::Http {
+                url,
+                field,
+                headers,
+                response_url,
+            } => {
+                let mut cmd = curl();
+                cmd.arg("-F")
+                    .arg(format!("{}=@{};filename={}", field, file.display(), key))
+                    .arg(url);
+
+                let response = run(cmd, &header_config(headers))?;
+                match response_url {
+                    Some(pointer) => serde_json::from_str::<Value>(&response)?
+                        .pointer(pointer)
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                        .ok_or_else(|| {
+                            ScreenshotError::RemoteFailed(format!(
+                                "Upload response has no URL at {}",
+                                pointer
+                            ))
+                        }),
+                    None => Ok(response.trim().to_string()),
+                }
+            }
+            Self::Put {
+                url,
+                headers,
+                public_url,
+            } => {
+                let target = join_url(url, key);
+                let mut cmd = curl();
+                cmd.arg("-T").arg(file).arg(&target);
+                run(cmd, &header_config(headers))?;
+
+                Ok(match public_url {
+                    Some(public_url) => join_url(public_url, key),
+                    None => target,
+                })
+            }
+            Self::Webdav(provider) => provider.upload(file, key),
+        }
+    }
+}
+
+// Headers and credentials go through curl's config on stdin so secrets never
+// show up in the process list.
+fn curl() -> Command {
+    let mut cmd = Command::new("curl");
+    cmd.args(["--silent", "--show-error", "--fail", "--config", "-"]);
+    cmd
+}
+
+fn header_config(headers: &BTreeMap<String, String>) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("header = \"{}: {}\"\n", escape(name), escape(value)))
+        .collect()
+}
+
+fn run(mut cmd: Command, config: &str) -> Result<String> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| ScreenshotError::ToolUnavailable {
+            tool: "curl".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ScreenshotError::RemoteFailed(format!(
+            "Upload failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn join_url(base: &str, key: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), key)
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
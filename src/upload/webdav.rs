@@ -0,0 +1,104 @@
+use super::{curl, escape, join_url, run};
+use crate::{Result, ScreenshotError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebdavProvider {
+    pub url: String,
+    pub username: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub share: bool,
+    #[serde(default)]
+    pub public_url: Option<String>,
+}
+
+impl WebdavProvider {
+    pub fn upload(&self, file: &Path, key: &str) -> Result<String> {
+        let directories: Vec<&str> = key.split('/').collect();
+        for depth in 1..directories.len() {
+            let collection = join_url(&self.url, &directories[..depth].join("/"));
+            // MKCOL fails on collections that already exist; only the PUT matters.
+            let mut cmd = curl();
+            cmd.args(["-X", "MKCOL"]).arg(format!("{}/", collection));
+            let _ = self.run(cmd);
+        }
+
+        let target = join_url(&self.url, key);
+        let mut cmd = curl();
+        cmd.arg("-T").arg(file).arg(&target);
+        self.run(cmd)?;
+
+        if self.share {
+            return self.create_share_link(key);
+        }
+
+        Ok(match &self.public_url {
+            Some(public_url) => join_url(public_url, key),
+            None => target,
+        })
+    }
+
+    // Nextcloud's DAV root is <server>/remote.php/dav/files/<user> (or the
+    // legacy /remote.php/webdav); shares are created against the path below it.
+    fn create_share_link(&self, key: &str) -> Result<String> {
+        let not_nextcloud = || {
+            ScreenshotError::Config(format!(
+                "{} is not a Nextcloud WebDAV URL; cannot create a share link",
+                self.url
+            ))
+        };
+
+        let (server, dav_path) = self
+            .url
+            .split_once("/remote.php/")
+            .ok_or_else(not_nextcloud)?;
+        let folder = dav_path
+            .strip_prefix("webdav")
+            .or_else(|| {
+                dav_path
+                    .strip_prefix("dav/files/")
+                    .map(|rest| rest.split_once('/').map_or("", |(_, folder)| folder))
+            })
+            .ok_or_else(not_nextcloud)?
+            .trim_matches('/');
+
+        let path = if folder.is_empty() {
+            format!("/{}", key)
+        } else {
+            format!("/{}/{}", folder, key)
+        };
+
+        let mut cmd = curl();
+        cmd.args(["-H", "OCS-APIRequest: true"])
+            .arg("--data-urlencode")
+            .arg(format!("path={}", path))
+            .args(["-d", "shareType=3"])
+            .arg(format!(
+                "{}/ocs/v2.php/apps/files_sharing/api/v1/shares?format=json",
+                server
+            ));
+
+        let response: Value = serde_json::from_str(&self.run(cmd)?)?;
+        response
+            .pointer("/ocs/data/url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| ScreenshotError::RemoteFailed("Share response has no URL".to_string()))
+    }
+
+    fn run(&self, cmd: Command) -> Result<String> {
+        let password = self.password.as_deref().unwrap_or_default();
+        let config = format!(
+            "user = \"{}:{}\"\n",
+            escape(&self.username),
+            escape(password)
+        );
+
+        run(cmd, &config)
+    }
+}
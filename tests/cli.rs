@@ -0,0 +1,429 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+
+const WINDOWS: &str = r#"[
+    {"title": "Terminal", "app_id": "foot", "x": 10, "y": 20, "width": 120, "height": 80},
+    {"title": "Browser", "app_id": "firefox", "x": 160, "y": 0, "width": 160, "height": 200}
+]"#;
+
+fn wst(home: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("wst").unwrap();
+    cmd.current_dir(home.path())
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("XDG_STATE_HOME", home.path().join("state"))
+        .env("XDG_DATA_HOME", home.path().join("data"))
+        .env("XDG_CACHE_HOME", home.path().join("cache"))
+        .env("JSWST_BACKEND", "mock")
+        .env("JSWST_MOCK_SIZE", "320x200")
+        .env("JSWST_MOCK_WINDOWS", WINDOWS);
+    cmd
+}
+
+fn dimensions(path: &Path) -> (u32, u32) {
+    image::image_dimensions(path).unwrap()
+}
+
+fn assert_snapshot(name: &str, actual: &[u8]) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    let actual = String::from_utf8_lossy(actual);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual.as_bytes()).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "snapshot {} changed", name);
+}
+
+#[test]
+fn screen_writes_the_full_frame() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("screen.png");
+
+    wst(&home)
+        .args(["-x", "screen"])
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(output.to_string_lossy()));
+
+    assert_eq!(dimensions(&output), (320, 200));
+}
+
+#[test]
+fn region_crops_the_frame() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("region.png");
+
+    wst(&home)
+        .args(["-x", "region", "--region", "10,20,100,50"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (100, 50));
+}
+
+#[test]
+fn region_accepts_percentages() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("half.png");
+
+    wst(&home)
+        .args(["-x", "region", "--region", "0,0,50%,50%"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (160, 100));
+}
+
+#[test]
+fn region_out_of_bounds_fails() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("missing.png");
+
+    wst(&home)
+        .args(["-x", "region", "--region", "300,180,100,100"])
+        .arg(&output)
+        .assert()
+        .code(6);
+
+    assert!(!output.exists());
+}
+
+#[test]
+fn repeated_regions_write_one_file_each() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("regions");
+    let file = home.path().join("regions.json");
+    std::fs::write(&file, r#"[{"x": 0, "y": 0, "width": 40, "height": 30}]"#).unwrap();
+
+    wst(&home)
+        .args([
+            "-x",
+            "region",
+            "--region",
+            "10,20,100,50",
+            "--region",
+            "0,0,50%,50%",
+        ])
+        .arg("--regions")
+        .arg(&file)
+        .arg(&output)
+        .assert()
+        .success();
+
+    let mut sizes: Vec<_> = std::fs::read_dir(&output)
+        .unwrap()
+        .map(|entry| dimensions(&entry.unwrap().path()))
+        .collect();
+    sizes.sort();
+    assert_eq!(sizes, vec![(40, 30), (100, 50), (160, 100)]);
+}
+
+#[test]
+fn privacy_rules_black_out_matching_windows() {
+    let home = TempDir::new().unwrap();
+    let config = home
+        .path()
+        .join("config/just-a-simple-wayland-screenshot-tool");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("config.toml"),
+        "[privacy]\nmask_apps = [\"foot\"]\n",
+    )
+    .unwrap();
+    let output = home.path().join("private.png");
+
+    wst(&home)
+        .env("JSWST_MOCK_CONTENT", "solid:#ffffff")
+        .args(["-x", "screen"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let image = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(image.get_pixel(50, 50).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(200, 50).0, [255, 255, 255, 255]);
+}
+
+#[test]
+fn all_windows_are_masked_and_recorded() {
+    let home = TempDir::new().unwrap();
+    let config = home
+        .path()
+        .join("config/just-a-simple-wayland-screenshot-tool");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("config.toml"),
+        "[privacy]\nmask_apps = [\"foot\"]\n",
+    )
+    .unwrap();
+    let out_dir = home.path().join("windows");
+
+    wst(&home)
+        .env("JSWST_MOCK_CONTENT", "solid:#ffffff")
+        .args(["-x", "--all-windows", "--out-dir"])
+        .arg(&out_dir)
+        .assert()
+        .success();
+
+    let terminal = image::open(out_dir.join("foot-Terminal.png"))
+        .unwrap()
+        .to_rgba8();
+    let browser = image::open(out_dir.join("firefox-Browser.png"))
+        .unwrap()
+        .to_rgba8();
+    assert_eq!(terminal.get_pixel(60, 40).0, [0, 0, 0, 255]);
+    assert_eq!(browser.get_pixel(60, 40).0, [255, 255, 255, 255]);
+
+    let history = std::fs::read_to_string(
+        home.path()
+            .join("state/just-a-simple-wayland-screenshot-tool/history.jsonl"),
+    )
+    .unwrap();
+    assert_eq!(history.lines().count(), 2);
+}
+
+#[test]
+fn auto_redact_blurs_matching_words() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempDir::new().unwrap();
+    let ocr = home.path().join("fake-ocr");
+    std::fs::write(
+        &ocr,
+        "#!/bin/sh\ncat >/dev/null\nprintf 'level\\n5\\t1\\t1\\t1\\t1\\t1\\t20\\t20\\t80\\t16\\t96\\tme@example.com\\n'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&ocr, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let config = home
+        .path()
+        .join("config/just-a-simple-wayland-screenshot-tool");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(
+        config.join("config.toml"),
+        format!("[redact]\nocr_command = \"{}\"\n", ocr.display()),
+    )
+    .unwrap();
+
+    let plain = home.path().join("plain.png");
+    let redacted = home.path().join("redacted.png");
+    for (output, extra) in [(&plain, None), (&redacted, Some("--auto-redact"))] {
+        wst(&home)
+            .env("JSWST_MOCK_CONTENT", "checkerboard:4")
+            .args(["-x", "screen"])
+            .args(extra)
+            .arg(output)
+            .assert()
+            .success();
+    }
+
+    let plain = image::open(&plain).unwrap().to_rgba8();
+    let redacted = image::open(&redacted).unwrap().to_rgba8();
+    assert_ne!(plain.get_pixel(50, 28), redacted.get_pixel(50, 28));
+    assert_eq!(plain.get_pixel(200, 150), redacted.get_pixel(200, 150));
+}
+
+#[test]
+fn scan_reports_barcodes_from_zbarimg() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempDir::new().unwrap();
+    let bin = home.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let zbarimg = bin.join("zbarimg");
+    std::fs::write(&zbarimg, "#!/bin/sh\necho 4006381333931\n").unwrap();
+    std::fs::set_permissions(&zbarimg, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let input = home.path().join("code.png");
+    image::RgbaImage::from_pixel(64, 32, image::Rgba([255, 255, 255, 255]))
+        .save(&input)
+        .unwrap();
+
+    let path = std::env::join_paths(std::iter::once(bin).chain(std::env::split_paths(
+        &std::env::var_os("PATH").unwrap_or_default(),
+    )))
+    .unwrap();
+    wst(&home)
+        .env("PATH", path)
+        .arg("scan")
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout("4006381333931\n");
+}
+
+#[test]
+fn active_window_uses_the_focused_window() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("window.png");
+
+    wst(&home)
+        .args(["-x", "active-window"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (120, 80));
+}
+
+#[test]
+fn json_reports_metadata() {
+    let home = TempDir::new().unwrap();
+    let output = home.path().join("meta.png");
+
+    let assert = wst(&home)
+        .args(["-x", "screen", "--json"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    let json = &stdout[stdout.find('{').expect("no JSON in output")..];
+    let metadata: serde_json::Value = serde_json::from_str(json).unwrap();
+
+    assert_eq!(metadata["width"], 320);
+    assert_eq!(metadata["height"], 200);
+    assert_eq!(metadata["mode"], "screen");
+}
+
+#[test]
+fn unsupported_format_is_rejected() {
+    let home = TempDir::new().unwrap();
+
+    wst(&home)
+        .args(["-x", "screen", "-f", "gif", "shot.gif"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("Unsupported format: gif"));
+}
+
+#[test]
+fn unknown_mode_is_a_usage_error() {
+    let home = TempDir::new().unwrap();
+
+    wst(&home).args(["-x", "everything"]).assert().code(2);
+}
+
+#[test]
+fn process_applies_a_border() {
+    let home = TempDir::new().unwrap();
+    let input = home.path().join("input.png");
+    let output = home.path().join("output.png");
+    image::RgbaImage::new(64, 32).save(&input).unwrap();
+
+    wst(&home)
+        .arg("process")
+        .arg(&input)
+        .arg(&output)
+        .args(["--border", "4"])
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (72, 40));
+}
+
+#[test]
+fn process_frames_in_a_phone() {
+    let home = TempDir::new().unwrap();
+    let input = home.path().join("input.png");
+    let output = home.path().join("output.png");
+    image::RgbaImage::new(64, 32).save(&input).unwrap();
+
+    wst(&home)
+        .arg("process")
+        .arg(&input)
+        .arg(&output)
+        .args(["--frame", "phone"])
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (88, 56));
+}
+
+#[test]
+fn process_adds_window_chrome() {
+    let home = TempDir::new().unwrap();
+    let input = home.path().join("input.png");
+    let output = home.path().join("output.png");
+    image::RgbaImage::new(64, 32).save(&input).unwrap();
+
+    wst(&home)
+        .arg("process")
+        .arg(&input)
+        .arg(&output)
+        .args(["--chrome", "gnome"])
+        .assert()
+        .success();
+
+    assert_eq!(dimensions(&output), (64, 78));
+}
+
+#[test]
+fn process_missing_input_fails() {
+    let home = TempDir::new().unwrap();
+
+    wst(&home)
+        .arg("process")
+        .arg(home.path().join("absent.png"))
+        .arg(home.path().join("output.png"))
+        .assert()
+        .failure();
+}
+
+#[test]
+fn list_displays() {
+    let home = TempDir::new().unwrap();
+    let assert = wst(&home).args(["list", "displays"]).assert().success();
+
+    assert_snapshot("list_displays", &assert.get_output().stdout);
+}
+
+#[test]
+fn list_windows() {
+    let home = TempDir::new().unwrap();
+    let assert = wst(&home).args(["list", "windows"]).assert().success();
+
+    assert_snapshot("list_windows", &assert.get_output().stdout);
+}
+
+#[test]
+fn list_capabilities() {
+    let home = TempDir::new().unwrap();
+    let assert = wst(&home).args(["list", "capabilities"]).assert().success();
+
+    assert_snapshot("list_capabilities", &assert.get_output().stdout);
+}
+
+#[test]
+fn config_show_round_trips() {
+    let home = TempDir::new().unwrap();
+    let assert = wst(&home).args(["config", "--show"]).assert().success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    let config: toml::Value = toml::from_str(&stdout).unwrap();
+    assert!(config.get("capture").is_some());
+}
+
+#[test]
+fn config_reset_writes_the_file() {
+    let home = TempDir::new().unwrap();
+
+    wst(&home).args(["config", "--reset"]).assert().success();
+
+    let path = home
+        .path()
+        .join("config/just-a-simple-wayland-screenshot-tool/config.toml");
+    assert!(path.is_file());
+}